@@ -16,21 +16,26 @@ fn main() {
     let config = FinancialHistoryConfig {
         organization_name: "Debug".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![BalanceSheetAccount {
             name: "Cash".to_string(),
             category: None,
             account_type: AccountType::Asset,
             method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
             snapshots: vec![
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             is_balancing_account: true,
@@ -45,16 +50,19 @@ fn main() {
                     period: period_range(2023, 1, 2023, 1),
                     value: 10_000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: period_range(2023, 2, 2023, 2),
                     value: 0.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: period_range(2023, 1, 2023, 3),
                     value: 25_000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             noise_factor: 0.0,
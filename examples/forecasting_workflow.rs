@@ -1,8 +1,9 @@
 use dotenv::dotenv;
 use financial_history_builder::llm::{FinancialExtractor, ForecastingSetupAgent};
-use financial_history_builder::{process_financial_history, AccountType, DenseSeries};
+use financial_history_builder::{
+    process_financial_history, write_wide_csv, ApplyMode, ExportOptions, ExportScope,
+};
 use gemini_structured_output::prelude::{Model, StructuredClientBuilder};
-use std::collections::{BTreeMap, BTreeSet};
 use std::error::Error;
 use std::path::Path;
 use tokio::fs;
@@ -151,7 +152,11 @@ async fn main() -> Result<(), Box<dyn Error>> {
     println!("⚡ PHASE 3: Applying Overrides");
     println!("═══════════════════════════════════════════════════════════════\n");
 
-    let final_config = overrides.apply(&raw_config);
+    let report = overrides.try_apply(&raw_config, ApplyMode::Lenient)?;
+    for (i, outcome) in report.failures() {
+        println!("   ⚠️  Skipped modification {}: {}", i + 1, outcome);
+    }
+    let final_config = report.config;
 
     println!("   ✅ Overrides Applied:");
     println!(
@@ -239,15 +244,14 @@ async fn main() -> Result<(), Box<dyn Error>> {
         })
         .unwrap_or("forecasting_output");
 
-    let pl_accounts = collect_income_accounts(&final_config);
-    let bs_accounts = collect_balance_accounts(&final_config);
-
     let pl_filename = format!("{}_pl.csv", base_name);
-    export_to_csv_transposed(&pl_accounts, &dense_data, &pl_filename).await?;
+    let pl_options = ExportOptions::new().with_scope(ExportScope::IncomeStatement);
+    write_wide_csv(&pl_filename, &final_config, &dense_data, &pl_options)?;
     println!("   ✅ Saved P&L CSV: {}", pl_filename);
 
     let bs_filename = format!("{}_balance_sheet.csv", base_name);
-    export_to_csv_transposed(&bs_accounts, &dense_data, &bs_filename).await?;
+    let bs_options = ExportOptions::new().with_scope(ExportScope::BalanceSheet);
+    write_wide_csv(&bs_filename, &final_config, &dense_data, &bs_options)?;
     println!("   ✅ Saved Balance Sheet CSV: {}", bs_filename);
 
     println!();
@@ -257,66 +261,3 @@ async fn main() -> Result<(), Box<dyn Error>> {
 
     Ok(())
 }
-
-async fn export_to_csv_transposed(
-    accounts: &[(String, AccountType)],
-    dense_data: &BTreeMap<String, DenseSeries>,
-    filename: &str,
-) -> Result<(), Box<dyn Error>> {
-    let mut dates = BTreeSet::new();
-    for (name, _) in accounts {
-        if let Some(series) = dense_data.get(name) {
-            for d in series.keys() {
-                dates.insert(*d);
-            }
-        }
-    }
-
-    if dates.is_empty() {
-        return Ok(());
-    }
-
-    let mut csv_out = String::new();
-
-    csv_out.push_str("Account");
-    for date in &dates {
-        csv_out.push_str(&format!(",{}", date));
-    }
-    csv_out.push('\n');
-
-    for (name, _) in accounts {
-        csv_out.push_str(name);
-        if let Some(series) = dense_data.get(name) {
-            for date in &dates {
-                let val = series.get(date).map(|p| p.value).unwrap_or(0.0);
-                csv_out.push_str(&format!(",{:.2}", val));
-            }
-        } else {
-            for _ in &dates {
-                csv_out.push_str(",0.00");
-            }
-        }
-        csv_out.push('\n');
-    }
-
-    fs::write(filename, csv_out).await?;
-    Ok(())
-}
-
-fn collect_income_accounts(
-    cfg: &financial_history_builder::FinancialHistoryConfig,
-) -> Vec<(String, AccountType)> {
-    cfg.income_statement
-        .iter()
-        .map(|a| (a.name.clone(), a.account_type.clone()))
-        .collect()
-}
-
-fn collect_balance_accounts(
-    cfg: &financial_history_builder::FinancialHistoryConfig,
-) -> Vec<(String, AccountType)> {
-    cfg.balance_sheet
-        .iter()
-        .map(|a| (a.name.clone(), a.account_type.clone()))
-        .collect()
-}
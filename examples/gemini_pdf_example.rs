@@ -1,8 +1,8 @@
 use dotenv::dotenv;
 use financial_history_builder::llm::{ExtractionEvent, FinancialExtractor};
 use financial_history_builder::{
-    process_financial_history, verify_accounting_equation, AccountType, DenseSeries,
-    FinancialHistoryConfig,
+    process_financial_history, verify_accounting_equation, write_wide_csv, AccountType,
+    DenseSeries, ExportOptions, ExportScope, FinancialHistoryConfig,
 };
 use futures::future;
 use gemini_structured_output::prelude::{Model, StructuredClientBuilder};
@@ -310,62 +310,31 @@ async fn main() -> Result<(), Box<dyn Error>> {
         .and_then(|p| p.file_stem().and_then(|s| s.to_str()))
         .unwrap_or("financial_history");
 
-    let pl_accounts = collect_income_accounts(&config);
-    let bs_accounts = collect_balance_accounts(&config);
-
     let pl_filename = format!("{}_pl.csv", base_name);
-    export_to_csv_transposed(&pl_accounts, &dense_data, &pl_filename).await?;
+    let pl_options = ExportOptions::new().with_scope(ExportScope::IncomeStatement);
+    write_wide_csv(&pl_filename, &config, &dense_data, &pl_options)?;
     println!("💾 Saved P/L to: {}", pl_filename);
 
     let bs_filename = format!("{}_balance_sheet.csv", base_name);
-    export_to_csv_transposed(&bs_accounts, &dense_data, &bs_filename).await?;
+    let bs_options = ExportOptions::new().with_scope(ExportScope::BalanceSheet);
+    write_wide_csv(&bs_filename, &config, &dense_data, &bs_options)?;
     println!("💾 Saved Balance Sheet to: {}", bs_filename);
 
-    Ok(())
-}
-
-async fn export_to_csv_transposed(
-    accounts: &[(String, AccountType)],
-    dense_data: &BTreeMap<String, DenseSeries>,
-    filename: &str,
-) -> Result<(), Box<dyn Error>> {
-    let mut dates = BTreeSet::new();
-    for (name, _) in accounts {
-        if let Some(series) = dense_data.get(name) {
-            for d in series.keys() {
-                dates.insert(*d);
-            }
-        }
-    }
-
-    if dates.is_empty() {
-        return Ok(());
-    }
-
-    let mut csv_out = String::new();
-
-    csv_out.push_str("Account");
-    for date in &dates {
-        csv_out.push_str(&format!(",{}", date));
-    }
-    csv_out.push('\n');
-
-    for (name, _) in accounts {
-        csv_out.push_str(name);
-        if let Some(series) = dense_data.get(name) {
-            for date in &dates {
-                let val = series.get(date).map(|p| p.value).unwrap_or(0.0);
-                csv_out.push_str(&format!(",{:.2}", val));
-            }
-        } else {
-            for _ in &dates {
-                csv_out.push_str(",0.00");
-            }
-        }
-        csv_out.push('\n');
+    #[cfg(feature = "xlsx")]
+    {
+        let xlsx_filename = format!("{}.xlsx", base_name);
+        let xlsx_options = ExportOptions::new()
+            .with_subtotals(true)
+            .with_thousands_separator(',');
+        financial_history_builder::write_workbook(
+            &xlsx_filename,
+            &config,
+            &dense_data,
+            &xlsx_options,
+        )?;
+        println!("💾 Saved workbook to: {}", xlsx_filename);
     }
 
-    fs::write(filename, csv_out).await?;
     Ok(())
 }
 
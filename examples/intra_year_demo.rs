@@ -22,21 +22,26 @@ fn main() {
     let config = FinancialHistoryConfig {
         organization_name: "Demo Corp".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![BalanceSheetAccount {
             name: "Cash".to_string(),
             category: None,
             account_type: AccountType::Asset,
             method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
             snapshots: vec![
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             is_balancing_account: true,
@@ -51,11 +56,13 @@ fn main() {
                     period: period_range(2023, 1, 2023, 6),
                     value: 300000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: period_range(2023, 1, 2023, 12),
                     value: 600000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             noise_factor: 0.0,
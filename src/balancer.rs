@@ -1,27 +1,198 @@
 use crate::error::{FinancialHistoryError, Result};
-use crate::schema::{AccountType, FinancialHistoryConfig};
+use crate::options::{BalancingStrategy, PlugSelection, RetainedEarningsMode};
+use crate::schema::{AccountType, BalanceSheetAccount, FinancialHistoryConfig};
+use crate::utils::get_fiscal_year_end_for_date;
 use crate::{DataOrigin, DenseSeries, DerivationDetails, MonthlyDataPoint};
-use chrono::NaiveDate;
+use chrono::{Datelike, NaiveDate};
+use serde::{Deserialize, Serialize};
 use std::collections::{BTreeMap, HashSet};
 
+/// Default equity line created by the `Suspense` and `BetweenAnchors` strategies.
+pub const SUSPENSE_ACCOUNT_NAME: &str = "Unreconciled Difference";
+
+/// Plug values this close to a snapshot are not reported as conflicts.
+const ANCHOR_CONFLICT_TOLERANCE: f64 = 0.01;
+
 pub struct AccountingBalancer<'a> {
     config: &'a FinancialHistoryConfig,
+    strategy: BalancingStrategy,
+    plug_selection: PlugSelection,
+    re_mode: RetainedEarningsMode,
+    re_tolerance: f64,
+    suspense_account: String,
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct VerificationResult {
     pub warnings: Vec<String>,
+    /// Every month where balancing disagreed with a document snapshot.
+    pub anchor_conflicts: Vec<AnchorConflict>,
+}
+
+/// Every check for every month, ready to render or serialize.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationReport {
+    pub tolerance: f64,
+    pub retained_earnings_tolerance: f64,
+    pub rows: Vec<VerificationRow>,
+}
+
+impl VerificationReport {
+    pub fn passed(&self) -> bool {
+        self.rows.iter().all(|row| row.passed())
+    }
+
+    /// Rows with at least one failing check.
+    pub fn failures(&self) -> impl Iterator<Item = &VerificationRow> {
+        self.rows.iter().filter(|row| !row.passed())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerificationRow {
+    pub date: NaiveDate,
+    pub assets: f64,
+    pub liabilities: f64,
+    pub equity: f64,
+    /// `assets - liabilities - equity`
+    pub difference: f64,
+    /// Sum of the absolute values of every balancing-plug point on this date.
+    pub plug_magnitude: f64,
+    /// Retained earnings movement less (net income - dividends). `None` for the first month
+    /// or when there is no Retained Earnings series.
+    pub retained_earnings_delta: Option<f64>,
+    pub checks: Vec<CheckResult>,
+}
+
+impl VerificationRow {
+    pub fn passed(&self) -> bool {
+        self.checks.iter().all(|c| c.passed)
+    }
+
+    fn balanced(&self) -> bool {
+        self.checks
+            .iter()
+            .filter(|c| c.check == VerificationCheck::AccountingEquation)
+            .all(|c| c.passed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VerificationCheck {
+    AccountingEquation,
+    RetainedEarningsRollforward,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckResult {
+    pub check: VerificationCheck,
+    pub passed: bool,
+}
+
+/// A month where the balancing figure differs from a document snapshot on the same account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AnchorConflict {
+    pub account: String,
+    pub date: NaiveDate,
+    pub document_value: f64,
+    /// The value the balancing strategy needed for this account.
+    pub required_value: f64,
+    /// `document_value - required_value`
+    pub difference: f64,
+    /// The suspense account holding the difference when the snapshot was kept;
+    /// `None` means the snapshot was overwritten.
+    pub routed_to: Option<String>,
 }
 
 impl<'a> AccountingBalancer<'a> {
     pub fn new(config: &'a FinancialHistoryConfig) -> Self {
-        Self { config }
+        Self {
+            config,
+            strategy: BalancingStrategy::SinglePlug,
+            plug_selection: PlugSelection::Heuristic,
+            re_mode: RetainedEarningsMode::Check,
+            re_tolerance: 1.0,
+            suspense_account: SUSPENSE_ACCOUNT_NAME.to_string(),
+        }
+    }
+
+    pub fn with_strategy(mut self, strategy: BalancingStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Names the equity line used by the `Suspense` and `BetweenAnchors` strategies.
+    pub fn with_suspense_account(mut self, name: impl Into<String>) -> Self {
+        self.suspense_account = name.into();
+        self
+    }
+
+    pub fn with_plug_selection(mut self, selection: PlugSelection) -> Self {
+        self.plug_selection = selection;
+        self
+    }
+
+    pub fn with_retained_earnings_mode(mut self, mode: RetainedEarningsMode) -> Self {
+        self.re_mode = mode;
+        self
+    }
+
+    /// Allowed gap between a Retained Earnings movement and net income before a warning is raised.
+    pub fn with_retained_earnings_tolerance(mut self, tolerance: f64) -> Self {
+        self.re_tolerance = tolerance;
+        self
     }
 
     pub fn enforce_accounting_equation(
         &self,
         dense_data: &mut BTreeMap<String, DenseSeries>,
     ) -> Result<VerificationResult> {
+        let mut warnings = Vec::new();
+        let mut anchor_conflicts = Vec::new();
+        if self.re_mode == RetainedEarningsMode::Derive {
+            warnings.extend(self.derive_retained_earnings(dense_data));
+        }
+
+        match self.strategy {
+            BalancingStrategy::SinglePlug => {
+                self.plug_single_account(dense_data, false, &mut anchor_conflicts)?
+            }
+            BalancingStrategy::BetweenAnchors => {
+                self.plug_single_account(dense_data, true, &mut anchor_conflicts)?
+            }
+            BalancingStrategy::ProRata => {
+                if let Some(warning) = self.plug_pro_rata(dense_data, &mut anchor_conflicts)? {
+                    warnings.push(warning);
+                }
+            }
+            BalancingStrategy::Suspense => self.plug_suspense(dense_data),
+        }
+
+        for conflict in anchor_conflicts.iter().filter(|c| c.routed_to.is_none()) {
+            warnings.push(format!(
+                "Balancing overwrote the {} snapshot on {}: document {:.2}, required {:.2}",
+                conflict.account, conflict.date, conflict.document_value, conflict.required_value
+            ));
+        }
+
+        if self.re_mode == RetainedEarningsMode::Check {
+            warnings.extend(self.check_retained_earnings_rollforward(dense_data));
+        }
+
+        Ok(VerificationResult {
+            warnings,
+            anchor_conflicts,
+        })
+    }
+
+    /// Overwrites the plug account with the balancing figure. With `keep_anchors`, months where
+    /// the plug has a document snapshot keep it and the remainder goes to the suspense account.
+    fn plug_single_account(
+        &self,
+        dense_data: &mut BTreeMap<String, DenseSeries>,
+        keep_anchors: bool,
+        conflicts: &mut Vec<AnchorConflict>,
+    ) -> Result<()> {
         let plug_account_name = self.find_or_create_plug_account(dense_data)?;
         let plug_type = self.get_account_type(&plug_account_name);
 
@@ -36,68 +207,320 @@ impl<'a> AccountingBalancer<'a> {
                 _ => assets - liabilities - equity,
             };
 
+            let anchor = dense_data
+                .get(&plug_account_name)
+                .and_then(|series| series.get(&date))
+                .filter(|point| keep_anchors && point.origin == DataOrigin::Anchor)
+                .map(|point| point.value);
+
+            if let Some(anchor_value) = anchor {
+                let difference = match plug_type {
+                    AccountType::Asset => anchor_value - required_plug,
+                    _ => required_plug - anchor_value,
+                };
+                if (anchor_value - required_plug).abs() > ANCHOR_CONFLICT_TOLERANCE {
+                    conflicts.push(AnchorConflict {
+                        account: plug_account_name.clone(),
+                        date,
+                        document_value: anchor_value,
+                        required_value: required_plug,
+                        difference: anchor_value - required_plug,
+                        routed_to: Some(self.suspense_account.clone()),
+                    });
+                }
+                dense_data
+                    .entry(self.suspense_account.clone())
+                    .or_default()
+                    .insert(
+                        date,
+                        self.plug_point(
+                            difference,
+                            format!(
+                                "Difference held in suspense so {} keeps its document value ({:.2})",
+                                plug_account_name, anchor_value
+                            ),
+                        ),
+                    );
+                continue;
+            }
+
+            self.write_plug(
+                dense_data,
+                &plug_account_name,
+                date,
+                self.plug_point(
+                    required_plug,
+                    format!(
+                        "System generated plug to enforce Assets ({:.2}) = Liab ({:.2}) + Equity ({:.2})",
+                        assets, liabilities, equity
+                    ),
+                ),
+                conflicts,
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Adjusts every flagged balancing account by a share of the imbalance proportional to its
+    /// absolute balance (equal shares when they are all zero). Falls back to a single plug when
+    /// nothing is flagged, returning a warning.
+    fn plug_pro_rata(
+        &self,
+        dense_data: &mut BTreeMap<String, DenseSeries>,
+        conflicts: &mut Vec<AnchorConflict>,
+    ) -> Result<Option<String>> {
+        let flagged: Vec<&BalanceSheetAccount> = self
+            .config
+            .balance_sheet
+            .iter()
+            .filter(|a| a.is_balancing_account && !self.is_derived(&a.name))
+            .collect();
+
+        if flagged.is_empty() {
+            self.plug_single_account(dense_data, false, conflicts)?;
+            return Ok(Some(
+                "Pro-rata balancing requested but no account is flagged is_balancing_account; \
+                 used a single plug instead"
+                    .to_string(),
+            ));
+        }
+
+        let all_dates = self.collect_all_dates(dense_data);
+
+        for date in all_dates {
+            let (assets, liabilities, equity) = self.calculate_balances(dense_data, "", date);
+            let imbalance = assets - liabilities - equity;
+
+            let balances: Vec<f64> = flagged
+                .iter()
+                .map(|a| {
+                    dense_data
+                        .get(&a.name)
+                        .and_then(|series| series.get(&date))
+                        .map(|p| p.value)
+                        .unwrap_or(0.0)
+                })
+                .collect();
+            let total: f64 = balances.iter().map(|b| b.abs()).sum();
+
+            for (account, balance) in flagged.iter().zip(&balances) {
+                let share = if total > 0.0 {
+                    balance.abs() / total
+                } else {
+                    1.0 / flagged.len() as f64
+                };
+                // Assets absorb an excess by shrinking, liabilities and equity by growing
+                let adjustment = match account.account_type {
+                    AccountType::Asset => -share * imbalance,
+                    _ => share * imbalance,
+                };
+
+                self.write_plug(
+                    dense_data,
+                    &account.name,
+                    date,
+                    self.plug_point(
+                        balance + adjustment,
+                        format!(
+                            "{:.2} adjusted by {:.2} ({:.1}% of the {:.2} imbalance)",
+                            balance,
+                            adjustment,
+                            share * 100.0,
+                            imbalance
+                        ),
+                    ),
+                    conflicts,
+                );
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Leaves every account untouched and books the imbalance to the suspense account.
+    fn plug_suspense(&self, dense_data: &mut BTreeMap<String, DenseSeries>) {
+        let all_dates = self.collect_all_dates(dense_data);
+
+        for date in all_dates {
+            let (assets, liabilities, equity) =
+                self.calculate_balances(dense_data, &self.suspense_account, date);
+            let difference = assets - liabilities - equity;
+
             dense_data
-                .entry(plug_account_name.clone())
+                .entry(self.suspense_account.clone())
                 .or_default()
                 .insert(
                     date,
-                    MonthlyDataPoint {
-                        value: required_plug,
-                        origin: DataOrigin::BalancingPlug,
-                        source: None,
-                        derivation: DerivationDetails {
-                            original_period_value: None,
-                            period_start: None,
-                            period_end: None,
-                            logic: format!(
-                                "System generated plug to enforce Assets ({:.2}) = Liab ({:.2}) + Equity ({:.2})",
-                                assets, liabilities, equity
-                            ),
-                        },
-                    },
+                    self.plug_point(
+                        difference,
+                        format!(
+                            "Unreconciled: Assets ({:.2}) - Liab ({:.2}) - Equity ({:.2})",
+                            assets, liabilities, equity
+                        ),
+                    ),
                 );
         }
+    }
 
-        let warnings = self.check_retained_earnings_rollforward(dense_data);
+    /// Inserts a balancing value, recording a conflict if it replaces a differing snapshot.
+    /// The snapshot's source is kept only when the values agree; a differing document value
+    /// is traceable through the conflict instead.
+    fn write_plug(
+        &self,
+        dense_data: &mut BTreeMap<String, DenseSeries>,
+        account: &str,
+        date: NaiveDate,
+        mut point: MonthlyDataPoint,
+        conflicts: &mut Vec<AnchorConflict>,
+    ) {
+        let series = dense_data.entry(account.to_string()).or_default();
+        if let Some(existing) = series.get(&date) {
+            if existing.origin == DataOrigin::Anchor {
+                if (existing.value - point.value).abs() > ANCHOR_CONFLICT_TOLERANCE {
+                    conflicts.push(AnchorConflict {
+                        account: account.to_string(),
+                        date,
+                        document_value: existing.value,
+                        required_value: point.value,
+                        difference: existing.value - point.value,
+                        routed_to: None,
+                    });
+                } else {
+                    point.source = existing.source.clone();
+                    point.override_lineage = existing.override_lineage.clone();
+                }
+            }
+        }
+        series.insert(date, point);
+    }
 
-        Ok(VerificationResult { warnings })
+    fn plug_point(&self, value: f64, logic: String) -> MonthlyDataPoint {
+        MonthlyDataPoint {
+            value,
+            origin: DataOrigin::BalancingPlug,
+            source: None,
+            override_lineage: None,
+            derivation: DerivationDetails {
+                original_period_value: None,
+                period_start: None,
+                period_end: None,
+                logic,
+                balancing_strategy: Some(self.strategy),
+            },
+        }
     }
 
+    /// Fails at the first month where `Assets = Liabilities + Equity` misses by more than
+    /// `tolerance`. Use [`Self::verification_report`] to see every month.
     pub fn verify_accounting_equation(
         &self,
         dense_data: &BTreeMap<String, DenseSeries>,
         tolerance: f64,
     ) -> Result<()> {
-        let all_dates = self.collect_all_dates(dense_data);
+        let report = self.verification_report(dense_data, tolerance);
 
-        for date in all_dates {
+        match report.rows.iter().find(|row| !row.balanced()) {
+            Some(row) => Err(FinancialHistoryError::AccountingEquationViolation {
+                date: row.date,
+                assets: row.assets,
+                liabilities: row.liabilities,
+                equity: row.equity,
+                difference: row.difference.abs(),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs every check on every month. The retained earnings check compares the movement in
+    /// Retained Earnings (plus Current Year Earnings, if present) with net income less dividends.
+    pub fn verification_report(
+        &self,
+        dense_data: &BTreeMap<String, DenseSeries>,
+        tolerance: f64,
+    ) -> VerificationReport {
+        // Points extrapolated back to the history window only repeat a later snapshot, so the
+        // openings are solved from the snapshots instead
+        let value_at = |name: &str, date: &NaiveDate| {
+            dense_data
+                .get(name)
+                .and_then(|series| series.get(date))
+                .filter(|p| p.origin != DataOrigin::Extrapolated)
+                .map(|p| p.value)
+        };
+        let retained = self.retained_earnings_account();
+        let current_year = self.current_year_earnings_account();
+        let total_earnings = |date: &NaiveDate| {
+            let retained = value_at(&retained?.name, date)?;
+            let current = current_year
+                .and_then(|acc| value_at(&acc.name, date))
+                .unwrap_or(0.0);
+            Some(retained + current)
+        };
+
+        let mut rows = Vec::new();
+        let mut previous: Option<NaiveDate> = None;
+
+        for date in self.collect_all_dates(dense_data) {
             let (assets, liabilities, equity) = self.calculate_balances(dense_data, "", date);
+            let difference = assets - liabilities - equity;
 
-            let left_side = assets;
-            let right_side = liabilities + equity;
-            let difference = (left_side - right_side).abs();
+            let plug_magnitude = dense_data
+                .values()
+                .filter_map(|series| series.get(&date))
+                .filter(|p| p.origin == DataOrigin::BalancingPlug)
+                .map(|p| p.value.abs())
+                .sum();
 
-            if difference > tolerance {
-                return Err(FinancialHistoryError::AccountingEquationViolation {
-                    date,
-                    assets,
-                    liabilities,
-                    equity,
-                    difference,
+            let retained_earnings_delta = previous.and_then(|prev| {
+                let movement = total_earnings(&date)? - total_earnings(&prev)?;
+                let expected = net_income(self.config, dense_data, date)
+                    - dividends(self.config, dense_data, date);
+                Some(movement - expected)
+            });
+
+            let mut checks = vec![CheckResult {
+                check: VerificationCheck::AccountingEquation,
+                passed: difference.abs() <= tolerance,
+            }];
+            if let Some(delta) = retained_earnings_delta {
+                checks.push(CheckResult {
+                    check: VerificationCheck::RetainedEarningsRollforward,
+                    passed: delta.abs() <= self.re_tolerance,
                 });
             }
+
+            rows.push(VerificationRow {
+                date,
+                assets,
+                liabilities,
+                equity,
+                difference,
+                plug_magnitude,
+                retained_earnings_delta,
+                checks,
+            });
+            previous = Some(date);
         }
 
-        Ok(())
+        VerificationReport {
+            tolerance,
+            retained_earnings_tolerance: self.re_tolerance,
+            rows,
+        }
     }
 
     fn find_or_create_plug_account(
         &self,
         dense_data: &BTreeMap<String, DenseSeries>,
     ) -> Result<String> {
+        if let PlugSelection::Account(name) = &self.plug_selection {
+            return Ok(name.clone());
+        }
+
         // 1. Explicit configuration - user designated balancing account
         for account in &self.config.balance_sheet {
-            if account.is_balancing_account {
+            if account.is_balancing_account && !self.is_derived(&account.name) {
                 return Ok(account.name.clone());
             }
         }
@@ -105,6 +528,7 @@ impl<'a> AccountingBalancer<'a> {
         // 2. Explicit Equity type with "retained" or "adjustment" in name
         for account in &self.config.balance_sheet {
             if account.account_type == AccountType::Equity
+                && !self.is_derived(&account.name)
                 && (account.name.to_lowercase().contains("retained")
                     || account.name.to_lowercase().contains("adjustment"))
             {
@@ -114,7 +538,7 @@ impl<'a> AccountingBalancer<'a> {
 
         // 3. Fallback: Any Equity account (by type, not name)
         for account in &self.config.balance_sheet {
-            if account.account_type == AccountType::Equity {
+            if account.account_type == AccountType::Equity && !self.is_derived(&account.name) {
                 return Ok(account.name.clone());
             }
         }
@@ -168,15 +592,8 @@ impl<'a> AccountingBalancer<'a> {
                         AccountType::Equity => equity += value,
                         _ => {}
                     }
-                } else {
-                    let name_lower = name.to_lowercase();
-                    if name_lower.contains("equity")
-                        || name_lower.contains("capital")
-                        || name_lower.contains("retained")
-                        || name_lower.contains("adjustment")
-                    {
-                        equity += value;
-                    }
+                } else if self.is_generated_equity(name, series) {
+                    equity += value;
                 }
             }
         }
@@ -184,6 +601,28 @@ impl<'a> AccountingBalancer<'a> {
         (assets, liabilities, equity)
     }
 
+    /// Accounts that only exist in the dense data (e.g. a plug created by the balancer)
+    /// are treated as Equity when they were explicitly chosen as the plug, hold balancing
+    /// figures, or look like equity. Checking the data lets a fresh balancer recognise a
+    /// renamed suspense account.
+    fn is_generated_equity(&self, name: &str, series: &DenseSeries) -> bool {
+        if let PlugSelection::Account(plug) = &self.plug_selection {
+            if plug == name {
+                return true;
+            }
+        }
+
+        let name_lower = name.to_lowercase();
+        name == self.suspense_account
+            || series
+                .values()
+                .any(|point| point.origin == DataOrigin::BalancingPlug)
+            || name_lower.contains("equity")
+            || name_lower.contains("capital")
+            || name_lower.contains("retained")
+            || name_lower.contains("adjustment")
+    }
+
     fn get_account_type(&self, name: &str) -> AccountType {
         if let Some(account) = self.config.balance_sheet.iter().find(|a| a.name == name) {
             return account.account_type.clone();
@@ -197,57 +636,201 @@ impl<'a> AccountingBalancer<'a> {
         dense_data: &BTreeMap<String, DenseSeries>,
         date: NaiveDate,
     ) -> f64 {
-        let mut revenue = 0.0;
-        let mut other_income = 0.0;
-        let mut cost_of_sales = 0.0;
-        let mut operating_expense = 0.0;
-
-        // New accumulators for specific expense types
-        let mut interest = 0.0;
-        let mut depreciation = 0.0;
-        let mut shareholder_salaries = 0.0;
-        let mut income_tax = 0.0;
-
-        for account in &self.config.income_statement {
-            if let Some(series) = dense_data.get(&account.name) {
-                if let Some(point) = series.get(&date) {
-                    match account.account_type {
-                        AccountType::Revenue => revenue += point.value,
-                        AccountType::OtherIncome => other_income += point.value,
-                        AccountType::CostOfSales => cost_of_sales += point.value,
-                        AccountType::OperatingExpense => operating_expense += point.value,
-                        // Handle new types as expenses
-                        AccountType::Interest => interest += point.value,
-                        AccountType::Depreciation => depreciation += point.value,
-                        AccountType::ShareholderSalaries => shareholder_salaries += point.value,
-                        AccountType::IncomeTax => income_tax += point.value,
-                        _ => {}
+        net_income(self.config, dense_data, date)
+    }
+
+    fn retained_earnings_account(&self) -> Option<&'a BalanceSheetAccount> {
+        self.config
+            .balance_sheet
+            .iter()
+            .find(|acc| acc.name.to_lowercase().contains("retained earnings"))
+    }
+
+    fn current_year_earnings_account(&self) -> Option<&'a BalanceSheetAccount> {
+        self.config
+            .balance_sheet
+            .iter()
+            .find(|acc| is_current_year_earnings(acc))
+    }
+
+    /// Accounts computed by `RetainedEarningsMode::Derive`, which must never absorb the plug.
+    fn is_derived(&self, name: &str) -> bool {
+        self.re_mode == RetainedEarningsMode::Derive
+            && [
+                self.retained_earnings_account(),
+                self.current_year_earnings_account(),
+            ]
+            .iter()
+            .flatten()
+            .any(|acc| acc.name == name)
+    }
+
+    /// Rewrites Retained Earnings (and Current Year Earnings, if present) from the P&L.
+    ///
+    /// The opening balance is the account's value in the first month, or is backed out of its
+    /// earliest snapshot when the series starts later. Returns a warning for every snapshot
+    /// the derived balance disagrees with, and for an opening no snapshot can pin down.
+    fn derive_retained_earnings(
+        &self,
+        dense_data: &mut BTreeMap<String, DenseSeries>,
+    ) -> Vec<String> {
+        let Some(retained) = self.retained_earnings_account() else {
+            return Vec::new();
+        };
+        let current_year = self.current_year_earnings_account();
+
+        let dates = self.collect_all_dates(dense_data);
+        let Some(&first) = dates.first() else {
+            return Vec::new();
+        };
+        let mut warnings = Vec::new();
+
+        // Points extrapolated back to the history window only repeat a later snapshot, so the
+        // openings are solved from the snapshots instead
+        let value_at = |name: &str, date: &NaiveDate| {
+            dense_data
+                .get(name)
+                .and_then(|series| series.get(date))
+                .filter(|p| p.origin != DataOrigin::Extrapolated)
+                .map(|p| p.value)
+        };
+
+        // Both balances are linear in the opening RE, so roll forward from zero and offset later
+        let fy_start_month = self.config.fiscal_year_end_month % 12 + 1;
+        let mut re_movement = 0.0;
+        let first_year_end = get_fiscal_year_end_for_date(first, self.config.fiscal_year_end_month);
+        let mut cye_balance = current_year
+            .map(|acc| {
+                value_at(&acc.name, &first)
+                    .or_else(|| {
+                        // Back-solve from the first snapshot before the year's earnings are swept
+                        let snap = acc
+                            .snapshots
+                            .iter()
+                            .filter(|s| s.date >= first && s.date <= first_year_end)
+                            .min_by_key(|s| s.date)?;
+                        let earned: f64 = dates
+                            .iter()
+                            .filter(|&&date| date > first && date <= snap.date)
+                            .map(|&date| net_income(self.config, dense_data, date))
+                            .sum();
+                        Some(snap.value - earned)
+                    })
+                    .unwrap_or_else(|| {
+                        if first.month() != fy_start_month {
+                            warnings.push(format!(
+                                "Cannot derive the {} opening on {}: the data starts mid fiscal \
+                                 year and no snapshot falls before {}, so earnings earlier in the \
+                                 year are left out",
+                                acc.name, first, first_year_end
+                            ));
+                        }
+                        net_income(self.config, dense_data, first)
+                    })
+            })
+            .unwrap_or(0.0);
+        let mut rolled: Vec<(NaiveDate, f64, f64)> = vec![(first, 0.0, cye_balance)];
+
+        for &date in dates.iter().skip(1) {
+            let earnings = net_income(self.config, dense_data, date);
+            let distributions = dividends(self.config, dense_data, date);
+
+            if current_year.is_some() {
+                if date.month() == fy_start_month {
+                    re_movement += cye_balance;
+                    cye_balance = 0.0;
+                }
+                cye_balance += earnings;
+                re_movement -= distributions;
+            } else {
+                re_movement += earnings - distributions;
+            }
+            rolled.push((date, re_movement, cye_balance));
+        }
+
+        let opening = value_at(&retained.name, &first)
+            .or_else(|| {
+                let mut snapshots = retained.snapshots.clone();
+                snapshots.sort_by_key(|s| s.date);
+                snapshots.iter().find_map(|snap| {
+                    rolled
+                        .iter()
+                        .find(|(date, _, _)| *date == snap.date)
+                        .map(|(_, movement, _)| snap.value - movement)
+                })
+            })
+            .unwrap_or_else(|| {
+                warnings.push(format!(
+                    "Cannot derive the {} opening on {}: no snapshot lines up with the data, \
+                     so it starts from zero",
+                    retained.name, first
+                ));
+                0.0
+            });
+
+        let mut write =
+            |account: &BalanceSheetAccount, date: NaiveDate, value: f64, logic: &str| {
+                let series = dense_data.entry(account.name.clone()).or_default();
+                let existing = series.get(&date);
+                let mut source = existing.and_then(|p| p.source.clone());
+                let mut override_lineage = existing.and_then(|p| p.override_lineage.clone());
+                if let Some(snap) = account.snapshots.iter().find(|s| s.date == date) {
+                    if (snap.value - value).abs() > self.re_tolerance {
+                        warnings.push(format!(
+                            "Derived {} on {} is {:.2} but the document reports {:.2}",
+                            account.name, date, value, snap.value
+                        ));
+                        // The document did not report this value
+                        source = None;
+                        override_lineage = None;
                     }
                 }
+                series.insert(
+                    date,
+                    MonthlyDataPoint {
+                        value,
+                        origin: DataOrigin::Derived,
+                        source,
+                        override_lineage,
+                        derivation: DerivationDetails {
+                            original_period_value: None,
+                            period_start: None,
+                            period_end: None,
+                            logic: logic.to_string(),
+                            balancing_strategy: None,
+                        },
+                    },
+                );
+            };
+
+        for (date, movement, cye) in rolled {
+            write(
+                retained,
+                date,
+                opening + movement,
+                &format!(
+                    "Opening retained earnings ({:.2}) plus accumulated earnings less dividends",
+                    opening
+                ),
+            );
+            if let Some(account) = current_year {
+                write(
+                    account,
+                    date,
+                    cye,
+                    "Net income since the start of the fiscal year",
+                );
             }
         }
 
-        // Net Income = (Revenue + Other Income) - (All Expenses)
-        revenue + other_income
-            - cost_of_sales
-            - operating_expense
-            - interest
-            - depreciation
-            - shareholder_salaries
-            - income_tax
+        warnings
     }
 
     fn check_retained_earnings_rollforward(
         &self,
         dense_data: &BTreeMap<String, DenseSeries>,
     ) -> Vec<String> {
-        let retained = self
-            .config
-            .balance_sheet
-            .iter()
-            .find(|acc| acc.name.to_lowercase().contains("retained earnings"));
-
-        let Some(account) = retained else {
+        let Some(account) = self.retained_earnings_account() else {
             return Vec::new();
         };
 
@@ -259,7 +842,6 @@ impl<'a> AccountingBalancer<'a> {
         dates.sort();
 
         let mut warnings = Vec::new();
-        const RE_TOLERANCE: f64 = 1.0;
 
         for window in dates.windows(2) {
             let prev = window[0];
@@ -268,11 +850,12 @@ impl<'a> AccountingBalancer<'a> {
             if let (Some(prev_point), Some(curr_point)) = (series.get(&prev), series.get(&current))
             {
                 let change = curr_point.value - prev_point.value;
-                let net_income = self.calculate_net_income(dense_data, current);
-                if (change - net_income).abs() > RE_TOLERANCE {
+                let expected = self.calculate_net_income(dense_data, current)
+                    - dividends(self.config, dense_data, current);
+                if (change - expected).abs() > self.re_tolerance {
                     warnings.push(format!(
-                        "Retained earnings movement mismatch on {}: change {:.2} vs net income {:.2}",
-                        current, change, net_income
+                        "Retained earnings movement mismatch on {}: change {:.2} vs net income less dividends {:.2}",
+                        current, change, expected
                     ));
                 }
             }
@@ -282,6 +865,75 @@ impl<'a> AccountingBalancer<'a> {
     }
 }
 
+/// The equity line holding this fiscal year's earnings until they are swept into retained
+/// earnings, e.g. "Current Year Earnings" or "Current Year Profit".
+pub(crate) fn is_current_year_earnings(account: &BalanceSheetAccount) -> bool {
+    let name = account.name.to_lowercase();
+    account.account_type == AccountType::Equity
+        && (name.contains("current year earnings") || name.contains("current year profit"))
+}
+
+/// Net income for one month: revenue and other income less every expense type.
+pub(crate) fn net_income(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    date: NaiveDate,
+) -> f64 {
+    let mut revenue = 0.0;
+    let mut other_income = 0.0;
+    let mut cost_of_sales = 0.0;
+    let mut operating_expense = 0.0;
+
+    // New accumulators for specific expense types
+    let mut interest = 0.0;
+    let mut depreciation = 0.0;
+    let mut shareholder_salaries = 0.0;
+    let mut income_tax = 0.0;
+
+    for account in &config.income_statement {
+        if let Some(series) = dense_data.get(&account.name) {
+            if let Some(point) = series.get(&date) {
+                match account.account_type {
+                    AccountType::Revenue => revenue += point.value,
+                    AccountType::OtherIncome => other_income += point.value,
+                    AccountType::CostOfSales => cost_of_sales += point.value,
+                    AccountType::OperatingExpense => operating_expense += point.value,
+                    // Handle new types as expenses
+                    AccountType::Interest => interest += point.value,
+                    AccountType::Depreciation => depreciation += point.value,
+                    AccountType::ShareholderSalaries => shareholder_salaries += point.value,
+                    AccountType::IncomeTax => income_tax += point.value,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // Net Income = (Revenue + Other Income) - (All Expenses)
+    revenue + other_income
+        - cost_of_sales
+        - operating_expense
+        - interest
+        - depreciation
+        - shareholder_salaries
+        - income_tax
+}
+
+/// Dividends and drawings for one month; they reduce retained earnings but not net income.
+pub(crate) fn dividends(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    date: NaiveDate,
+) -> f64 {
+    config
+        .income_statement
+        .iter()
+        .filter(|account| account.account_type == AccountType::Dividends)
+        .filter_map(|account| dense_data.get(&account.name)?.get(&date))
+        .map(|point| point.value)
+        .sum()
+}
+
 pub fn enforce_accounting_equation(
     config: &FinancialHistoryConfig,
     dense_data: &mut BTreeMap<String, DenseSeries>,
@@ -299,26 +951,41 @@ pub fn verify_accounting_equation(
     balancer.verify_accounting_equation(dense_data, tolerance)
 }
 
+pub fn verification_report(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    tolerance: f64,
+) -> VerificationReport {
+    AccountingBalancer::new(config).verification_report(dense_data, tolerance)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::schema::{BalanceSheetAccount, BalanceSheetSnapshot, InterpolationMethod};
+    use crate::schema::{
+        BalanceSheetAccount, BalanceSheetSnapshot, ExtrapolationPolicy, IncomeStatementAccount,
+        InterpolationMethod, PeriodConstraint, SeasonalityProfileId, SourceMetadata,
+    };
 
     #[test]
     fn test_enforce_accounting_equation() {
         let config = FinancialHistoryConfig {
             organization_name: "Test Corp".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![
                 BalanceSheetAccount {
                     name: "Cash".to_string(),
                     category: None,
                     account_type: AccountType::Asset,
                     method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 10000.0,
                         source: None,
+                        override_lineage: None,
                     }],
                     is_balancing_account: false,
                     noise_factor: 0.0,
@@ -328,10 +995,12 @@ mod tests {
                     category: None,
                     account_type: AccountType::Liability,
                     method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 5000.0,
                         source: None,
+                        override_lineage: None,
                     }],
                     is_balancing_account: false,
                     noise_factor: 0.0,
@@ -341,6 +1010,7 @@ mod tests {
                     category: None,
                     account_type: AccountType::Equity,
                     method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![],
                     is_balancing_account: false,
                     noise_factor: 0.0,
@@ -358,11 +1028,13 @@ mod tests {
                 value: 10000.0,
                 origin: DataOrigin::Anchor,
                 source: None,
+                override_lineage: None,
                 derivation: DerivationDetails {
                     original_period_value: None,
                     period_start: None,
                     period_end: None,
                     logic: "Test data".to_string(),
+                    balancing_strategy: None,
                 },
             },
         );
@@ -375,11 +1047,13 @@ mod tests {
                 value: 5000.0,
                 origin: DataOrigin::Anchor,
                 source: None,
+                override_lineage: None,
                 derivation: DerivationDetails {
                     original_period_value: None,
                     period_start: None,
                     period_end: None,
                     logic: "Test data".to_string(),
+                    balancing_strategy: None,
                 },
             },
         );
@@ -397,12 +1071,15 @@ mod tests {
         let config = FinancialHistoryConfig {
             organization_name: "Test Corp".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![
                 BalanceSheetAccount {
                     name: "Cash".to_string(),
                     category: None,
                     account_type: AccountType::Asset,
                     method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![],
                     is_balancing_account: false,
                     noise_factor: 0.0,
@@ -412,6 +1089,7 @@ mod tests {
                     category: None,
                     account_type: AccountType::Liability,
                     method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![],
                     is_balancing_account: false,
                     noise_factor: 0.0,
@@ -429,11 +1107,13 @@ mod tests {
                 value: 10000.0,
                 origin: DataOrigin::Anchor,
                 source: None,
+                override_lineage: None,
                 derivation: DerivationDetails {
                     original_period_value: None,
                     period_start: None,
                     period_end: None,
                     logic: "Test data".to_string(),
+                    balancing_strategy: None,
                 },
             },
         );
@@ -446,11 +1126,13 @@ mod tests {
                 value: 3000.0,
                 origin: DataOrigin::Anchor,
                 source: None,
+                override_lineage: None,
                 derivation: DerivationDetails {
                     original_period_value: None,
                     period_start: None,
                     period_end: None,
                     logic: "Test data".to_string(),
+                    balancing_strategy: None,
                 },
             },
         );
@@ -459,4 +1141,644 @@ mod tests {
         let result = verify_accounting_equation(&config, &dense_data, 0.01);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_derive_retained_earnings_rolls_forward_by_fiscal_year() {
+        use crate::engine::Densifier;
+
+        let snapshot = |month: u32, day: u32, value: f64| BalanceSheetSnapshot {
+            date: NaiveDate::from_ymd_opt(2023, month, day).unwrap(),
+            value,
+            source: None,
+            override_lineage: None,
+        };
+        let equity = |name: &str, snapshots: Vec<BalanceSheetSnapshot>| BalanceSheetAccount {
+            name: name.to_string(),
+            category: None,
+            account_type: AccountType::Equity,
+            method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
+            snapshots,
+            is_balancing_account: false,
+            noise_factor: 0.0,
+        };
+        let flow = |name: &str, account_type: AccountType, period: &str, value: f64| {
+            IncomeStatementAccount {
+                name: name.to_string(),
+                account_type,
+                seasonality_profile: SeasonalityProfileId::Flat,
+                constraints: vec![PeriodConstraint {
+                    period: period.to_string(),
+                    value,
+                    source: None,
+                    override_lineage: None,
+                }],
+                noise_factor: 0.0,
+            }
+        };
+
+        // June year end; the December RE snapshot is 300 higher than the P&L supports
+        let config = FinancialHistoryConfig {
+            organization_name: "Rollforward Ltd".to_string(),
+            fiscal_year_end_month: 6,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![
+                BalanceSheetAccount {
+                    name: "Cash".to_string(),
+                    category: None,
+                    account_type: AccountType::Asset,
+                    method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
+                    snapshots: vec![snapshot(1, 31, 6700.0), snapshot(12, 31, 6700.0)],
+                    is_balancing_account: false,
+                    noise_factor: 0.0,
+                },
+                equity(
+                    "Retained Earnings",
+                    vec![snapshot(1, 31, 5000.0), snapshot(12, 31, 9000.0)],
+                ),
+                equity("Current Year Earnings", vec![snapshot(1, 31, 700.0)]),
+                equity("Share Capital", vec![snapshot(1, 31, 1000.0)]),
+            ],
+            income_statement: vec![
+                flow("Sales", AccountType::Revenue, "2023-01:2023-12", 12000.0),
+                flow("Dividends Paid", AccountType::Dividends, "2023-09", 2000.0),
+            ],
+        };
+
+        let mut dense_data = Densifier::new(6)
+            .without_noise()
+            .densify_config(&config)
+            .unwrap();
+        let result = AccountingBalancer::new(&config)
+            .with_retained_earnings_mode(RetainedEarningsMode::Derive)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        let at = |name: &str, month: u32, day: u32| {
+            dense_data[name][&NaiveDate::from_ymd_opt(2023, month, day).unwrap()].value
+        };
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-6;
+
+        assert!(close(at("Retained Earnings", 6, 30), 5000.0));
+        assert!(close(at("Current Year Earnings", 6, 30), 5700.0));
+        // July opens a new fiscal year: CYE is swept into RE
+        assert!(close(at("Retained Earnings", 7, 31), 10700.0));
+        assert!(close(at("Current Year Earnings", 7, 31), 1000.0));
+        assert!(close(at("Retained Earnings", 9, 30), 8700.0));
+        assert!(close(at("Current Year Earnings", 12, 31), 6000.0));
+        assert_eq!(
+            dense_data["Retained Earnings"][&NaiveDate::from_ymd_opt(2023, 12, 31).unwrap()].origin,
+            DataOrigin::Derived
+        );
+
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].contains("9000.00"));
+
+        // The plug falls to Share Capital, never to the derived accounts
+        assert_eq!(
+            dense_data["Share Capital"][&NaiveDate::from_ymd_opt(2023, 3, 31).unwrap()].origin,
+            DataOrigin::BalancingPlug
+        );
+        assert!(verify_accounting_equation(&config, &dense_data, 1e-6).is_ok());
+
+        // Derived balances tie to the P&L every month, across the July sweep
+        let report = verification_report(&config, &dense_data, 1e-6);
+        assert!(report.passed());
+        assert!(report.rows[1..]
+            .iter()
+            .all(|row| row.retained_earnings_delta.unwrap().abs() < 1e-6));
+    }
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn snapshot(date: NaiveDate, value: f64) -> BalanceSheetSnapshot {
+        BalanceSheetSnapshot {
+            date,
+            value,
+            source: None,
+            override_lineage: None,
+        }
+    }
+
+    fn constraint(period: &str, value: f64) -> PeriodConstraint {
+        PeriodConstraint {
+            period: period.to_string(),
+            value,
+            source: None,
+            override_lineage: None,
+        }
+    }
+
+    fn balance_sheet_account(
+        name: &str,
+        account_type: AccountType,
+        snapshots: Vec<BalanceSheetSnapshot>,
+    ) -> BalanceSheetAccount {
+        BalanceSheetAccount {
+            name: name.to_string(),
+            category: None,
+            account_type,
+            method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
+            snapshots,
+            is_balancing_account: false,
+            noise_factor: 0.0,
+        }
+    }
+
+    fn income_statement_account(
+        name: &str,
+        account_type: AccountType,
+        constraints: Vec<PeriodConstraint>,
+    ) -> IncomeStatementAccount {
+        IncomeStatementAccount {
+            name: name.to_string(),
+            account_type,
+            seasonality_profile: SeasonalityProfileId::Flat,
+            constraints,
+            noise_factor: 0.0,
+        }
+    }
+
+    fn config(
+        balance_sheet: Vec<BalanceSheetAccount>,
+        income_statement: Vec<IncomeStatementAccount>,
+    ) -> FinancialHistoryConfig {
+        FinancialHistoryConfig {
+            organization_name: "Derive Ltd".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet,
+            income_statement,
+        }
+    }
+
+    #[test]
+    fn test_derive_retained_earnings_solves_opening_before_first_snapshot() {
+        use crate::engine::Densifier;
+
+        // RE is first reported in March, but the history window starts in January
+        let mut cash = balance_sheet_account(
+            "Cash",
+            AccountType::Asset,
+            vec![snapshot(date(2023, 3, 31), 6000.0)],
+        );
+        cash.is_balancing_account = true;
+        let mut config = config(
+            vec![
+                cash,
+                balance_sheet_account(
+                    "Retained Earnings",
+                    AccountType::Equity,
+                    vec![snapshot(date(2023, 3, 31), 5000.0)],
+                ),
+                balance_sheet_account(
+                    "Share Capital",
+                    AccountType::Equity,
+                    vec![snapshot(date(2023, 3, 31), 1000.0)],
+                ),
+            ],
+            vec![income_statement_account(
+                "Sales",
+                AccountType::Revenue,
+                vec![constraint("2023-01:2023-12", 1200.0)],
+            )],
+        );
+        config.history_start = Some(date(2023, 1, 1));
+
+        let mut dense_data = Densifier::new(12)
+            .without_noise()
+            .densify_config(&config)
+            .unwrap();
+        assert_eq!(
+            dense_data["Retained Earnings"][&date(2023, 1, 31)].origin,
+            DataOrigin::Extrapolated
+        );
+        let result = AccountingBalancer::new(&config)
+            .with_retained_earnings_mode(RetainedEarningsMode::Derive)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        let at = |month: u32| {
+            dense_data["Retained Earnings"][&crate::utils::last_day_of_month(2023, month)].value
+        };
+        assert!((at(1) - 4800.0).abs() < 1e-6);
+        assert!((at(3) - 5000.0).abs() < 1e-6);
+        assert!((at(12) - 5900.0).abs() < 1e-6);
+        assert!(result
+            .warnings
+            .iter()
+            .all(|w| !w.contains("Retained Earnings")));
+    }
+
+    #[test]
+    fn test_derive_current_year_earnings_solves_mid_year_opening() {
+        use crate::engine::Densifier;
+
+        // The data starts in March; January and February earned 200 that is already in CYE
+        let equity =
+            |name: &str, snapshots| balance_sheet_account(name, AccountType::Equity, snapshots);
+        let mut cash = balance_sheet_account(
+            "Cash",
+            AccountType::Asset,
+            vec![snapshot(date(2023, 3, 31), 2300.0)],
+        );
+        cash.is_balancing_account = true;
+        let config = config(
+            vec![
+                cash,
+                equity(
+                    "Retained Earnings",
+                    vec![snapshot(date(2023, 3, 31), 2000.0)],
+                ),
+                equity(
+                    "Current Year Earnings",
+                    vec![snapshot(date(2023, 5, 31), 500.0)],
+                ),
+            ],
+            vec![income_statement_account(
+                "Sales",
+                AccountType::Revenue,
+                vec![constraint("2023-03:2023-12", 1000.0)],
+            )],
+        );
+
+        let mut dense_data = Densifier::new(12)
+            .without_noise()
+            .densify_config(&config)
+            .unwrap();
+        let result = AccountingBalancer::new(&config)
+            .with_retained_earnings_mode(RetainedEarningsMode::Derive)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        let at = |month: u32| {
+            dense_data["Current Year Earnings"][&crate::utils::last_day_of_month(2023, month)].value
+        };
+        assert!((at(3) - 300.0).abs() < 1e-6);
+        assert!((at(5) - 500.0).abs() < 1e-6);
+        assert!((at(12) - 1200.0).abs() < 1e-6);
+        assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+    }
+
+    #[test]
+    fn test_derive_current_year_earnings_warns_on_unsolvable_mid_year_opening() {
+        use crate::engine::Densifier;
+
+        // The data starts in March and nothing says what CYE held before then
+        let equity =
+            |name: &str, snapshots| balance_sheet_account(name, AccountType::Equity, snapshots);
+        let mut cash = balance_sheet_account(
+            "Cash",
+            AccountType::Asset,
+            vec![snapshot(date(2023, 3, 31), 2300.0)],
+        );
+        cash.is_balancing_account = true;
+        let config = config(
+            vec![
+                cash,
+                equity(
+                    "Retained Earnings",
+                    vec![snapshot(date(2023, 3, 31), 2000.0)],
+                ),
+                equity(
+                    "Current Year Earnings",
+                    vec![snapshot(date(2024, 1, 31), 0.0)],
+                ),
+            ],
+            vec![income_statement_account(
+                "Sales",
+                AccountType::Revenue,
+                vec![constraint("2023-03:2023-12", 1000.0)],
+            )],
+        );
+
+        let mut dense_data = Densifier::new(12)
+            .without_noise()
+            .densify_config(&config)
+            .unwrap();
+        let result = AccountingBalancer::new(&config)
+            .with_retained_earnings_mode(RetainedEarningsMode::Derive)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        assert!(
+            result
+                .warnings
+                .iter()
+                .any(|w| w.contains("Current Year Earnings opening")
+                    && w.contains("mid fiscal year")),
+            "{:?}",
+            result.warnings
+        );
+        assert!(result
+            .warnings
+            .iter()
+            .all(|w| !w.contains("Retained Earnings opening")));
+    }
+
+    #[test]
+    fn test_check_retained_earnings_allows_for_dividends() {
+        use crate::engine::Densifier;
+
+        // Net income of 100 a month is paid out in February and March, so RE stays flat
+        let config = FinancialHistoryConfig {
+            organization_name: "Payout Ltd".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![BalanceSheetAccount {
+                name: "Retained Earnings".to_string(),
+                category: None,
+                account_type: AccountType::Equity,
+                method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
+                snapshots: vec![
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+                        value: 1000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2023, 3, 31).unwrap(),
+                        value: 1000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                ],
+                is_balancing_account: false,
+                noise_factor: 0.0,
+            }],
+            income_statement: vec![
+                IncomeStatementAccount {
+                    name: "Sales".to_string(),
+                    account_type: AccountType::Revenue,
+                    seasonality_profile: SeasonalityProfileId::Flat,
+                    constraints: vec![PeriodConstraint {
+                        period: "2023-01:2023-03".to_string(),
+                        value: 300.0,
+                        source: None,
+                        override_lineage: None,
+                    }],
+                    noise_factor: 0.0,
+                },
+                IncomeStatementAccount {
+                    name: "Dividends Paid".to_string(),
+                    account_type: AccountType::Dividends,
+                    seasonality_profile: SeasonalityProfileId::Flat,
+                    constraints: vec![PeriodConstraint {
+                        period: "2023-02:2023-03".to_string(),
+                        value: 200.0,
+                        source: None,
+                        override_lineage: None,
+                    }],
+                    noise_factor: 0.0,
+                },
+            ],
+        };
+
+        let dense_data = Densifier::new(12)
+            .without_noise()
+            .densify_config(&config)
+            .unwrap();
+        let balancer = AccountingBalancer::new(&config);
+        assert!(balancer
+            .check_retained_earnings_rollforward(&dense_data)
+            .is_empty());
+    }
+
+    /// Cash and Debtors are flagged; assets exceed liabilities + equity by 1000 in January
+    /// and 1300 in March, both document snapshots.
+    fn strategy_test_setup() -> (FinancialHistoryConfig, BTreeMap<String, DenseSeries>) {
+        let account = |name: &str, account_type: AccountType, values: (f64, f64), flagged: bool| {
+            BalanceSheetAccount {
+                name: name.to_string(),
+                category: None,
+                account_type,
+                method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
+                snapshots: vec![
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+                        value: values.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2023, 3, 31).unwrap(),
+                        value: values.1,
+                        source: None,
+                        override_lineage: None,
+                    },
+                ],
+                is_balancing_account: flagged,
+                noise_factor: 0.0,
+            }
+        };
+        let config = FinancialHistoryConfig {
+            organization_name: "Strategy Test".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![
+                account("Cash", AccountType::Asset, (1000.0, 1300.0), true),
+                account("Debtors", AccountType::Asset, (3000.0, 3000.0), true),
+                account("Loan", AccountType::Liability, (2000.0, 2000.0), false),
+                account(
+                    "Share Capital",
+                    AccountType::Equity,
+                    (1000.0, 1000.0),
+                    false,
+                ),
+            ],
+            income_statement: vec![],
+        };
+        let dense_data = crate::engine::Densifier::new(12)
+            .without_noise()
+            .densify_config(&config)
+            .unwrap();
+        (config, dense_data)
+    }
+
+    fn value<'d>(
+        dense_data: &'d BTreeMap<String, DenseSeries>,
+        name: &str,
+        month: u32,
+    ) -> &'d MonthlyDataPoint {
+        let date = crate::utils::last_day_of_month(2023, month);
+        &dense_data[name][&date]
+    }
+
+    #[test]
+    fn test_single_plug_reports_overwritten_anchors() {
+        let (config, mut dense_data) = strategy_test_setup();
+        let january = crate::utils::last_day_of_month(2023, 1);
+        dense_data
+            .get_mut("Cash")
+            .unwrap()
+            .get_mut(&january)
+            .unwrap()
+            .source = Some(SourceMetadata {
+            document_name: "0".to_string(),
+            original_text: Some("Bank statement".to_string()),
+        });
+        let result = AccountingBalancer::new(&config)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        let conflicts: Vec<(u32, f64, f64)> = result
+            .anchor_conflicts
+            .iter()
+            .map(|c| (c.date.month(), c.document_value, c.difference))
+            .collect();
+        assert_eq!(conflicts, vec![(1, 1000.0, 1000.0), (3, 1300.0, 1300.0)]);
+        assert!(result
+            .anchor_conflicts
+            .iter()
+            .all(|c| c.account == "Cash" && c.routed_to.is_none()));
+        assert_eq!(
+            result
+                .warnings
+                .iter()
+                .filter(|w| w.contains("overwrote the Cash snapshot"))
+                .count(),
+            2
+        );
+        // The plug is not the statement's number, so it does not cite the statement
+        let plug = value(&dense_data, "Cash", 1);
+        assert_eq!(plug.origin, DataOrigin::BalancingPlug);
+        assert!(plug.source.is_none());
+    }
+
+    #[test]
+    fn test_anchor_differences_route_to_named_suspense_account() {
+        let (config, mut dense_data) = strategy_test_setup();
+        let result = AccountingBalancer::new(&config)
+            .with_strategy(BalancingStrategy::BetweenAnchors)
+            .with_suspense_account("Bank Reconciliation Suspense")
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        assert_eq!(result.anchor_conflicts.len(), 2);
+        assert!(result
+            .anchor_conflicts
+            .iter()
+            .all(|c| c.routed_to.as_deref() == Some("Bank Reconciliation Suspense")));
+        assert!(!dense_data.contains_key(SUSPENSE_ACCOUNT_NAME));
+        assert!(
+            (value(&dense_data, "Bank Reconciliation Suspense", 3).value - 1300.0).abs() < 1e-9
+        );
+
+        // Verified without being told the suspense account's name
+        assert!(verify_accounting_equation(&config, &dense_data, 1e-6).is_ok());
+        assert!(verification_report(&config, &dense_data, 1e-6).passed());
+        let cash_flow = crate::cashflow::generate_cash_flow(&config, &dense_data).unwrap();
+        assert!(cash_flow.gaps(1e-6).is_empty());
+    }
+
+    #[test]
+    fn test_between_anchors_keeps_plug_snapshots_exact() {
+        let (config, mut dense_data) = strategy_test_setup();
+        AccountingBalancer::new(&config)
+            .with_strategy(BalancingStrategy::BetweenAnchors)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        assert_eq!(value(&dense_data, "Cash", 1).value, 1000.0);
+        assert_eq!(value(&dense_data, "Cash", 1).origin, DataOrigin::Anchor);
+        assert!((value(&dense_data, SUSPENSE_ACCOUNT_NAME, 1).value - 1000.0).abs() < 1e-9);
+        assert!((value(&dense_data, SUSPENSE_ACCOUNT_NAME, 3).value - 1300.0).abs() < 1e-9);
+
+        let feb = value(&dense_data, "Cash", 2);
+        assert!(feb.value.abs() < 1e-9);
+        assert_eq!(
+            feb.derivation.balancing_strategy,
+            Some(BalancingStrategy::BetweenAnchors)
+        );
+        assert!(!dense_data[SUSPENSE_ACCOUNT_NAME]
+            .contains_key(&NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()));
+        assert!(verify_accounting_equation(&config, &dense_data, 1e-6).is_ok());
+    }
+
+    #[test]
+    fn test_suspense_strategy_leaves_accounts_untouched() {
+        let (config, mut dense_data) = strategy_test_setup();
+        let before = dense_data.clone();
+        AccountingBalancer::new(&config)
+            .with_strategy(BalancingStrategy::Suspense)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        for (name, series) in &before {
+            for (date, point) in series {
+                assert_eq!(dense_data[name][date].value, point.value);
+            }
+        }
+        let feb_cash = value(&before, "Cash", 2).value;
+        let suspense = value(&dense_data, SUSPENSE_ACCOUNT_NAME, 2);
+        assert!((suspense.value - feb_cash).abs() < 1e-9);
+        assert_eq!(
+            suspense.derivation.balancing_strategy,
+            Some(BalancingStrategy::Suspense)
+        );
+        assert!(verify_accounting_equation(&config, &dense_data, 1e-6).is_ok());
+    }
+
+    #[test]
+    fn test_pro_rata_spreads_imbalance_by_balance() {
+        let (config, mut dense_data) = strategy_test_setup();
+        let result = AccountingBalancer::new(&config)
+            .with_strategy(BalancingStrategy::ProRata)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        assert!(!result.warnings.iter().any(|w| w.contains("Pro-rata")));
+        // Both flagged accounts move off their January and March snapshots
+        assert_eq!(result.anchor_conflicts.len(), 4);
+        // January: 1000 imbalance split 25% / 75% by the 1000 / 3000 balances
+        assert!((value(&dense_data, "Cash", 1).value - 750.0).abs() < 1e-9);
+        assert!((value(&dense_data, "Debtors", 1).value - 2250.0).abs() < 1e-9);
+        assert_eq!(
+            value(&dense_data, "Debtors", 2)
+                .derivation
+                .balancing_strategy,
+            Some(BalancingStrategy::ProRata)
+        );
+        assert!(!dense_data.contains_key(SUSPENSE_ACCOUNT_NAME));
+        assert!(verify_accounting_equation(&config, &dense_data, 1e-6).is_ok());
+    }
+
+    #[test]
+    fn test_verification_report_lists_every_month() {
+        let (config, mut dense_data) = strategy_test_setup();
+
+        let before = verification_report(&config, &dense_data, 0.01);
+        assert_eq!(before.rows.len(), 3);
+        assert_eq!(before.failures().count(), 3);
+        assert!((before.rows[0].difference - 1000.0).abs() < 1e-9);
+        assert!(before.rows[0].retained_earnings_delta.is_none());
+
+        AccountingBalancer::new(&config)
+            .with_strategy(BalancingStrategy::Suspense)
+            .enforce_accounting_equation(&mut dense_data)
+            .unwrap();
+
+        let after = verification_report(&config, &dense_data, 0.01);
+        assert!(after.passed());
+        assert!((after.rows[2].plug_magnitude - 1300.0).abs() < 1e-9);
+        assert_eq!(
+            after.rows[2].checks[0].check,
+            VerificationCheck::AccountingEquation
+        );
+
+        let json = serde_json::to_string(&after).unwrap();
+        let parsed: VerificationReport = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.rows.len(), 3);
+    }
 }
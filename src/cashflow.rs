@@ -0,0 +1,602 @@
+use crate::balancer::{dividends, is_current_year_earnings, net_income};
+use crate::error::{FinancialHistoryError, Result};
+use crate::schema::{AccountType, BalanceSheetAccount, FinancialHistoryConfig};
+use crate::DenseSeries;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum CashFlowSection {
+    Operating,
+    Investing,
+    Financing,
+}
+
+/// One row of the cash flow statement with its monthly amounts (inflows positive).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashFlowLine {
+    pub label: String,
+    pub section: CashFlowSection,
+    pub values: BTreeMap<NaiveDate, f64>,
+}
+
+/// Ties the statement back to the cash accounts for one month.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashReconciliation {
+    pub date: NaiveDate,
+    pub opening_cash: f64,
+    pub net_cash_flow: f64,
+    /// Closing cash according to the balance sheet.
+    pub closing_cash: f64,
+    /// `closing_cash - (opening_cash + net_cash_flow)`. Non-zero when balance sheet movements
+    /// are not explained by net income, e.g. retained earnings snapshots that disagree with the P&L.
+    pub gap: f64,
+}
+
+/// Monthly indirect-method cash flow statement.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CashFlowStatement {
+    /// Balance sheet accounts treated as cash and cash equivalents.
+    pub cash_accounts: Vec<String>,
+    /// Operating lines first, then investing, then financing.
+    pub lines: Vec<CashFlowLine>,
+    pub reconciliation: Vec<CashReconciliation>,
+}
+
+impl CashFlowStatement {
+    pub fn section_total(&self, section: CashFlowSection, date: NaiveDate) -> f64 {
+        self.lines
+            .iter()
+            .filter(|line| line.section == section)
+            .filter_map(|line| line.values.get(&date))
+            .sum()
+    }
+
+    /// Months whose reconciliation gap exceeds the tolerance.
+    pub fn gaps(&self, tolerance: f64) -> Vec<&CashReconciliation> {
+        self.reconciliation
+            .iter()
+            .filter(|r| r.gap.abs() > tolerance)
+            .collect()
+    }
+}
+
+/// Derives a cash flow statement from densified (and balanced) data.
+///
+/// Operating cash flow starts from net income, adds back `Depreciation` and takes the movement
+/// in every other current balance, grouped by `category`. Non-current assets are investing,
+/// borrowings, contributed equity and `Dividends` are financing. Retained and current year earnings
+/// are skipped because net income and dividends already cover them. The first month only provides opening balances.
+pub fn generate_cash_flow(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+) -> Result<CashFlowStatement> {
+    let cash_accounts: Vec<String> = config
+        .balance_sheet
+        .iter()
+        .filter(|a| is_cash_account(a))
+        .map(|a| a.name.clone())
+        .collect();
+
+    if cash_accounts.is_empty() {
+        return Err(FinancialHistoryError::ValidationError {
+            account: "Cash".to_string(),
+            details: "No cash account found; mark the bank account as the balancing account \
+                      or include 'Cash' or 'Bank' in its name"
+                .to_string(),
+        });
+    }
+
+    let dates: Vec<NaiveDate> = cash_accounts
+        .iter()
+        .filter_map(|name| dense_data.get(name))
+        .flat_map(|series| series.keys().copied())
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .collect();
+
+    let balance = |name: &str, date: &NaiveDate| -> f64 {
+        dense_data
+            .get(name)
+            .and_then(|series| series.get(date))
+            .map(|p| p.value)
+            .unwrap_or(0.0)
+    };
+
+    let mut builder = LineBuilder::default();
+    let pays_dividends = config
+        .income_statement
+        .iter()
+        .any(|a| a.account_type == AccountType::Dividends);
+    let has_investing_assets = config
+        .balance_sheet
+        .iter()
+        .any(|a| !cash_accounts.contains(&a.name) && classify(a) == Some(Flow::Investing));
+
+    for window in dates.windows(2) {
+        let (prev, date) = (window[0], window[1]);
+
+        builder.add(
+            "Net income",
+            CashFlowSection::Operating,
+            date,
+            net_income(config, dense_data, date),
+        );
+
+        let depreciation: f64 = config
+            .income_statement
+            .iter()
+            .filter(|a| a.account_type == AccountType::Depreciation)
+            .map(|a| balance(&a.name, &date))
+            .sum();
+        builder.add(
+            "Depreciation and amortisation",
+            CashFlowSection::Operating,
+            date,
+            depreciation,
+        );
+
+        for account in &config.balance_sheet {
+            if cash_accounts.contains(&account.name) {
+                continue;
+            }
+            let Some(flow) = classify(account) else {
+                continue;
+            };
+
+            let delta = balance(&account.name, &date) - balance(&account.name, &prev);
+            // An increase in an asset consumes cash; an increase in a claim provides it
+            let cash_effect = match account.account_type {
+                AccountType::Asset => -delta,
+                _ => delta,
+            };
+
+            let (label, section) = match flow {
+                Flow::WorkingCapital => (
+                    format!("Change in {}", category_label(account)),
+                    CashFlowSection::Operating,
+                ),
+                Flow::Investing => (
+                    "Purchase of non-current assets (net)".to_string(),
+                    CashFlowSection::Investing,
+                ),
+                Flow::Financing => (
+                    format!("Change in {}", category_label(account)),
+                    CashFlowSection::Financing,
+                ),
+            };
+            builder.add(&label, section, date, cash_effect);
+        }
+
+        // Net book value falls by the depreciation charge without any cash leaving
+        if has_investing_assets {
+            builder.add(
+                "Purchase of non-current assets (net)",
+                CashFlowSection::Investing,
+                date,
+                -depreciation,
+            );
+        }
+
+        if pays_dividends {
+            builder.add(
+                "Dividends paid",
+                CashFlowSection::Financing,
+                date,
+                -dividends(config, dense_data, date),
+            );
+        }
+
+        // Accounts created by the balancer (e.g. an equity plug) move like contributed equity
+        for name in dense_data.keys() {
+            let configured = config.balance_sheet.iter().any(|a| &a.name == name)
+                || config.income_statement.iter().any(|a| &a.name == name);
+            if !configured {
+                let delta = balance(name, &date) - balance(name, &prev);
+                builder.add(name, CashFlowSection::Financing, date, delta);
+            }
+        }
+    }
+
+    let lines = builder.finish();
+
+    let reconciliation = dates
+        .windows(2)
+        .map(|window| {
+            let (prev, date) = (window[0], window[1]);
+            let opening_cash: f64 = cash_accounts.iter().map(|n| balance(n, &prev)).sum();
+            let closing_cash: f64 = cash_accounts.iter().map(|n| balance(n, &date)).sum();
+            let net_cash_flow: f64 = lines.iter().filter_map(|l| l.values.get(&date)).sum();
+            CashReconciliation {
+                date,
+                opening_cash,
+                net_cash_flow,
+                closing_cash,
+                gap: closing_cash - (opening_cash + net_cash_flow),
+            }
+        })
+        .collect();
+
+    Ok(CashFlowStatement {
+        cash_accounts,
+        lines,
+        reconciliation,
+    })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Flow {
+    WorkingCapital,
+    Investing,
+    Financing,
+}
+
+#[derive(Default)]
+struct LineBuilder {
+    lines: Vec<CashFlowLine>,
+}
+
+impl LineBuilder {
+    fn add(&mut self, label: &str, section: CashFlowSection, date: NaiveDate, value: f64) {
+        let line = match self
+            .lines
+            .iter_mut()
+            .find(|l| l.label == label && l.section == section)
+        {
+            Some(line) => line,
+            None => {
+                self.lines.push(CashFlowLine {
+                    label: label.to_string(),
+                    section,
+                    values: BTreeMap::new(),
+                });
+                self.lines.last_mut().unwrap()
+            }
+        };
+        *line.values.entry(date).or_insert(0.0) += value;
+    }
+
+    fn finish(mut self) -> Vec<CashFlowLine> {
+        // Stable sort keeps first-seen order within each section
+        self.lines.sort_by_key(|l| l.section);
+        self.lines
+    }
+}
+
+fn is_cash_account(account: &BalanceSheetAccount) -> bool {
+    if account.account_type != AccountType::Asset {
+        return false;
+    }
+    let name = account.name.to_lowercase();
+    account.is_balancing_account || name.contains("cash") || name.contains("bank")
+}
+
+/// `None` for balances already represented by net income.
+fn classify(account: &BalanceSheetAccount) -> Option<Flow> {
+    let text = format!(
+        "{} {}",
+        account.name.to_lowercase(),
+        account.category.as_deref().unwrap_or("").to_lowercase()
+    );
+    let mentions = |words: &[&str]| words.iter().any(|w| text.contains(w));
+
+    match account.account_type {
+        AccountType::Asset => {
+            if mentions(&[
+                "non-current",
+                "non current",
+                "fixed",
+                "property",
+                "plant",
+                "equipment",
+                "vehicle",
+                "intangible",
+                "investment",
+                "goodwill",
+                "depreciation",
+                "amortisation",
+                "amortization",
+            ]) {
+                Some(Flow::Investing)
+            } else {
+                Some(Flow::WorkingCapital)
+            }
+        }
+        AccountType::Liability => {
+            if mentions(&[
+                "non-current",
+                "non current",
+                "loan",
+                "borrowing",
+                "debt",
+                "mortgage",
+                "lease",
+                "hire purchase",
+            ]) {
+                Some(Flow::Financing)
+            } else {
+                Some(Flow::WorkingCapital)
+            }
+        }
+        AccountType::Equity => {
+            if mentions(&["retained"]) || is_current_year_earnings(account) {
+                None
+            } else {
+                Some(Flow::Financing)
+            }
+        }
+        _ => None,
+    }
+}
+
+fn category_label(account: &BalanceSheetAccount) -> String {
+    match &account.category {
+        Some(category) => category.clone(),
+        None => match account.account_type {
+            AccountType::Asset => "Other Current Assets".to_string(),
+            AccountType::Liability => "Other Current Liabilities".to_string(),
+            _ => "Equity".to_string(),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{
+        ExtrapolationPolicy, IncomeStatementAccount, InterpolationMethod, PeriodConstraint,
+        SeasonalityProfileId,
+    };
+    use crate::{DataOrigin, DerivationDetails, MonthlyDataPoint};
+
+    fn months() -> Vec<NaiveDate> {
+        vec![
+            NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 2, 28).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 3, 31).unwrap(),
+        ]
+    }
+
+    fn series(values: &[f64]) -> DenseSeries {
+        months()
+            .into_iter()
+            .zip(values)
+            .map(|(date, value)| {
+                (
+                    date,
+                    MonthlyDataPoint {
+                        value: *value,
+                        origin: DataOrigin::Interpolated,
+                        source: None,
+                        override_lineage: None,
+                        derivation: DerivationDetails {
+                            original_period_value: None,
+                            period_start: None,
+                            period_end: None,
+                            logic: "Test data".to_string(),
+                            balancing_strategy: None,
+                        },
+                    },
+                )
+            })
+            .collect()
+    }
+
+    fn bs(name: &str, category: &str, account_type: AccountType) -> BalanceSheetAccount {
+        BalanceSheetAccount {
+            name: name.to_string(),
+            category: Some(category.to_string()),
+            account_type,
+            method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
+            snapshots: vec![],
+            is_balancing_account: false,
+            noise_factor: 0.0,
+        }
+    }
+
+    fn is(name: &str, account_type: AccountType) -> IncomeStatementAccount {
+        IncomeStatementAccount {
+            name: name.to_string(),
+            account_type,
+            seasonality_profile: SeasonalityProfileId::Flat,
+            constraints: vec![PeriodConstraint {
+                period: "2023-01:2023-03".to_string(),
+                value: 0.0,
+                source: None,
+                override_lineage: None,
+            }],
+            noise_factor: 0.0,
+        }
+    }
+
+    fn test_config() -> FinancialHistoryConfig {
+        let mut cash = bs("Business Account", "Current Assets", AccountType::Asset);
+        cash.is_balancing_account = true;
+        FinancialHistoryConfig {
+            organization_name: "Cash Flow Test".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![
+                cash,
+                bs("Accounts Receivable", "Current Assets", AccountType::Asset),
+                bs("Equipment", "Fixed Assets", AccountType::Asset),
+                bs(
+                    "Accounts Payable",
+                    "Current Liabilities",
+                    AccountType::Liability,
+                ),
+                bs(
+                    "Term Loan",
+                    "Non-Current Liabilities",
+                    AccountType::Liability,
+                ),
+                bs("Share Capital", "Equity", AccountType::Equity),
+                bs("Retained Earnings", "Equity", AccountType::Equity),
+            ],
+            income_statement: vec![
+                is("Sales", AccountType::Revenue),
+                is("Depreciation", AccountType::Depreciation),
+            ],
+        }
+    }
+
+    /// Balanced data where retained earnings move exactly by net income (900/month).
+    fn test_data() -> BTreeMap<String, DenseSeries> {
+        let mut data = BTreeMap::new();
+        data.insert("Sales".to_string(), series(&[1000.0, 1000.0, 1000.0]));
+        data.insert("Depreciation".to_string(), series(&[100.0, 100.0, 100.0]));
+        data.insert(
+            "Accounts Receivable".to_string(),
+            series(&[500.0, 700.0, 600.0]),
+        );
+        data.insert("Equipment".to_string(), series(&[5000.0, 5900.0, 5800.0]));
+        data.insert(
+            "Accounts Payable".to_string(),
+            series(&[300.0, 350.0, 300.0]),
+        );
+        data.insert("Term Loan".to_string(), series(&[4000.0, 5000.0, 4800.0]));
+        data.insert(
+            "Share Capital".to_string(),
+            series(&[1000.0, 1000.0, 1000.0]),
+        );
+        data.insert(
+            "Retained Earnings".to_string(),
+            series(&[900.0, 1800.0, 2700.0]),
+        );
+        // Cash = L + E - other assets
+        data.insert(
+            "Business Account".to_string(),
+            series(&[700.0, 1550.0, 2400.0]),
+        );
+        data
+    }
+
+    #[test]
+    fn test_indirect_method_reconciles_to_cash() {
+        let config = test_config();
+        let statement = generate_cash_flow(&config, &test_data()).unwrap();
+        let feb = months()[1];
+
+        assert_eq!(
+            statement.cash_accounts,
+            vec!["Business Account".to_string()]
+        );
+        assert!(statement.gaps(1e-9).is_empty());
+
+        // 900 NI + 100 D&A - 200 receivables + 50 payables
+        assert!((statement.section_total(CashFlowSection::Operating, feb) - 850.0).abs() < 1e-9);
+        // Equipment up 900 after 100 of depreciation: 1000 spent
+        assert!((statement.section_total(CashFlowSection::Investing, feb) + 1000.0).abs() < 1e-9);
+        assert!((statement.section_total(CashFlowSection::Financing, feb) - 1000.0).abs() < 1e-9);
+
+        let labels: Vec<&str> = statement.lines.iter().map(|l| l.label.as_str()).collect();
+        assert!(labels.contains(&"Change in Current Assets"));
+        assert!(!labels.iter().any(|l| l.contains("Retained")));
+        assert_eq!(statement.reconciliation.len(), 2);
+    }
+
+    #[test]
+    fn test_reports_gap_when_retained_earnings_disagree_with_net_income() {
+        let config = test_config();
+        let mut data = test_data();
+        data.insert(
+            "Retained Earnings".to_string(),
+            series(&[900.0, 1850.0, 2750.0]),
+        );
+        data.insert(
+            "Business Account".to_string(),
+            series(&[700.0, 1600.0, 2450.0]),
+        );
+
+        let statement = generate_cash_flow(&config, &data).unwrap();
+        let gaps = statement.gaps(0.01);
+
+        assert_eq!(gaps.len(), 1);
+        assert_eq!(gaps[0].date, months()[1]);
+        assert!((gaps[0].gap - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_current_year_profit_is_not_a_financing_flow() {
+        let mut config = test_config();
+        config
+            .balance_sheet
+            .push(bs("Current Year Profit", "Equity", AccountType::Equity));
+        // The year's earnings sit in Current Year Profit instead of Retained Earnings
+        let mut data = test_data();
+        data.insert(
+            "Retained Earnings".to_string(),
+            series(&[900.0, 900.0, 900.0]),
+        );
+        data.insert(
+            "Current Year Profit".to_string(),
+            series(&[0.0, 900.0, 1800.0]),
+        );
+
+        let statement = generate_cash_flow(&config, &data).unwrap();
+        let feb = months()[1];
+
+        assert!(statement.gaps(1e-9).is_empty());
+        assert!((statement.section_total(CashFlowSection::Financing, feb) - 1000.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contra_asset_moves_with_investing_not_working_capital() {
+        let mut cash = bs("Business Account", "Current Assets", AccountType::Asset);
+        cash.is_balancing_account = true;
+        let config = FinancialHistoryConfig {
+            balance_sheet: vec![
+                cash,
+                bs("Equipment", "Fixed Assets", AccountType::Asset),
+                BalanceSheetAccount {
+                    category: None,
+                    ..bs("Accumulated Depreciation", "", AccountType::Asset)
+                },
+                bs("Share Capital", "Equity", AccountType::Equity),
+                bs("Retained Earnings", "Equity", AccountType::Equity),
+            ],
+            ..test_config()
+        };
+        // Equipment is held at cost; the contra account carries the depreciation
+        let mut data = BTreeMap::new();
+        data.insert("Sales".to_string(), series(&[1000.0, 1000.0, 1000.0]));
+        data.insert("Depreciation".to_string(), series(&[100.0, 100.0, 100.0]));
+        data.insert("Equipment".to_string(), series(&[6000.0, 6000.0, 6000.0]));
+        data.insert(
+            "Accumulated Depreciation".to_string(),
+            series(&[-100.0, -200.0, -300.0]),
+        );
+        data.insert(
+            "Share Capital".to_string(),
+            series(&[5000.0, 5000.0, 5000.0]),
+        );
+        data.insert(
+            "Retained Earnings".to_string(),
+            series(&[900.0, 1800.0, 2700.0]),
+        );
+        data.insert(
+            "Business Account".to_string(),
+            series(&[0.0, 1000.0, 2000.0]),
+        );
+
+        let statement = generate_cash_flow(&config, &data).unwrap();
+        let feb = months()[1];
+
+        assert!(statement.gaps(1e-9).is_empty());
+        // 900 NI + 100 D&A, with nothing spent on equipment
+        assert!((statement.section_total(CashFlowSection::Operating, feb) - 1000.0).abs() < 1e-9);
+        assert!(
+            statement
+                .section_total(CashFlowSection::Investing, feb)
+                .abs()
+                < 1e-9
+        );
+        assert!(!statement
+            .lines
+            .iter()
+            .any(|l| l.label.contains("Other Current Assets")));
+    }
+}
@@ -26,6 +26,7 @@ pub struct ChartOfAccounts {
     pub depreciation: Vec<AccountEntry>,
     pub shareholder_salaries: Vec<AccountEntry>,
     pub income_tax: Vec<AccountEntry>,
+    pub dividends: Vec<AccountEntry>,
 }
 
 impl ChartOfAccounts {
@@ -41,6 +42,7 @@ impl ChartOfAccounts {
         let mut depreciation = Vec::new();
         let mut shareholder_salaries = Vec::new();
         let mut income_tax = Vec::new();
+        let mut dividends = Vec::new();
 
         for account in &config.balance_sheet {
             let entry = AccountEntry {
@@ -76,6 +78,7 @@ impl ChartOfAccounts {
                 AccountType::Depreciation => depreciation.push(entry),
                 AccountType::ShareholderSalaries => shareholder_salaries.push(entry),
                 AccountType::IncomeTax => income_tax.push(entry),
+                AccountType::Dividends => dividends.push(entry),
                 _ => {}
             }
         }
@@ -91,6 +94,7 @@ impl ChartOfAccounts {
         depreciation.sort_by(|a, b| a.name.cmp(&b.name));
         shareholder_salaries.sort_by(|a, b| a.name.cmp(&b.name));
         income_tax.sort_by(|a, b| a.name.cmp(&b.name));
+        dividends.sort_by(|a, b| a.name.cmp(&b.name));
 
         Self {
             organization_name: config.organization_name.clone(),
@@ -106,6 +110,7 @@ impl ChartOfAccounts {
             depreciation,
             shareholder_salaries,
             income_tax,
+            dividends,
         }
     }
 
@@ -223,6 +228,13 @@ impl ChartOfAccounts {
             ));
         }
 
+        for account in &self.dividends {
+            output.push_str(&format!(
+                "Dividends,{},{:?},{}\n",
+                account.name, account.account_type, account.is_balancing_account
+            ));
+        }
+
         output
     }
 
@@ -323,6 +335,12 @@ impl ChartOfAccounts {
         }
         output.push('\n');
 
+        output.push_str("### Dividends\n\n");
+        for account in &self.dividends {
+            output.push_str(&format!("- {}\n", account.name));
+        }
+        output.push('\n');
+
         output
     }
 
@@ -338,6 +356,7 @@ impl ChartOfAccounts {
             + self.depreciation.len()
             + self.shareholder_salaries.len()
             + self.income_tax.len()
+            + self.dividends.len()
     }
 
     pub fn get_balancing_account(&self) -> Option<&AccountEntry> {
@@ -353,6 +372,7 @@ impl ChartOfAccounts {
             .chain(self.depreciation.iter())
             .chain(self.shareholder_salaries.iter())
             .chain(self.income_tax.iter())
+            .chain(self.dividends.iter())
             .find(|a| a.is_balancing_account)
     }
 }
@@ -361,8 +381,8 @@ impl ChartOfAccounts {
 mod tests {
     use super::*;
     use crate::schema::{
-        BalanceSheetAccount, BalanceSheetSnapshot, IncomeStatementAccount, InterpolationMethod,
-        PeriodConstraint, SeasonalityProfileId,
+        BalanceSheetAccount, BalanceSheetSnapshot, ExtrapolationPolicy, IncomeStatementAccount,
+        InterpolationMethod, PeriodConstraint, SeasonalityProfileId,
     };
     use chrono::NaiveDate;
 
@@ -371,15 +391,19 @@ mod tests {
         let config = FinancialHistoryConfig {
             organization_name: "Test Corp".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 10000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 is_balancing_account: true,
                 noise_factor: 0.0,
@@ -392,6 +416,7 @@ mod tests {
                     period: "2023-01:2023-12".to_string(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.0,
             }],
@@ -414,15 +439,19 @@ mod tests {
         let config = FinancialHistoryConfig {
             organization_name: "Test Corp".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 10000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 is_balancing_account: true,
                 noise_factor: 0.0,
@@ -443,15 +472,19 @@ mod tests {
         let config = FinancialHistoryConfig {
             organization_name: "Test Corp".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 10000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 is_balancing_account: true,
                 noise_factor: 0.0,
@@ -0,0 +1,861 @@
+//! Structured differences between two configs, and turning them back into overrides.
+//!
+//! [`diff`] reports what changed between two versions of a config: accounts added, removed,
+//! renamed or merged, metadata and history window changes, and snapshot and constraint value
+//! changes.
+//! [`overrides_between`] turns the same comparison into a [`FinancialHistoryOverrides`] that
+//! replays the changes, so a hand-edited config can be kept as an override list.
+//!
+//! Renames and merges are inferred from values: a removed account whose values reappear
+//! unchanged under a new name was renamed, and removed accounts whose values add up to another
+//! account's were merged into it.
+
+use crate::overrides::{AccountModification, FinancialHistoryOverrides, Statement};
+use crate::schema::{BalanceSheetAccount, FinancialHistoryConfig, IncomeStatementAccount};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Values closer than this are considered unchanged.
+const VALUE_TOLERANCE: f64 = 1e-6;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AccountChange {
+    pub statement: Statement,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenamedAccount {
+    pub statement: Statement,
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MergedAccounts {
+    pub statement: Statement,
+    /// Accounts that no longer exist. The target's own values, if it existed, are included too.
+    pub sources: Vec<String>,
+    pub target: String,
+}
+
+/// A changed account property, with both sides rendered as text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetadataChange {
+    pub account: String,
+    /// Field name as in the config JSON, e.g. `account_type`.
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+/// A changed config-level property such as `history_start`, with both sides rendered as text.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SettingChange {
+    /// Field name as in the config JSON.
+    pub field: String,
+    pub before: String,
+    pub after: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueChange {
+    pub statement: Statement,
+    /// Account name in the newer config.
+    pub account: String,
+    /// Snapshot date (`YYYY-MM-DD`) or constraint period.
+    pub key: String,
+    /// `None` if the value was added.
+    pub before: Option<f64>,
+    /// `None` if the value was removed.
+    pub after: Option<f64>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ConfigDiff {
+    pub settings: Vec<SettingChange>,
+    pub added: Vec<AccountChange>,
+    pub removed: Vec<AccountChange>,
+    pub renamed: Vec<RenamedAccount>,
+    pub merged: Vec<MergedAccounts>,
+    pub metadata: Vec<MetadataChange>,
+    /// Value changes on accounts present in both configs, including renamed ones.
+    /// Merge targets are not listed; their values follow from the merge.
+    pub values: Vec<ValueChange>,
+}
+
+impl ConfigDiff {
+    pub fn is_empty(&self) -> bool {
+        self.settings.is_empty()
+            && self.added.is_empty()
+            && self.removed.is_empty()
+            && self.renamed.is_empty()
+            && self.merged.is_empty()
+            && self.metadata.is_empty()
+            && self.values.is_empty()
+    }
+}
+
+/// One account reduced to what the diff compares: a name and keyed values.
+struct Entry<'a> {
+    name: &'a str,
+    /// Snapshot dates or constraint periods, in config order. IS periods may repeat, and
+    /// their order within a period does not matter.
+    values: Vec<(String, f64)>,
+}
+
+impl Entry<'_> {
+    fn from_balance_sheet(account: &BalanceSheetAccount) -> Entry<'_> {
+        Entry {
+            name: &account.name,
+            values: account
+                .snapshots
+                .iter()
+                .map(|s| (s.date.to_string(), s.value))
+                .collect(),
+        }
+    }
+
+    fn from_income_statement(account: &IncomeStatementAccount) -> Entry<'_> {
+        Entry {
+            name: &account.name,
+            values: account
+                .constraints
+                .iter()
+                .map(|c| (c.period.clone(), c.value))
+                .collect(),
+        }
+    }
+
+    fn by_key(&self) -> BTreeMap<&str, Vec<f64>> {
+        let mut map: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+        for (key, value) in &self.values {
+            map.entry(key).or_default().push(*value);
+        }
+        map
+    }
+}
+
+/// Compares two configs; `a` is the older one.
+pub fn diff(a: &FinancialHistoryConfig, b: &FinancialHistoryConfig) -> ConfigDiff {
+    let mut result = ConfigDiff::default();
+
+    let mut setting = |field: &str, before: String, after: String| {
+        if before != after {
+            result.settings.push(SettingChange {
+                field: field.to_string(),
+                before,
+                after,
+            });
+        }
+    };
+    setting(
+        "fiscal_year_end_month",
+        a.fiscal_year_end_month.to_string(),
+        b.fiscal_year_end_month.to_string(),
+    );
+    setting(
+        "history_start",
+        format!("{:?}", a.history_start),
+        format!("{:?}", b.history_start),
+    );
+    setting(
+        "history_end",
+        format!("{:?}", a.history_end),
+        format!("{:?}", b.history_end),
+    );
+
+    let bs_a: Vec<Entry> = a
+        .balance_sheet
+        .iter()
+        .map(Entry::from_balance_sheet)
+        .collect();
+    let bs_b: Vec<Entry> = b
+        .balance_sheet
+        .iter()
+        .map(Entry::from_balance_sheet)
+        .collect();
+    diff_statement(Statement::BalanceSheet, &bs_a, &bs_b, &mut result);
+
+    let is_a: Vec<Entry> = a
+        .income_statement
+        .iter()
+        .map(Entry::from_income_statement)
+        .collect();
+    let is_b: Vec<Entry> = b
+        .income_statement
+        .iter()
+        .map(Entry::from_income_statement)
+        .collect();
+    diff_statement(Statement::IncomeStatement, &is_a, &is_b, &mut result);
+
+    for renamed in &result.renamed {
+        match renamed.statement {
+            Statement::BalanceSheet => {
+                let before = a.balance_sheet.iter().find(|x| x.name == renamed.from);
+                let after = b.balance_sheet.iter().find(|x| x.name == renamed.to);
+                if let (Some(before), Some(after)) = (before, after) {
+                    balance_sheet_metadata(before, after, &mut result.metadata);
+                }
+            }
+            Statement::IncomeStatement => {
+                let before = a.income_statement.iter().find(|x| x.name == renamed.from);
+                let after = b.income_statement.iter().find(|x| x.name == renamed.to);
+                if let (Some(before), Some(after)) = (before, after) {
+                    income_statement_metadata(before, after, &mut result.metadata);
+                }
+            }
+        }
+    }
+    let merge_targets: Vec<&str> = result.merged.iter().map(|m| m.target.as_str()).collect();
+    for before in &a.balance_sheet {
+        if let Some(after) = b.balance_sheet.iter().find(|x| x.name == before.name) {
+            if !merge_targets.contains(&after.name.as_str()) {
+                balance_sheet_metadata(before, after, &mut result.metadata);
+            }
+        }
+    }
+    for before in &a.income_statement {
+        if let Some(after) = b.income_statement.iter().find(|x| x.name == before.name) {
+            if !merge_targets.contains(&after.name.as_str()) {
+                income_statement_metadata(before, after, &mut result.metadata);
+            }
+        }
+    }
+
+    result
+}
+
+fn diff_statement(statement: Statement, a: &[Entry], b: &[Entry], result: &mut ConfigDiff) {
+    let find = |entries: &[Entry], name: &str| entries.iter().position(|e| e.name == name);
+    let mut removed: Vec<usize> = (0..a.len())
+        .filter(|&i| find(b, a[i].name).is_none())
+        .collect();
+    let mut added: Vec<usize> = (0..b.len())
+        .filter(|&j| find(a, b[j].name).is_none())
+        .collect();
+    let mut merge_targets = Vec::new();
+
+    // Merges: removed accounts whose values all land in one account of the newer config
+    for (j, target) in b.iter().enumerate() {
+        let existing = find(a, target.name).map(|i| &a[i]);
+        let candidates: Vec<usize> = removed
+            .iter()
+            .copied()
+            .filter(|&i| !a[i].values.is_empty() && contributes_to(statement, &a[i], target))
+            .collect();
+        let needed = if existing.is_some() { 1 } else { 2 };
+        let Some(candidates) = largest_merge(statement, a, &candidates, existing, target, needed)
+        else {
+            continue;
+        };
+
+        result.merged.push(MergedAccounts {
+            statement,
+            sources: candidates.iter().map(|&i| a[i].name.to_string()).collect(),
+            target: target.name.to_string(),
+        });
+        removed.retain(|i| !candidates.contains(i));
+        added.retain(|&x| x != j);
+        merge_targets.push(j);
+    }
+
+    // Renames: a removed account whose values reappear unchanged under a new name
+    for i in removed.clone() {
+        let Some(position) = added
+            .iter()
+            .position(|&j| !a[i].values.is_empty() && same_values(&a[i], &b[j]))
+        else {
+            continue;
+        };
+        let j = added.remove(position);
+        removed.retain(|&x| x != i);
+        result.renamed.push(RenamedAccount {
+            statement,
+            from: a[i].name.to_string(),
+            to: b[j].name.to_string(),
+        });
+        value_changes(statement, &a[i], &b[j], &mut result.values);
+    }
+
+    result
+        .removed
+        .extend(removed.iter().map(|&i| AccountChange {
+            statement,
+            name: a[i].name.to_string(),
+        }));
+    result.added.extend(added.iter().map(|&j| AccountChange {
+        statement,
+        name: b[j].name.to_string(),
+    }));
+
+    for (j, after) in b.iter().enumerate() {
+        if merge_targets.contains(&j) {
+            continue;
+        }
+        if let Some(i) = find(a, after.name) {
+            value_changes(statement, &a[i], after, &mut result.values);
+        }
+    }
+}
+
+/// Most candidates searched for a merge; beyond this only the full set is tried.
+const MAX_MERGE_CANDIDATES: usize = 12;
+
+/// The largest subset of `candidates` (at least `needed` of them) that, together with the
+/// target's own earlier values, adds up to `target`.
+fn largest_merge(
+    statement: Statement,
+    a: &[Entry],
+    candidates: &[usize],
+    existing: Option<&Entry>,
+    target: &Entry,
+    needed: usize,
+) -> Option<Vec<usize>> {
+    if candidates.len() < needed {
+        return None;
+    }
+    let subsets: Vec<Vec<usize>> = if candidates.len() > MAX_MERGE_CANDIDATES {
+        vec![candidates.to_vec()]
+    } else {
+        let mut all: Vec<Vec<usize>> = (1..1u32 << candidates.len())
+            .map(|mask| {
+                (0..candidates.len())
+                    .filter(|bit| mask & (1 << bit) != 0)
+                    .map(|bit| candidates[bit])
+                    .collect()
+            })
+            .collect();
+        all.sort_by_key(|subset: &Vec<usize>| std::cmp::Reverse(subset.len()));
+        all
+    };
+
+    subsets
+        .into_iter()
+        .filter(|subset| subset.len() >= needed)
+        .find(|subset| {
+            let parts: Vec<&Entry> = subset.iter().map(|&i| &a[i]).chain(existing).collect();
+            is_merge_of(statement, &parts, target)
+        })
+}
+
+/// Whether every value of `source` could have been folded into `target` by a merge.
+fn contributes_to(statement: Statement, source: &Entry, target: &Entry) -> bool {
+    let target_keys = target.by_key();
+    match statement {
+        // Merged snapshots are summed by date
+        Statement::BalanceSheet => source
+            .values
+            .iter()
+            .all(|(key, _)| target_keys.contains_key(key.as_str())),
+        // Merged constraints are carried over unchanged
+        Statement::IncomeStatement => source.values.iter().all(|(key, value)| {
+            target_keys
+                .get(key.as_str())
+                .is_some_and(|values| values.iter().any(|v| close(*v, *value)))
+        }),
+    }
+}
+
+fn is_merge_of(statement: Statement, parts: &[&Entry], target: &Entry) -> bool {
+    match statement {
+        Statement::BalanceSheet => {
+            let mut sums: BTreeMap<&str, f64> = BTreeMap::new();
+            for part in parts {
+                for (key, value) in &part.values {
+                    *sums.entry(key).or_default() += value;
+                }
+            }
+            let target_values: BTreeMap<&str, f64> = target
+                .values
+                .iter()
+                .map(|(k, v)| (k.as_str(), *v))
+                .collect();
+            sums.len() == target_values.len()
+                && sums
+                    .iter()
+                    .all(|(k, v)| target_values.get(k).is_some_and(|t| close(*t, *v)))
+        }
+        Statement::IncomeStatement => {
+            let mut combined: Vec<(String, f64)> =
+                parts.iter().flat_map(|p| p.values.clone()).collect();
+            let mut expected = target.values.clone();
+            combined.sort_by(|x, y| x.0.cmp(&y.0).then(x.1.total_cmp(&y.1)));
+            expected.sort_by(|x, y| x.0.cmp(&y.0).then(x.1.total_cmp(&y.1)));
+            combined.len() == expected.len()
+                && combined
+                    .iter()
+                    .zip(&expected)
+                    .all(|(x, y)| x.0 == y.0 && close(x.1, y.1))
+        }
+    }
+}
+
+fn same_values(a: &Entry, b: &Entry) -> bool {
+    let mut changes = Vec::new();
+    value_changes(Statement::BalanceSheet, a, b, &mut changes);
+    changes.is_empty()
+}
+
+fn value_changes(statement: Statement, a: &Entry, b: &Entry, changes: &mut Vec<ValueChange>) {
+    let before = a.by_key();
+    let after = b.by_key();
+    let mut keys: Vec<&str> = before.keys().chain(after.keys()).copied().collect();
+    keys.sort();
+    keys.dedup();
+
+    for key in keys {
+        // Values kept under a repeated key are unchanged wherever they sit; the rest are
+        // paired up in order
+        let mut old = before.get(key).cloned().unwrap_or_default();
+        let mut new = after.get(key).cloned().unwrap_or_default();
+        old.retain(|x| match new.iter().position(|y| close(*x, *y)) {
+            Some(n) => {
+                new.remove(n);
+                false
+            }
+            None => true,
+        });
+
+        for n in 0..old.len().max(new.len()) {
+            changes.push(ValueChange {
+                statement,
+                account: b.name.to_string(),
+                key: key.to_string(),
+                before: old.get(n).copied(),
+                after: new.get(n).copied(),
+            });
+        }
+    }
+}
+
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= VALUE_TOLERANCE
+}
+
+fn push_change<T: std::fmt::Debug + PartialEq>(
+    account: &str,
+    field: &str,
+    before: &T,
+    after: &T,
+    changes: &mut Vec<MetadataChange>,
+) {
+    if before != after {
+        changes.push(MetadataChange {
+            account: account.to_string(),
+            field: field.to_string(),
+            before: format!("{:?}", before),
+            after: format!("{:?}", after),
+        });
+    }
+}
+
+fn balance_sheet_metadata(
+    a: &BalanceSheetAccount,
+    b: &BalanceSheetAccount,
+    changes: &mut Vec<MetadataChange>,
+) {
+    let name = &b.name;
+    push_change(
+        name,
+        "account_type",
+        &a.account_type,
+        &b.account_type,
+        changes,
+    );
+    push_change(name, "category", &a.category, &b.category, changes);
+    push_change(name, "method", &a.method, &b.method, changes);
+    push_change(
+        name,
+        "extrapolation",
+        &a.extrapolation,
+        &b.extrapolation,
+        changes,
+    );
+    push_change(
+        name,
+        "is_balancing_account",
+        &a.is_balancing_account,
+        &b.is_balancing_account,
+        changes,
+    );
+    push_change(
+        name,
+        "noise_factor",
+        &a.noise_factor,
+        &b.noise_factor,
+        changes,
+    );
+}
+
+fn income_statement_metadata(
+    a: &IncomeStatementAccount,
+    b: &IncomeStatementAccount,
+    changes: &mut Vec<MetadataChange>,
+) {
+    let name = &b.name;
+    push_change(
+        name,
+        "account_type",
+        &a.account_type,
+        &b.account_type,
+        changes,
+    );
+    push_change(
+        name,
+        "seasonality_profile",
+        &a.seasonality_profile,
+        &b.seasonality_profile,
+        changes,
+    );
+    push_change(
+        name,
+        "noise_factor",
+        &a.noise_factor,
+        &b.noise_factor,
+        changes,
+    );
+}
+
+/// Builds overrides that turn `a` into `b` when applied.
+///
+/// Added accounts become new accounts; renames, merges, deletions, type, category and
+/// balancing-flag changes, and changed or added values become modifications. Overrides cannot
+/// remove a single snapshot or constraint, clear a category, change an interpolation method,
+/// extrapolation policy, seasonality profile or noise factor, or change the fiscal year end or
+/// history window, so those changes are left out; `diff(&overrides.apply(a), b)` lists
+/// whatever remains.
+pub fn overrides_between(
+    a: &FinancialHistoryConfig,
+    b: &FinancialHistoryConfig,
+) -> FinancialHistoryOverrides {
+    let changes = diff(a, b);
+    let mut overrides = FinancialHistoryOverrides::default();
+
+    for added in &changes.added {
+        match added.statement {
+            Statement::BalanceSheet => overrides.new_balance_sheet_accounts.extend(
+                b.balance_sheet
+                    .iter()
+                    .filter(|x| x.name == added.name)
+                    .cloned(),
+            ),
+            Statement::IncomeStatement => overrides.new_income_statement_accounts.extend(
+                b.income_statement
+                    .iter()
+                    .filter(|x| x.name == added.name)
+                    .cloned(),
+            ),
+        }
+    }
+
+    let modifications = &mut overrides.modifications;
+    for renamed in &changes.renamed {
+        modifications.push(AccountModification::Rename {
+            target: renamed.from.clone(),
+            new_name: renamed.to.clone(),
+        });
+    }
+    for removed in &changes.removed {
+        modifications.push(AccountModification::Delete {
+            target: removed.name.clone(),
+        });
+    }
+    for merged in &changes.merged {
+        modifications.push(AccountModification::Merge {
+            sources: merged.sources.clone(),
+            target_name: merged.target.clone(),
+        });
+        // A merge copies its properties from the first account found, so restate the target's
+        if let Some(target) = b.balance_sheet.iter().find(|x| x.name == merged.target) {
+            modifications.push(AccountModification::UpdateMetadata {
+                target: target.name.clone(),
+                new_category: target.category.clone(),
+                new_type: Some(target.account_type.clone()),
+                new_is_balancing_account: Some(target.is_balancing_account),
+            });
+        } else if let Some(target) = b.income_statement.iter().find(|x| x.name == merged.target) {
+            modifications.push(AccountModification::UpdateMetadata {
+                target: target.name.clone(),
+                new_category: None,
+                new_type: Some(target.account_type.clone()),
+                new_is_balancing_account: None,
+            });
+        }
+    }
+
+    let mut metadata: BTreeMap<&str, Vec<&MetadataChange>> = BTreeMap::new();
+    for change in &changes.metadata {
+        metadata.entry(&change.account).or_default().push(change);
+    }
+    for (account, fields) in metadata {
+        let changed = |field: &str| fields.iter().any(|c| c.field == field);
+        let modification = if let Some(x) = b.balance_sheet.iter().find(|x| x.name == account) {
+            AccountModification::UpdateMetadata {
+                target: x.name.clone(),
+                new_category: x.category.clone().filter(|_| changed("category")),
+                new_type: Some(x.account_type.clone()).filter(|_| changed("account_type")),
+                new_is_balancing_account: Some(x.is_balancing_account)
+                    .filter(|_| changed("is_balancing_account")),
+            }
+        } else if let Some(x) = b.income_statement.iter().find(|x| x.name == account) {
+            AccountModification::UpdateMetadata {
+                target: x.name.clone(),
+                new_category: None,
+                new_type: Some(x.account_type.clone()).filter(|_| changed("account_type")),
+                new_is_balancing_account: None,
+            }
+        } else {
+            continue;
+        };
+        if let AccountModification::UpdateMetadata {
+            new_category: None,
+            new_type: None,
+            new_is_balancing_account: None,
+            ..
+        } = modification
+        {
+            continue;
+        }
+        modifications.push(modification);
+    }
+
+    for change in &changes.values {
+        if let Some(value) = change.after {
+            // Snapshots are replaced by date; constraints by period and their old value
+            let previous_value = match change.statement {
+                Statement::BalanceSheet => None,
+                Statement::IncomeStatement => change.before,
+            };
+            modifications.push(AccountModification::SetValue {
+                target: change.account.clone(),
+                date_or_period: change.key.clone(),
+                value,
+                previous_value,
+                reason: None,
+            });
+        }
+    }
+
+    overrides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{
+        AccountType, BalanceSheetSnapshot, ExtrapolationPolicy, InterpolationMethod,
+        PeriodConstraint, SeasonalityProfileId,
+    };
+    use chrono::NaiveDate;
+
+    fn balance(name: &str, values: &[(u32, f64)]) -> BalanceSheetAccount {
+        BalanceSheetAccount {
+            name: name.to_string(),
+            category: None,
+            account_type: AccountType::Asset,
+            method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
+            snapshots: values
+                .iter()
+                .map(|&(month, value)| BalanceSheetSnapshot {
+                    date: crate::utils::last_day_of_month(2023, month),
+                    value,
+                    source: None,
+                    override_lineage: None,
+                })
+                .collect(),
+            is_balancing_account: false,
+            noise_factor: 0.0,
+        }
+    }
+
+    fn income(name: &str, values: &[(&str, f64)]) -> IncomeStatementAccount {
+        IncomeStatementAccount {
+            name: name.to_string(),
+            account_type: AccountType::OperatingExpense,
+            seasonality_profile: SeasonalityProfileId::Flat,
+            constraints: values
+                .iter()
+                .map(|&(period, value)| PeriodConstraint {
+                    period: period.to_string(),
+                    value,
+                    source: None,
+                    override_lineage: None,
+                })
+                .collect(),
+            noise_factor: 0.0,
+        }
+    }
+
+    fn base() -> FinancialHistoryConfig {
+        FinancialHistoryConfig {
+            organization_name: "Test".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![
+                balance("Cash", &[(6, 100.0), (12, 150.0)]),
+                balance("Petty Cash", &[(12, 5.0)]),
+                balance("Float", &[(6, 1.0), (12, 2.0)]),
+                balance("Debtors", &[(12, 40.0)]),
+            ],
+            income_statement: vec![
+                income("Telco", &[("2023-01:2023-12", 1200.0)]),
+                income("Phone", &[("2023-01:2023-06", 300.0)]),
+                income("Internet", &[("2023-07:2023-12", 500.0)]),
+            ],
+        }
+    }
+
+    fn edited() -> FinancialHistoryConfig {
+        let mut config = base();
+        // Petty Cash and Float folded into Cash
+        config.balance_sheet = vec![
+            balance("Cash", &[(6, 101.0), (12, 157.0)]),
+            balance("Trade Debtors", &[(12, 40.0)]),
+            balance("Inventory", &[(12, 80.0)]),
+        ];
+        config.balance_sheet[1].category = Some("Current Assets".to_string());
+        config.income_statement = vec![
+            income("Telephone & Internet", &[("2023-01:2023-12", 1200.0)]),
+            income(
+                "Communications",
+                &[("2023-01:2023-06", 300.0), ("2023-07:2023-12", 500.0)],
+            ),
+        ];
+        config
+    }
+
+    #[test]
+    fn test_diff_finds_renames_merges_and_value_changes() {
+        let changes = diff(&base(), &edited());
+
+        assert_eq!(
+            changes.merged,
+            [
+                MergedAccounts {
+                    statement: Statement::BalanceSheet,
+                    sources: vec!["Petty Cash".to_string(), "Float".to_string()],
+                    target: "Cash".to_string(),
+                },
+                MergedAccounts {
+                    statement: Statement::IncomeStatement,
+                    sources: vec!["Phone".to_string(), "Internet".to_string()],
+                    target: "Communications".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            changes.renamed,
+            [
+                RenamedAccount {
+                    statement: Statement::BalanceSheet,
+                    from: "Debtors".to_string(),
+                    to: "Trade Debtors".to_string(),
+                },
+                RenamedAccount {
+                    statement: Statement::IncomeStatement,
+                    from: "Telco".to_string(),
+                    to: "Telephone & Internet".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            changes.added,
+            [AccountChange {
+                statement: Statement::BalanceSheet,
+                name: "Inventory".to_string(),
+            }]
+        );
+        assert!(changes.removed.is_empty());
+        assert_eq!(changes.metadata.len(), 1);
+        assert_eq!(changes.metadata[0].field, "category");
+        assert!(changes.values.is_empty());
+    }
+
+    #[test]
+    fn test_unmatched_values_are_value_changes() {
+        let mut edited = base();
+        edited.income_statement[0].constraints[0].value = 1250.0;
+        edited.balance_sheet[0].snapshots.pop();
+
+        let changes = diff(&base(), &edited);
+        assert_eq!(
+            changes.values,
+            [
+                ValueChange {
+                    statement: Statement::BalanceSheet,
+                    account: "Cash".to_string(),
+                    key: "2023-12-31".to_string(),
+                    before: Some(150.0),
+                    after: None,
+                },
+                ValueChange {
+                    statement: Statement::IncomeStatement,
+                    account: "Telco".to_string(),
+                    key: "2023-01:2023-12".to_string(),
+                    before: Some(1200.0),
+                    after: Some(1250.0),
+                },
+            ]
+        );
+        assert!(diff(&base(), &base()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_reports_extrapolation_and_history_window() {
+        let mut edited = base();
+        edited.balance_sheet[1].extrapolation = ExtrapolationPolicy::Zero;
+        edited.history_start = NaiveDate::from_ymd_opt(2023, 1, 31);
+
+        let changes = diff(&base(), &edited);
+        assert_eq!(
+            changes.settings,
+            [SettingChange {
+                field: "history_start".to_string(),
+                before: "None".to_string(),
+                after: "Some(2023-01-31)".to_string(),
+            }]
+        );
+        assert_eq!(changes.metadata.len(), 1);
+        assert_eq!(changes.metadata[0].account, "Petty Cash");
+        assert_eq!(changes.metadata[0].field, "extrapolation");
+
+        // Neither can be replayed, so both remain after the overrides
+        let overrides = overrides_between(&base(), &edited);
+        assert!(overrides.modifications.is_empty());
+    }
+
+    #[test]
+    fn test_overrides_between_replays_the_edit() {
+        let replay = |a: &FinancialHistoryConfig, b: &FinancialHistoryConfig| {
+            let overrides = overrides_between(a, b);
+            let replayed = overrides.try_apply(a, crate::ApplyMode::Strict).unwrap();
+            let remaining = diff(&replayed.config, b);
+            assert!(remaining.is_empty(), "{:?}", remaining);
+        };
+        replay(&base(), &edited());
+
+        // Value changes, including one of two constraints for the same period, as left by
+        // an income statement merge
+        let mut a = base();
+        a.income_statement
+            .push(income("Comms", &[("2023-01", 100.0), ("2023-01", 50.0)]));
+        let mut b = a.clone();
+        b.income_statement[3] = income("Comms", &[("2023-01", 100.0), ("2023-01", 60.0)]);
+        b.income_statement[2].constraints[0].value = 550.0;
+        b.income_statement[0] = income(
+            "Telco",
+            &[("2023-01:2023-12", 1200.0), ("2023-01:2023-12", 1200.0)],
+        );
+        b.balance_sheet[0].snapshots[1].value = 160.0;
+        assert_eq!(diff(&a, &b).values.len(), 4);
+        replay(&a, &b);
+
+        // Reordering the constraints of one period is not a change
+        let mut reordered = a.clone();
+        reordered.income_statement[3].constraints.swap(0, 1);
+        assert!(diff(&a, &reordered).is_empty());
+    }
+}
@@ -1,16 +1,76 @@
 use crate::error::Result;
+use crate::interpolation::{BalanceCurve, DriverLink};
 use crate::schema::*;
-use crate::seasonality::{get_profile_weights, rotate_weights_for_fiscal_year};
-use crate::utils::get_month_ends_in_period;
+use crate::seasonality::{infer_profile, rotate_weights_for_fiscal_year, SeasonalityRegistry};
+use crate::solver::solve_weighted_min_norm;
+use crate::utils::{get_month_ends_in_period, last_day_of_month};
 use crate::{DataOrigin, DenseSeries, DerivationDetails, MonthlyDataPoint};
 use chrono::{Datelike, NaiveDate};
-use rand::rng;
+use log::warn;
+use rand::{rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 use rand_distr::{Distribution, Normal};
-use splines::{Interpolation, Key, Spline};
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
+/// Absolute gap between a constraint and its achieved total that still counts as met.
+pub(crate) const CONSTRAINT_TOLERANCE: f64 = 0.01;
+/// Floor for seasonality weights in the least-squares solver, which needs them strictly positive.
+const MIN_SOLVER_WEIGHT: f64 = 1e-9;
+
+/// Strategy used to turn income statement period constraints into monthly values.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum IncomeStatementSolver {
+    /// Smallest periods are locked first and larger periods fill the remaining months.
+    /// Partially overlapping periods cannot all be honoured.
+    #[default]
+    Hierarchical,
+    /// Solves all constraints at once, staying as close to the seasonality shape as possible.
+    /// Inconsistent constraints get a least-squares compromise; see [`ConstraintOutcome`].
+    LeastSquares,
+}
+
+/// How a period constraint is reflected in the dense series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstraintStatus {
+    /// The monthly values add up to the requested total.
+    Satisfied,
+    /// Met, but every month is already fixed by smaller constraints, so it had no influence.
+    OverDetermined,
+    /// Not met, or only met by pushing the remaining months against the sign of the total
+    /// because smaller constraints already exceed it.
+    Conflicting,
+    /// Part of the period lies outside the generated months, so it cannot be checked.
+    OutOfRange,
+}
+
+/// One period constraint checked against the densified series.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConstraintOutcome {
+    pub period: String,
+    pub requested: f64,
+    /// Sum of the generated monthly values over the period.
+    pub achieved: f64,
+    /// `achieved - requested`
+    pub residual: f64,
+    pub status: ConstraintStatus,
+}
+
+#[derive(Debug, Clone)]
+pub struct IncomeStatementSolution {
+    pub series: DenseSeries,
+    /// One entry per constraint, in the order they appear on the account.
+    pub constraints: Vec<ConstraintOutcome>,
+}
+
 pub struct Densifier {
     fiscal_year_end_month: u32,
+    seed: Option<u64>,
+    noise_enabled: bool,
+    solver: IncomeStatementSolver,
+    history_start: Option<NaiveDate>,
+    history_end: Option<NaiveDate>,
+    seasonality: SeasonalityRegistry,
 }
 
 // Internal struct to track state during solving
@@ -20,6 +80,7 @@ struct MonthSlot {
     value: f64,
     origin: DataOrigin,
     source: Option<SourceMetadata>,
+    override_lineage: Option<OverrideLineage>,
     derivation_logic: String,
     original_period_info: Option<(f64, NaiveDate, NaiveDate)>,
 }
@@ -28,10 +89,124 @@ impl Densifier {
     pub fn new(fiscal_year_end_month: u32) -> Self {
         Self {
             fiscal_year_end_month,
+            seed: None,
+            noise_enabled: true,
+            solver: IncomeStatementSolver::Hierarchical,
+            history_start: None,
+            history_end: None,
+            seasonality: SeasonalityRegistry::new(),
         }
     }
 
+    /// Makes noise generation reproducible.
+    ///
+    /// Every account draws from its own stream, derived from this seed and the account
+    /// name, so adding or removing an account never reshuffles the noise on the others.
+    /// The streams come from ChaCha8 and `rand_distr` only changes sampled values in a
+    /// breaking release, so a `cargo update` leaves seeded output unchanged.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Ignores every account's `noise_factor`, producing the pure interpolation/allocation.
+    pub fn without_noise(mut self) -> Self {
+        self.noise_enabled = false;
+        self
+    }
+
+    pub fn with_solver(mut self, solver: IncomeStatementSolver) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    /// Resolves [`SeasonalityProfileId::Named`] profiles in `registry` instead of the built-ins.
+    pub fn with_seasonality_registry(mut self, registry: SeasonalityRegistry) -> Self {
+        self.seasonality = registry;
+        self
+    }
+
+    /// Extends balance sheet accounts to the months from `start` to `end`, following each
+    /// account's [`ExtrapolationPolicy`]. Either bound may be left open.
+    ///
+    /// [`densify_config`](Self::densify_config) falls back to the config's own
+    /// `history_start`/`history_end` for any bound not set here.
+    pub fn with_history_window(mut self, start: Option<NaiveDate>, end: Option<NaiveDate>) -> Self {
+        self.history_start = start;
+        self.history_end = end;
+        self
+    }
+
+    /// Densifies every balance sheet and income statement account in the config.
+    ///
+    /// Income statement accounts come first, so driver-linked balance sheet accounts can
+    /// follow them.
+    pub fn densify_config(
+        &self,
+        config: &FinancialHistoryConfig,
+    ) -> Result<BTreeMap<String, DenseSeries>> {
+        let mut data = BTreeMap::new();
+        let window = (
+            self.history_start.or(config.history_start),
+            self.history_end.or(config.history_end),
+        );
+
+        for account in &config.income_statement {
+            let series = self.densify_income_statement(account)?;
+            data.insert(account.name.clone(), series);
+        }
+
+        let mut balance_sheet = Vec::with_capacity(config.balance_sheet.len());
+        for account in &config.balance_sheet {
+            let series = self.densify_balance_sheet_within(account, window, &data)?;
+            balance_sheet.push((account.name.clone(), series));
+        }
+        data.extend(balance_sheet);
+
+        Ok(data)
+    }
+
+    fn noise_factor(&self, account_noise: f64) -> f64 {
+        if self.noise_enabled {
+            account_noise
+        } else {
+            0.0
+        }
+    }
+
+    /// ChaCha8's output is fixed by its specification, unlike `StdRng`, whose algorithm may
+    /// change between `rand` releases.
+    fn account_rng(&self, account_name: &str) -> ChaCha8Rng {
+        match self.seed {
+            Some(seed) => ChaCha8Rng::seed_from_u64(derive_account_seed(seed, account_name)),
+            None => ChaCha8Rng::from_rng(&mut rng()),
+        }
+    }
+
+    /// Densifies a balance sheet account on its own. A driver-linked account has no driver
+    /// series here and is interpolated linearly; see
+    /// [`densify_balance_sheet_with_drivers`](Self::densify_balance_sheet_with_drivers).
     pub fn densify_balance_sheet(&self, account: &BalanceSheetAccount) -> Result<DenseSeries> {
+        self.densify_balance_sheet_with_drivers(account, &BTreeMap::new())
+    }
+
+    /// Densifies a balance sheet account, looking up the income statement series of a
+    /// [`InterpolationMethod::DriverLinked`] account in `drivers` by name.
+    pub fn densify_balance_sheet_with_drivers(
+        &self,
+        account: &BalanceSheetAccount,
+        drivers: &BTreeMap<String, DenseSeries>,
+    ) -> Result<DenseSeries> {
+        let window = (self.history_start, self.history_end);
+        self.densify_balance_sheet_within(account, window, drivers)
+    }
+
+    fn densify_balance_sheet_within(
+        &self,
+        account: &BalanceSheetAccount,
+        (history_start, history_end): (Option<NaiveDate>, Option<NaiveDate>),
+        drivers: &BTreeMap<String, DenseSeries>,
+    ) -> Result<DenseSeries> {
         if account.snapshots.is_empty() {
             return Ok(BTreeMap::new());
         }
@@ -39,38 +214,82 @@ impl Densifier {
         let mut snapshots = account.snapshots.clone();
         snapshots.sort_by_key(|s| s.date);
 
-        // CatmullRom needs outer "ghost" points; financial snapshots typically don't have them,
-        // leading to zeros at the ends. Cosine uses only the two bounding points for a smooth fit.
-        let interpolation = match account.method {
-            InterpolationMethod::Step => Interpolation::Step(0.0),
-            InterpolationMethod::Curve => Interpolation::Cosine,
-            InterpolationMethod::Linear => Interpolation::Linear,
-        };
-
-        let keys: Vec<Key<f64, f64>> = snapshots
+        let points: Vec<(f64, f64)> = snapshots
             .iter()
             .map(|s| {
                 let t = s.date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as f64;
-                Key::new(t, s.value, interpolation)
+                (t, s.value)
             })
             .collect();
 
-        let spline = Spline::from_vec(keys);
+        let curve = BalanceCurve::new(&account.method, &points);
+        let driver_link = match &account.method {
+            InterpolationMethod::DriverLinked { drivers: names } => {
+                let link = names
+                    .iter()
+                    .map(|name| drivers.get(name))
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|series| DriverLink::new(&snapshots, &series));
+                if link.is_none() {
+                    warn!(
+                        "'{}': drivers {:?} are missing or zero in a snapshot month; interpolating linearly",
+                        account.name, names
+                    );
+                }
+                link.map(|link| (link, names.join(" + ")))
+            }
+            _ => None,
+        };
+        let fallback_logic = match (&account.method, &driver_link) {
+            (InterpolationMethod::DriverLinked { .. }, Some(_)) => {
+                "Interpolated linearly, no driver value this month".to_string()
+            }
+            (InterpolationMethod::DriverLinked { .. }, None) => {
+                "Interpolated linearly, drivers unavailable".to_string()
+            }
+            (method, _) => format!("Interpolated using {:?} method", method),
+        };
 
-        let start = snapshots.first().unwrap().date;
-        let end = snapshots.last().unwrap().date;
+        let first = snapshots.first().unwrap();
+        let last = snapshots.last().unwrap();
+        let start = history_start.map_or(first.date, |d| d.min(first.date));
+        let end = history_end
+            .map(|d| last_day_of_month(d.year(), d.month()))
+            .map_or(last.date, |d| d.max(last.date));
         let dates = get_month_ends_in_period(start, end);
 
         let mut series = BTreeMap::new();
-        let mut rng = rng();
-        let noise_factor = account.noise_factor;
+        let mut rng = self.account_rng(&account.name);
+        let noise_factor = self.noise_factor(account.noise_factor);
 
         for date in dates {
             let t = date.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() as f64;
 
             let exact_match = snapshots.iter().find(|s| s.date == date);
 
-            let (value, origin, source, derivation) = if let Some(snap) = exact_match {
+            let (value, origin, source, derivation) = if date < first.date || date > last.date {
+                let (anchor, neighbour, side) = if date < first.date {
+                    (first, snapshots.get(1), "before first")
+                } else {
+                    let neighbour = snapshots.len().checked_sub(2).map(|i| &snapshots[i]);
+                    (last, neighbour, "after last")
+                };
+                (
+                    extrapolate(account.extrapolation, anchor, neighbour, date),
+                    DataOrigin::Extrapolated,
+                    None,
+                    DerivationDetails {
+                        original_period_value: None,
+                        period_start: None,
+                        period_end: None,
+                        logic: format!(
+                            "Extrapolated {} snapshot using {:?} policy",
+                            side, account.extrapolation
+                        ),
+                        balancing_strategy: None,
+                    },
+                )
+            } else if let Some(snap) = exact_match {
                 (
                     snap.value,
                     DataOrigin::Anchor,
@@ -80,10 +299,19 @@ impl Densifier {
                         period_start: None,
                         period_end: None,
                         logic: "Exact snapshot match from document".to_string(),
+                        balancing_strategy: None,
                     },
                 )
             } else {
-                let mut val = spline.clamped_sample(t).unwrap_or(0.0);
+                let linked = driver_link
+                    .as_ref()
+                    .and_then(|(link, names)| Some((link.sample(date)?, names)));
+                let (mut val, logic) = match linked {
+                    Some(((value, days), names)) => {
+                        (value, format!("Linked to {} at {:.1} days", names, days))
+                    }
+                    None => (curve.sample(t), fallback_logic.clone()),
+                };
                 if noise_factor > 0.0 {
                     let normal = Normal::new(0.0, noise_factor).unwrap();
                     val *= 1.0 + normal.sample(&mut rng);
@@ -96,17 +324,25 @@ impl Densifier {
                         original_period_value: None,
                         period_start: None,
                         period_end: None,
-                        logic: format!("Interpolated using {:?} method", account.method),
+                        logic,
+                        balancing_strategy: None,
                     },
                 )
             };
 
+            // Only an exact snapshot carries the override that wrote it
+            let override_lineage = match origin {
+                DataOrigin::Anchor => exact_match.and_then(|s| s.override_lineage.clone()),
+                _ => None,
+            };
+
             series.insert(
                 date,
                 MonthlyDataPoint {
                     value,
                     origin,
                     source,
+                    override_lineage,
                     derivation,
                 },
             );
@@ -119,8 +355,19 @@ impl Densifier {
         &self,
         account: &IncomeStatementAccount,
     ) -> Result<DenseSeries> {
+        Ok(self.solve_income_statement(account)?.series)
+    }
+
+    /// Densifies an income statement account and reports how well each constraint was met.
+    pub fn solve_income_statement(
+        &self,
+        account: &IncomeStatementAccount,
+    ) -> Result<IncomeStatementSolution> {
         if account.constraints.is_empty() {
-            return Ok(BTreeMap::new());
+            return Ok(IncomeStatementSolution {
+                series: BTreeMap::new(),
+                constraints: Vec::new(),
+            });
         }
 
         // Resolve period strings into date ranges up front
@@ -143,7 +390,7 @@ impl Densifier {
 
         let all_dates = get_month_ends_in_period(global_start, global_end);
 
-        let calendar_weights = self.get_calendar_weights(&account.seasonality_profile)?;
+        let calendar_weights = self.get_calendar_weights(account)?;
 
         let mut grid: BTreeMap<NaiveDate, MonthSlot> = BTreeMap::new();
         for date in &all_dates {
@@ -156,6 +403,7 @@ impl Densifier {
                     value: 0.0,
                     origin: DataOrigin::Interpolated,
                     source: None,
+                    override_lineage: None,
                     derivation_logic: "Implied zero (no coverage)".to_string(),
                     original_period_info: None,
                 },
@@ -164,10 +412,65 @@ impl Densifier {
 
         resolved_constraints.sort_by_key(|(s, e, _)| (*e - *s).num_days());
 
-        let mut rng = rng();
-        let noise = account.noise_factor;
+        match self.solver {
+            IncomeStatementSolver::Hierarchical => {
+                self.allocate_hierarchical(account, &resolved_constraints, &mut grid)
+            }
+            IncomeStatementSolver::LeastSquares => {
+                self.allocate_least_squares(account, &resolved_constraints, &mut grid)
+            }
+        }
 
-        for (start_date, end_date, constraint) in resolved_constraints {
+        let series: DenseSeries = grid
+            .into_iter()
+            .map(|(k, v)| {
+                (
+                    k,
+                    MonthlyDataPoint {
+                        value: v.value,
+                        origin: v.origin,
+                        source: v.source,
+                        override_lineage: v.override_lineage,
+                        derivation: DerivationDetails {
+                            original_period_value: v.original_period_info.map(|x| x.0),
+                            period_start: v.original_period_info.map(|x| x.1),
+                            period_end: v.original_period_info.map(|x| x.2),
+                            logic: v.derivation_logic,
+                            balancing_strategy: None,
+                        },
+                    },
+                )
+            })
+            .collect();
+
+        let constraints = evaluate_constraints(account, &series)?;
+        for c in constraints
+            .iter()
+            .filter(|c| c.status == ConstraintStatus::Conflicting)
+        {
+            warn!(
+                "{}: constraint {} requested {:.2} but the solution totals {:.2} (residual {:+.2})",
+                account.name, c.period, c.requested, c.achieved, c.residual
+            );
+        }
+
+        Ok(IncomeStatementSolution {
+            series,
+            constraints,
+        })
+    }
+
+    /// Smallest periods first; each constraint only fills the months that are still unlocked.
+    fn allocate_hierarchical(
+        &self,
+        account: &IncomeStatementAccount,
+        resolved_constraints: &[(NaiveDate, NaiveDate, &PeriodConstraint)],
+        grid: &mut BTreeMap<NaiveDate, MonthSlot>,
+    ) {
+        let mut rng = self.account_rng(&account.name);
+        let noise = self.noise_factor(account.noise_factor);
+
+        for &(start_date, end_date, constraint) in resolved_constraints {
             let constraint_dates = get_month_ends_in_period(start_date, end_date);
 
             // Identify single-month constraints explicitly
@@ -249,6 +552,7 @@ impl Densifier {
                     slot.value = final_val;
                     slot.locked = true;
                     slot.source = constraint.source.clone();
+                    slot.override_lineage = constraint.override_lineage.clone();
 
                     if is_single_month {
                         slot.origin = DataOrigin::Anchor;
@@ -267,31 +571,103 @@ impl Densifier {
                 }
             }
         }
+    }
 
-        let result: DenseSeries = grid
-            .into_iter()
-            .map(|(k, v)| {
-                (
-                    k,
-                    MonthlyDataPoint {
-                        value: v.value,
-                        origin: v.origin,
-                        source: v.source,
-                        derivation: DerivationDetails {
-                            original_period_value: v.original_period_info.map(|x| x.0),
-                            period_start: v.original_period_info.map(|x| x.1),
-                            period_end: v.original_period_info.map(|x| x.2),
-                            logic: v.derivation_logic,
-                        },
-                    },
-                )
+    /// Solves every constraint simultaneously: the allocation closest to the seasonality
+    /// weights that meets all constraints, or the least-squares compromise if they conflict.
+    /// Each month is attributed to the smallest constraint covering it.
+    fn allocate_least_squares(
+        &self,
+        account: &IncomeStatementAccount,
+        resolved_constraints: &[(NaiveDate, NaiveDate, &PeriodConstraint)],
+        grid: &mut BTreeMap<NaiveDate, MonthSlot>,
+    ) {
+        let mut rng = self.account_rng(&account.name);
+        let noise = self.noise_factor(account.noise_factor);
+
+        let dates: Vec<NaiveDate> = grid.keys().copied().collect();
+
+        // Noise perturbs the target shape rather than the result, so constraints still hold
+        let weights: Vec<f64> = dates
+            .iter()
+            .map(|d| {
+                let base = grid[d].weight;
+                let perturbed = if noise > 0.0 {
+                    let normal = Normal::new(0.0, noise).unwrap();
+                    base * (1.0 + normal.sample(&mut rng))
+                } else {
+                    base
+                };
+                perturbed.max(MIN_SOLVER_WEIGHT)
             })
             .collect();
-        Ok(result)
+
+        let memberships: Vec<Vec<usize>> = resolved_constraints
+            .iter()
+            .map(|(start, end, _)| {
+                dates
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, d)| *d >= start && *d <= end)
+                    .map(|(i, _)| i)
+                    .collect()
+            })
+            .collect();
+        let targets: Vec<f64> = resolved_constraints
+            .iter()
+            .map(|(_, _, c)| c.value)
+            .collect();
+
+        let values = solve_weighted_min_norm(&memberships, &targets, &weights);
+
+        for (i, date) in dates.iter().enumerate() {
+            let slot = grid.get_mut(date).unwrap();
+            slot.value = values[i];
+
+            // Constraints are sorted by duration, so the first hit is the most specific one
+            let covering = resolved_constraints
+                .iter()
+                .zip(&memberships)
+                .find(|(_, months)| months.contains(&i))
+                .map(|(c, _)| c);
+
+            if let Some(&(start_date, end_date, constraint)) = covering {
+                slot.locked = true;
+                slot.source = constraint.source.clone();
+                slot.override_lineage = constraint.override_lineage.clone();
+
+                if start_date.year() == end_date.year() && start_date.month() == end_date.month() {
+                    slot.origin = DataOrigin::Anchor;
+                    slot.derivation_logic = "Direct monthly match".to_string();
+                    slot.original_period_info = None;
+                } else {
+                    slot.origin = DataOrigin::Allocated;
+                    let days_diff = (end_date - start_date).num_days();
+                    let period_type = if days_diff > 360 { "Annual" } else { "Period" };
+                    slot.derivation_logic = format!(
+                        "Least-squares allocation of {} total (Seasonality: {:?})",
+                        period_type, account.seasonality_profile
+                    );
+                    slot.original_period_info = Some((constraint.value, start_date, end_date));
+                }
+            }
+        }
     }
 
-    fn get_calendar_weights(&self, profile: &SeasonalityProfileId) -> Result<Vec<f64>> {
-        let base_weights = get_profile_weights(profile)?;
+    fn get_calendar_weights(&self, account: &IncomeStatementAccount) -> Result<Vec<f64>> {
+        if account.seasonality_profile == SeasonalityProfileId::Learned {
+            let fy_weights = infer_profile(&account.constraints, self.fiscal_year_end_month)
+                .unwrap_or_else(|| {
+                    warn!(
+                        "'{}': no complete fiscal year of monthly values to learn seasonality from; using Flat",
+                        account.name
+                    );
+                    vec![1.0 / 12.0; 12]
+                });
+            return Ok(self.align_weights_to_calendar(&fy_weights));
+        }
+
+        let base_weights = self.seasonality.weights(&account.seasonality_profile)?;
         let fy_weights = rotate_weights_for_fiscal_year(&base_weights, self.fiscal_year_end_month);
         Ok(self.align_weights_to_calendar(&fy_weights))
     }
@@ -312,26 +688,135 @@ impl Densifier {
     }
 }
 
-pub fn process_config(config: &FinancialHistoryConfig) -> Result<BTreeMap<String, DenseSeries>> {
-    let densifier = Densifier::new(config.fiscal_year_end_month);
-    let mut data = BTreeMap::new();
+/// Mixes the global seed with the account name using FNV-1a.
+/// Unlike `DefaultHasher`, the result is stable across platforms and Rust releases.
+fn derive_account_seed(seed: u64, account_name: &str) -> u64 {
+    const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    seed.to_le_bytes()
+        .iter()
+        .chain(account_name.as_bytes())
+        .fold(FNV_OFFSET_BASIS, |hash, byte| {
+            (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME)
+        })
+}
 
-    for account in &config.balance_sheet {
-        let series = densifier.densify_balance_sheet(account)?;
-        data.insert(account.name.clone(), series);
+/// Checks every constraint on `account` against a densified series.
+///
+/// Works on any series, including one that has since been clipped or rounded, which is why
+/// it re-derives which months each constraint controlled instead of asking the solver.
+pub fn evaluate_constraints(
+    account: &IncomeStatementAccount,
+    series: &DenseSeries,
+) -> Result<Vec<ConstraintOutcome>> {
+    evaluate_constraints_with_slack(account, series, 0.0)
+}
+
+/// `per_month_slack` widens the tolerance for series rounded after solving.
+pub(crate) fn evaluate_constraints_with_slack(
+    account: &IncomeStatementAccount,
+    series: &DenseSeries,
+    per_month_slack: f64,
+) -> Result<Vec<ConstraintOutcome>> {
+    let mut resolved = Vec::new();
+    for c in &account.constraints {
+        let (start, end) = c.resolve_dates()?;
+        resolved.push((start, end, c));
     }
 
-    for account in &config.income_statement {
-        let series = densifier.densify_income_statement(account)?;
-        data.insert(account.name.clone(), series);
+    // Both solvers attribute a month to the smallest constraint covering it
+    let mut by_duration: Vec<usize> = (0..resolved.len()).collect();
+    by_duration.sort_by_key(|&i| (resolved[i].1 - resolved[i].0).num_days());
+    let mut owner: BTreeMap<NaiveDate, usize> = BTreeMap::new();
+    for &i in &by_duration {
+        for date in get_month_ends_in_period(resolved[i].0, resolved[i].1) {
+            owner.entry(date).or_insert(i);
+        }
     }
 
-    Ok(data)
+    let outcomes = resolved
+        .iter()
+        .enumerate()
+        .map(|(i, (start, end, constraint))| {
+            let months = get_month_ends_in_period(*start, *end);
+            let value_of = |d: &NaiveDate| series.get(d).map(|p| p.value).unwrap_or(0.0);
+
+            let achieved: f64 = months.iter().map(value_of).sum();
+            let residual = achieved - constraint.value;
+            let tolerance = CONSTRAINT_TOLERANCE + per_month_slack * months.len() as f64;
+
+            let owns_months = months.iter().any(|d| owner.get(d) == Some(&i));
+            let inherited: f64 = months
+                .iter()
+                .filter(|d| owner.get(d) != Some(&i))
+                .map(value_of)
+                .sum();
+            let remaining = constraint.value - inherited;
+
+            let status = if !months.iter().all(|d| series.contains_key(d)) {
+                ConstraintStatus::OutOfRange
+            } else if residual.abs() > tolerance {
+                ConstraintStatus::Conflicting
+            } else if !owns_months {
+                ConstraintStatus::OverDetermined
+            } else if remaining * constraint.value < 0.0 && remaining.abs() > tolerance {
+                ConstraintStatus::Conflicting
+            } else {
+                ConstraintStatus::Satisfied
+            };
+
+            ConstraintOutcome {
+                period: constraint.period.clone(),
+                requested: constraint.value,
+                achieved,
+                residual,
+                status,
+            }
+        })
+        .collect();
+
+    Ok(outcomes)
+}
+
+/// Value of a balance sheet account at `date`, outside its snapshots, extrapolated from
+/// `anchor` (the nearest snapshot) and `neighbour` (the one next to it, for the trend).
+fn extrapolate(
+    policy: ExtrapolationPolicy,
+    anchor: &BalanceSheetSnapshot,
+    neighbour: Option<&BalanceSheetSnapshot>,
+    date: NaiveDate,
+) -> f64 {
+    match (policy, neighbour) {
+        (ExtrapolationPolicy::Zero, _) => 0.0,
+        (ExtrapolationPolicy::LinearTrend, Some(neighbour)) if neighbour.date != anchor.date => {
+            let slope =
+                (anchor.value - neighbour.value) / (anchor.date - neighbour.date).num_days() as f64;
+            anchor.value + slope * (date - anchor.date).num_days() as f64
+        }
+        _ => anchor.value,
+    }
+}
+
+pub fn process_config(config: &FinancialHistoryConfig) -> Result<BTreeMap<String, DenseSeries>> {
+    Densifier::new(config.fiscal_year_end_month).densify_config(config)
+}
+
+/// Same as [`process_config`], but noise is drawn from streams derived from `seed`,
+/// so repeated runs produce bit-identical output.
+pub fn process_config_with_seed(
+    config: &FinancialHistoryConfig,
+    seed: u64,
+) -> Result<BTreeMap<String, DenseSeries>> {
+    Densifier::new(config.fiscal_year_end_month)
+        .with_seed(seed)
+        .densify_config(config)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::error::FinancialHistoryError;
 
     #[test]
     fn test_hierarchical_constraint_solving() {
@@ -344,16 +829,19 @@ mod tests {
                     period: "2023-02".to_string(),
                     value: 5000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: "2023-01:2023-03".to_string(),
                     value: 13000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: "2023-01:2023-12".to_string(),
                     value: 50000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             noise_factor: 0.0,
@@ -413,16 +901,19 @@ mod tests {
             category: None,
             account_type: AccountType::Asset,
             method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
             snapshots: vec![
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 200000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             is_balancing_account: false,
@@ -447,26 +938,151 @@ mod tests {
         assert!((last - 200000.0).abs() < 0.01);
     }
 
+    #[test]
+    fn test_balance_sheet_extrapolation_policies() {
+        let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        let mut account = BalanceSheetAccount {
+            name: "Loan".to_string(),
+            category: None,
+            account_type: AccountType::Liability,
+            method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
+            snapshots: vec![
+                BalanceSheetSnapshot {
+                    date: date(2023, 6, 30),
+                    value: 100.0,
+                    source: None,
+                    override_lineage: None,
+                },
+                BalanceSheetSnapshot {
+                    date: date(2023, 12, 31),
+                    value: 160.0,
+                    source: None,
+                    override_lineage: None,
+                },
+            ],
+            is_balancing_account: false,
+            noise_factor: 0.05,
+        };
+        let densifier = Densifier::new(12)
+            .with_seed(1)
+            .with_history_window(Some(date(2023, 1, 1)), Some(date(2024, 3, 15)));
+
+        let series = densifier.densify_balance_sheet(&account).unwrap();
+        assert_eq!(series.len(), 15);
+        let jan = &series[&date(2023, 1, 31)];
+        assert_eq!(jan.origin, DataOrigin::Extrapolated);
+        assert_eq!(jan.value, 100.0);
+        assert_eq!(series[&date(2024, 3, 31)].value, 160.0);
+        assert_eq!(series[&date(2023, 6, 30)].origin, DataOrigin::Anchor);
+
+        account.extrapolation = ExtrapolationPolicy::Zero;
+        let series = densifier.densify_balance_sheet(&account).unwrap();
+        assert_eq!(series[&date(2023, 5, 31)].value, 0.0);
+        assert_eq!(series[&date(2024, 1, 31)].value, 0.0);
+
+        account.extrapolation = ExtrapolationPolicy::LinearTrend;
+        let series = densifier.densify_balance_sheet(&account).unwrap();
+        let expected_jan = 100.0 - 60.0 * 150.0 / 184.0;
+        assert!((series[&date(2023, 1, 31)].value - expected_jan).abs() < 1e-9);
+        assert!(series[&date(2024, 3, 31)].value > 160.0);
+
+        // Without a window the series still spans only the snapshots
+        let series = Densifier::new(12).densify_balance_sheet(&account).unwrap();
+        assert_eq!(series.len(), 7);
+    }
+
+    #[test]
+    fn test_driver_linked_receivables_follow_revenue() {
+        let date = |m| crate::utils::last_day_of_month(2023, m);
+        let receivables = BalanceSheetAccount {
+            name: "Accounts Receivable".to_string(),
+            category: None,
+            account_type: AccountType::Asset,
+            method: InterpolationMethod::DriverLinked {
+                drivers: vec!["Sales".to_string()],
+            },
+            extrapolation: ExtrapolationPolicy::Hold,
+            snapshots: vec![
+                BalanceSheetSnapshot {
+                    date: date(1),
+                    value: 5000.0,
+                    source: None,
+                    override_lineage: None,
+                },
+                BalanceSheetSnapshot {
+                    date: date(12),
+                    value: 30000.0,
+                    source: None,
+                    override_lineage: None,
+                },
+            ],
+            is_balancing_account: false,
+            noise_factor: 0.0,
+        };
+        let sales = IncomeStatementAccount {
+            name: "Sales".to_string(),
+            account_type: AccountType::Revenue,
+            seasonality_profile: SeasonalityProfileId::RetailPeak,
+            constraints: vec![PeriodConstraint {
+                period: "2023-01:2023-12".to_string(),
+                value: 120000.0,
+                source: None,
+                override_lineage: None,
+            }],
+            noise_factor: 0.0,
+        };
+        let config = FinancialHistoryConfig {
+            organization_name: "Driver Test".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![receivables],
+            income_statement: vec![sales],
+        };
+
+        let data = Densifier::new(12).densify_config(&config).unwrap();
+        let ar = &data["Accounts Receivable"];
+        assert_eq!(ar[&date(1)].value, 5000.0);
+        assert_eq!(ar[&date(12)].value, 30000.0);
+
+        // Days outstanding drift smoothly, so balances track the flat Jan-Nov revenue
+        // rather than jumping towards December's balance
+        let nov = ar[&date(11)].value;
+        let linear_nov = 5000.0 + 25000.0 * 304.0 / 334.0;
+        assert!(
+            nov < linear_nov / 2.0,
+            "November AR {} should follow revenue",
+            nov
+        );
+        assert!(ar[&date(6)].derivation.logic.starts_with("Linked to Sales"));
+    }
+
     #[test]
     fn test_process_config() {
         let config = FinancialHistoryConfig {
             organization_name: "Test Corp".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 50000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 75000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: true,
@@ -480,6 +1096,7 @@ mod tests {
                     period: "2023-01:2023-12".to_string(),
                     value: 120000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.0,
             }],
@@ -498,4 +1115,197 @@ mod tests {
             .sum();
         assert!((revenue_sum - 120000.0).abs() < 0.01);
     }
+
+    fn revenue_with(constraints: &[(&str, f64)]) -> IncomeStatementAccount {
+        IncomeStatementAccount {
+            name: "Revenue".to_string(),
+            account_type: AccountType::Revenue,
+            seasonality_profile: SeasonalityProfileId::Flat,
+            constraints: constraints
+                .iter()
+                .map(|(period, value)| PeriodConstraint {
+                    period: period.to_string(),
+                    value: *value,
+                    source: None,
+                    override_lineage: None,
+                })
+                .collect(),
+            noise_factor: 0.0,
+        }
+    }
+
+    #[test]
+    fn test_learned_seasonality_allocates_annual_totals() {
+        let periods: Vec<String> = (1..=12).map(|m| format!("2023-{:02}", m)).collect();
+        let mut constraints: Vec<(&str, f64)> = periods
+            .iter()
+            .map(|p| (p.as_str(), if p.ends_with("-12") { 450.0 } else { 50.0 }))
+            .collect();
+        constraints.push(("2024-01:2024-12", 2000.0));
+        let mut account = revenue_with(&constraints);
+        account.seasonality_profile = SeasonalityProfileId::Learned;
+
+        let series = Densifier::new(12)
+            .densify_income_statement(&account)
+            .unwrap();
+        let at = |m| series[&crate::utils::last_day_of_month(2024, m)].value;
+        // 2023 put 450 of 1000 in December
+        assert!((at(12) - 900.0).abs() < 1e-6);
+        assert!((at(3) - 100.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_named_seasonality_uses_registry() {
+        let mut account = revenue_with(&[("2023-01:2023-12", 1200.0)]);
+        account.seasonality_profile = SeasonalityProfileId::Named("January Only".to_string());
+
+        assert!(matches!(
+            Densifier::new(12).densify_income_statement(&account),
+            Err(FinancialHistoryError::UnknownSeasonalityProfile(_))
+        ));
+
+        let mut weights = vec![0.0; 12];
+        weights[0] = 1.0;
+        let mut registry = SeasonalityRegistry::empty();
+        registry.insert("January Only", weights).unwrap();
+        // June fiscal year end: the weights stay on calendar January
+        let series = Densifier::new(6)
+            .without_noise()
+            .with_seasonality_registry(registry)
+            .densify_income_statement(&account)
+            .unwrap();
+        assert!((series[&crate::utils::last_day_of_month(2023, 1)].value - 1200.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_least_squares_satisfies_partially_overlapping_constraints() {
+        // Consistent with Jan-Mar 40k, Apr-Jun 20k, Jul-Sep 40k
+        let account = revenue_with(&[
+            ("2023-01:2023-06", 60000.0),
+            ("2023-04:2023-09", 60000.0),
+            ("2023-01:2023-09", 100000.0),
+        ]);
+
+        let solution = Densifier::new(12)
+            .with_solver(IncomeStatementSolver::LeastSquares)
+            .solve_income_statement(&account)
+            .unwrap();
+
+        assert_eq!(solution.series.len(), 9);
+        for residual in &solution.constraints {
+            assert!(
+                residual.residual.abs() < 1e-6,
+                "{} should be met exactly, residual {}",
+                residual.period,
+                residual.residual
+            );
+        }
+
+        // The hierarchical solver spreads H1 evenly, then has nothing left to adjust for Jan-Sep
+        let hierarchical = Densifier::new(12).solve_income_statement(&account).unwrap();
+        let jan_sep = &hierarchical.constraints[2];
+        assert!((jan_sep.residual + 10000.0).abs() < 0.01);
+        assert_eq!(jan_sep.status, ConstraintStatus::Conflicting);
+    }
+
+    #[test]
+    fn test_least_squares_reports_residuals_for_inconsistent_constraints() {
+        let account = revenue_with(&[
+            ("2023-01:2023-02", 300.0),
+            ("2023-01", 100.0),
+            ("2023-02", 100.0),
+        ]);
+
+        let solution = Densifier::new(12)
+            .with_solver(IncomeStatementSolver::LeastSquares)
+            .solve_income_statement(&account)
+            .unwrap();
+
+        let jan = solution.series[&NaiveDate::from_ymd_opt(2023, 1, 31).unwrap()].value;
+        let feb = solution.series[&NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()].value;
+        assert!((jan - 400.0 / 3.0).abs() < 1e-6);
+        assert!((feb - 400.0 / 3.0).abs() < 1e-6);
+
+        let residuals: Vec<(&str, f64)> = solution
+            .constraints
+            .iter()
+            .map(|r| (r.period.as_str(), r.residual))
+            .collect();
+        assert_eq!(residuals[0].0, "2023-01:2023-02");
+        assert!((residuals[0].1 + 100.0 / 3.0).abs() < 1e-6);
+        assert!((residuals[1].1 - 100.0 / 3.0).abs() < 1e-6);
+        assert!((residuals[2].1 - 100.0 / 3.0).abs() < 1e-6);
+        assert!(solution
+            .constraints
+            .iter()
+            .all(|c| c.status == ConstraintStatus::Conflicting));
+    }
+
+    fn noisy_revenue(name: &str) -> IncomeStatementAccount {
+        IncomeStatementAccount {
+            name: name.to_string(),
+            account_type: AccountType::Revenue,
+            seasonality_profile: SeasonalityProfileId::Flat,
+            constraints: vec![PeriodConstraint {
+                period: "2023-01:2023-12".to_string(),
+                value: 120000.0,
+                source: None,
+                override_lineage: None,
+            }],
+            noise_factor: 0.05,
+        }
+    }
+
+    #[test]
+    fn test_seeded_noise_is_reproducible() {
+        let account = noisy_revenue("Revenue");
+
+        let first = Densifier::new(12)
+            .with_seed(42)
+            .densify_income_statement(&account)
+            .unwrap();
+        let second = Densifier::new(12)
+            .with_seed(42)
+            .densify_income_statement(&account)
+            .unwrap();
+        let other_seed = Densifier::new(12)
+            .with_seed(7)
+            .densify_income_statement(&account)
+            .unwrap();
+
+        let bits = |series: &DenseSeries| -> Vec<u64> {
+            series.values().map(|p| p.value.to_bits()).collect()
+        };
+
+        assert_eq!(bits(&first), bits(&second));
+        assert_ne!(bits(&first), bits(&other_seed));
+    }
+
+    #[test]
+    fn test_account_noise_independent_of_other_accounts() {
+        let mut config = FinancialHistoryConfig {
+            organization_name: "Seed Corp".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![],
+            income_statement: vec![noisy_revenue("Revenue")],
+        };
+
+        let before = process_config_with_seed(&config, 42).unwrap();
+        config
+            .income_statement
+            .insert(0, noisy_revenue("Consulting"));
+        let after = process_config_with_seed(&config, 42).unwrap();
+
+        let revenue_before: Vec<u64> = before["Revenue"]
+            .values()
+            .map(|p| p.value.to_bits())
+            .collect();
+        let revenue_after: Vec<u64> = after["Revenue"]
+            .values()
+            .map(|p| p.value.to_bits())
+            .collect();
+        assert_eq!(revenue_before, revenue_after);
+    }
 }
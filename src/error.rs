@@ -22,6 +22,9 @@ pub enum FinancialHistoryError {
     #[error("Custom seasonality profile has invalid weights: {0}")]
     InvalidSeasonalityWeights(String),
 
+    #[error("Unknown seasonality profile '{0}'")]
+    UnknownSeasonalityProfile(String),
+
     #[error("Accounting equation violation on {date}: Assets ({assets}) != Liabilities ({liabilities}) + Equity ({equity}), difference: {difference}")]
     AccountingEquationViolation {
         date: NaiveDate,
@@ -37,12 +40,28 @@ pub enum FinancialHistoryError {
     #[error("Date calculation error: {0}")]
     DateError(String),
 
+    #[error("Invalid date format '{0}'")]
+    InvalidDateFormat(String),
+
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
 
     #[error("IO error: {0}")]
     IoError(#[from] std::io::Error),
 
+    #[error("CSV error: {0}")]
+    Csv(#[from] csv::Error),
+
+    #[error("Import failed: {0}")]
+    ImportFailed(String),
+
+    #[error("Override #{index} could not be applied: {reason}")]
+    OverrideFailed { index: usize, reason: String },
+
+    #[cfg(feature = "xlsx")]
+    #[error("XLSX error: {0}")]
+    Xlsx(#[from] rust_xlsxwriter::XlsxError),
+
     #[error("Gemini structured output error: {0}")]
     StructuredOutput(#[from] StructuredError),
 
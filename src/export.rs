@@ -0,0 +1,382 @@
+use crate::chart_of_accounts::{AccountEntry, ChartOfAccounts};
+use crate::error::{FinancialHistoryError, Result};
+use crate::schema::FinancialHistoryConfig;
+use crate::DenseSeries;
+use chrono::format::{Item, StrftimeItems};
+use chrono::NaiveDate;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+#[cfg(feature = "xlsx")]
+pub mod xlsx;
+
+/// Which statement's accounts to export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportScope {
+    #[default]
+    All,
+    IncomeStatement,
+    BalanceSheet,
+}
+
+/// Layout and number formatting for [`to_wide_csv`] and [`to_long_csv`].
+///
+/// ```rust,ignore
+/// let options = ExportOptions::new()
+///     .with_scope(ExportScope::BalanceSheet)
+///     .with_subtotals(true)
+///     .with_thousands_separator(',');
+/// write_wide_csv("balance_sheet.csv", &config, &dense, &options)?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportOptions {
+    pub scope: ExportScope,
+    /// Add a "Total {section}" row after each account type section (wide layout only).
+    pub subtotals: bool,
+    pub decimals: usize,
+    pub thousands_separator: Option<char>,
+    /// Write negatives as `(1,234.00)` instead of `-1,234.00`.
+    pub negative_parentheses: bool,
+    /// `chrono` format string for dates in headers and rows, checked by
+    /// [`ExportOptions::with_date_format`].
+    date_format: String,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            scope: ExportScope::All,
+            subtotals: false,
+            decimals: 2,
+            thousands_separator: None,
+            negative_parentheses: false,
+            date_format: "%Y-%m-%d".to_string(),
+        }
+    }
+}
+
+impl ExportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_scope(mut self, scope: ExportScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    pub fn with_subtotals(mut self, subtotals: bool) -> Self {
+        self.subtotals = subtotals;
+        self
+    }
+
+    pub fn with_decimals(mut self, decimals: usize) -> Self {
+        self.decimals = decimals;
+        self
+    }
+
+    pub fn with_thousands_separator(mut self, separator: char) -> Self {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    pub fn with_negative_parentheses(mut self, parentheses: bool) -> Self {
+        self.negative_parentheses = parentheses;
+        self
+    }
+
+    /// Fails on a format string `chrono` cannot render, such as `%Q`.
+    pub fn with_date_format(mut self, format: impl Into<String>) -> Result<Self> {
+        let format = format.into();
+        if StrftimeItems::new(&format).any(|item| item == Item::Error) {
+            return Err(FinancialHistoryError::InvalidDateFormat(format));
+        }
+        self.date_format = format;
+        Ok(self)
+    }
+
+    pub fn date_format(&self) -> &str {
+        &self.date_format
+    }
+
+    /// Formats a number according to these options.
+    pub fn format_value(&self, value: f64) -> String {
+        let rounded = format!("{:.*}", self.decimals, value.abs());
+        let negative = value < 0.0 && rounded.chars().any(|c| c.is_ascii_digit() && c != '0');
+
+        let (int_part, frac_part) = match rounded.split_once('.') {
+            Some((i, f)) => (i, Some(f)),
+            None => (rounded.as_str(), None),
+        };
+
+        let mut out = match self.thousands_separator {
+            Some(sep) => group_thousands(int_part, sep),
+            None => int_part.to_string(),
+        };
+        if let Some(frac) = frac_part {
+            out.push('.');
+            out.push_str(frac);
+        }
+
+        match (negative, self.negative_parentheses) {
+            (false, _) => out,
+            (true, true) => format!("({})", out),
+            (true, false) => format!("-{}", out),
+        }
+    }
+
+    fn format_date(&self, date: &NaiveDate) -> String {
+        date.format(&self.date_format).to_string()
+    }
+}
+
+/// An account type section in statement order, with its accounts.
+struct Section<'a> {
+    label: &'static str,
+    accounts: &'a [AccountEntry],
+}
+
+/// Income statement sections first, then the balance sheet. Accounts in `dense` that are not
+/// in the config (generated plugs, suspense) are listed under Equity.
+fn sections<'a>(chart: &'a ChartOfAccounts, scope: ExportScope) -> Vec<Section<'a>> {
+    let income = [
+        ("Revenue", &chart.revenue),
+        ("Cost of Sales", &chart.cost_of_sales),
+        ("Operating Expenses", &chart.operating_expenses),
+        ("Other Income", &chart.other_income),
+        ("Interest", &chart.interest),
+        ("Depreciation", &chart.depreciation),
+        ("Shareholder Salaries", &chart.shareholder_salaries),
+        ("Income Tax", &chart.income_tax),
+        ("Dividends", &chart.dividends),
+    ];
+    let balance = [
+        ("Assets", &chart.assets),
+        ("Liabilities", &chart.liabilities),
+        ("Equity", &chart.equity),
+    ];
+
+    let selected: Vec<(&'static str, &'a Vec<AccountEntry>)> = match scope {
+        ExportScope::All => income.into_iter().chain(balance).collect(),
+        ExportScope::IncomeStatement => income.into_iter().collect(),
+        ExportScope::BalanceSheet => balance.into_iter().collect(),
+    };
+
+    selected
+        .into_iter()
+        .filter(|(_, accounts)| !accounts.is_empty())
+        .map(|(label, accounts)| Section { label, accounts })
+        .collect()
+}
+
+fn exported_dates(
+    sections: &[Section],
+    dense_data: &BTreeMap<String, DenseSeries>,
+) -> BTreeSet<NaiveDate> {
+    sections
+        .iter()
+        .flat_map(|s| s.accounts.iter())
+        .filter_map(|a| dense_data.get(&a.name))
+        .flat_map(|series| series.keys().copied())
+        .collect()
+}
+
+/// One row per account and one column per month, grouped by account type section.
+/// Months an account has no value for are left empty.
+pub fn to_wide_csv(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> String {
+    let chart = ChartOfAccounts::from_dense_data(config, dense_data);
+    let sections = sections(&chart, options.scope);
+    let dates = exported_dates(&sections, dense_data);
+
+    let mut out = csv_writer();
+    let mut header = vec!["Section".to_string(), "Account".to_string()];
+    header.extend(dates.iter().map(|d| options.format_date(d)));
+    push_record(&mut out, &header);
+
+    for section in &sections {
+        let mut totals: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+
+        for account in section.accounts {
+            let series = dense_data.get(&account.name);
+            let mut record = vec![section.label.to_string(), account.name.clone()];
+            for date in &dates {
+                match series.and_then(|s| s.get(date)) {
+                    Some(point) => {
+                        *totals.entry(*date).or_insert(0.0) += point.value;
+                        record.push(options.format_value(point.value));
+                    }
+                    None => record.push(String::new()),
+                }
+            }
+            push_record(&mut out, &record);
+        }
+
+        if options.subtotals {
+            let mut record = vec![
+                section.label.to_string(),
+                format!("Total {}", section.label),
+            ];
+            record.extend(dates.iter().map(|d| {
+                totals
+                    .get(d)
+                    .map(|v| options.format_value(*v))
+                    .unwrap_or_default()
+            }));
+            push_record(&mut out, &record);
+        }
+    }
+
+    into_string(out)
+}
+
+/// One row per account and month with the value's provenance:
+/// `Section,Account,Date,Value,Origin,Source Document,Source Text,Logic`.
+pub fn to_long_csv(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> String {
+    let chart = ChartOfAccounts::from_dense_data(config, dense_data);
+
+    let mut out = csv_writer();
+    push_record(
+        &mut out,
+        &[
+            "Section",
+            "Account",
+            "Date",
+            "Value",
+            "Origin",
+            "Source Document",
+            "Source Text",
+            "Logic",
+        ]
+        .map(String::from),
+    );
+
+    for section in sections(&chart, options.scope) {
+        for account in section.accounts {
+            let Some(series) = dense_data.get(&account.name) else {
+                continue;
+            };
+            for (date, point) in series {
+                let source = point.source.as_ref();
+                push_record(
+                    &mut out,
+                    &[
+                        section.label.to_string(),
+                        account.name.clone(),
+                        options.format_date(date),
+                        options.format_value(point.value),
+                        format!("{:?}", point.origin),
+                        source.map(|s| s.document_name.clone()).unwrap_or_default(),
+                        source
+                            .and_then(|s| s.original_text.clone())
+                            .unwrap_or_default(),
+                        point.derivation.logic.clone(),
+                    ],
+                );
+            }
+        }
+    }
+
+    into_string(out)
+}
+
+pub fn write_wide_csv(
+    path: impl AsRef<Path>,
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> Result<()> {
+    std::fs::write(path, to_wide_csv(config, dense_data, options))?;
+    Ok(())
+}
+
+pub fn write_long_csv(
+    path: impl AsRef<Path>,
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> Result<()> {
+    std::fs::write(path, to_long_csv(config, dense_data, options))?;
+    Ok(())
+}
+
+fn group_thousands(digits: &str, separator: char) -> String {
+    let len = digits.len();
+    let mut out = String::with_capacity(len + len / 3);
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (len - i) % 3 == 0 {
+            out.push(separator);
+        }
+        out.push(c);
+    }
+    out
+}
+
+/// An in-memory CSV writer with `\n` line endings.
+fn csv_writer() -> csv::Writer<Vec<u8>> {
+    csv::WriterBuilder::new()
+        .terminator(csv::Terminator::Any(b'\n'))
+        .from_writer(Vec::new())
+}
+
+// Writing to a `Vec` cannot fail, and every field is a `String`, so the output is UTF-8.
+fn push_record(out: &mut csv::Writer<Vec<u8>>, fields: &[String]) {
+    out.write_record(fields).expect("writing CSV to memory");
+}
+
+fn into_string(out: csv::Writer<Vec<u8>>) -> String {
+    let bytes = out.into_inner().expect("flushing CSV to memory");
+    String::from_utf8(bytes).expect("CSV fields are UTF-8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_value() {
+        let plain = ExportOptions::new();
+        assert_eq!(plain.format_value(1234567.891), "1234567.89");
+        assert_eq!(plain.format_value(-0.001), "0.00");
+
+        let accounting = ExportOptions::new()
+            .with_decimals(0)
+            .with_thousands_separator(',')
+            .with_negative_parentheses(true);
+        assert_eq!(accounting.format_value(-1234567.6), "(1,234,568)");
+        assert_eq!(accounting.format_value(999.0), "999");
+        assert_eq!(accounting.format_value(1000.0), "1,000");
+    }
+
+    #[test]
+    fn test_bad_date_format_is_rejected() {
+        let options = ExportOptions::new().with_date_format("%b %Y").unwrap();
+        assert_eq!(options.date_format(), "%b %Y");
+        assert_eq!(
+            options.format_date(&NaiveDate::from_ymd_opt(2023, 1, 31).unwrap()),
+            "Jan 2023"
+        );
+
+        assert!(matches!(
+            ExportOptions::new().with_date_format("%Q"),
+            Err(FinancialHistoryError::InvalidDateFormat(format)) if format == "%Q"
+        ));
+    }
+
+    #[test]
+    fn test_push_record_quotes_only_when_needed() {
+        let mut out = csv_writer();
+        push_record(
+            &mut out,
+            &["Cash", "1,000.00", "Says \"hi\"", ""].map(String::from),
+        );
+        assert_eq!(into_string(out), "Cash,\"1,000.00\",\"Says \"\"hi\"\"\",\n");
+    }
+}
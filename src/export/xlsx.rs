@@ -0,0 +1,335 @@
+//! Excel workbook export (requires the `xlsx` feature).
+//!
+//! Writes a "Profit and Loss" sheet, a "Balance Sheet" sheet and a "Chart of Accounts" sheet.
+//! Statement cells are shaded by [`DataOrigin`] and carry a note with the value's derivation
+//! logic and source, so a reviewer can trace any number from inside Excel.
+
+use super::{exported_dates, sections, ExportOptions, ExportScope, Section};
+use crate::chart_of_accounts::ChartOfAccounts;
+use crate::error::Result;
+use crate::schema::FinancialHistoryConfig;
+use crate::{DataOrigin, DenseSeries, MonthlyDataPoint};
+use chrono::NaiveDate;
+use rust_xlsxwriter::utility::column_number_to_name;
+use rust_xlsxwriter::{ColNum, Color, Format, Note, Workbook, Worksheet};
+use std::collections::BTreeMap;
+use std::path::Path;
+
+const ACCOUNT_COLUMN_WIDTH: f64 = 36.0;
+const VALUE_COLUMN_WIDTH: f64 = 13.0;
+
+/// Background colour for cells of the given origin.
+pub fn origin_color(origin: &DataOrigin) -> Color {
+    match origin {
+        DataOrigin::Anchor => Color::RGB(0xC6EFCE),
+        DataOrigin::Interpolated => Color::RGB(0xDDEBF7),
+        DataOrigin::Extrapolated => Color::RGB(0xEDEDED),
+        DataOrigin::Allocated => Color::RGB(0xFFF2CC),
+        DataOrigin::BalancingPlug => Color::RGB(0xF8CBAD),
+        DataOrigin::Derived => Color::RGB(0xE4DFEC),
+    }
+}
+
+/// Builds the workbook described in the module docs and saves it to `path`.
+pub fn write_workbook(
+    path: impl AsRef<Path>,
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> Result<()> {
+    build_workbook(config, dense_data, options)?.save(path)?;
+    Ok(())
+}
+
+/// Same as [`write_workbook`], returning the `.xlsx` bytes instead of writing a file.
+pub fn workbook_to_buffer(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> Result<Vec<u8>> {
+    Ok(build_workbook(config, dense_data, options)?.save_to_buffer()?)
+}
+
+fn build_workbook(
+    config: &FinancialHistoryConfig,
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> Result<Workbook> {
+    let chart = ChartOfAccounts::from_dense_data(config, dense_data);
+    let mut workbook = Workbook::new();
+
+    let statements = [
+        ("Profit and Loss", ExportScope::IncomeStatement),
+        ("Balance Sheet", ExportScope::BalanceSheet),
+    ];
+    for (name, scope) in statements {
+        let sheet = workbook.add_worksheet();
+        sheet.set_name(name)?;
+        write_statement(sheet, &sections(&chart, scope), dense_data, options)?;
+    }
+
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("Chart of Accounts")?;
+    write_chart(sheet, &chart)?;
+
+    Ok(workbook)
+}
+
+fn write_statement(
+    sheet: &mut Worksheet,
+    sections: &[Section],
+    dense_data: &BTreeMap<String, DenseSeries>,
+    options: &ExportOptions,
+) -> Result<()> {
+    let dates: Vec<NaiveDate> = exported_dates(sections, dense_data).into_iter().collect();
+    let num_format = excel_number_format(options);
+    let header = Format::new().set_bold();
+    let total = Format::new().set_bold().set_num_format(&num_format);
+
+    sheet.write_string_with_format(0, 0, "Section", &header)?;
+    sheet.write_string_with_format(0, 1, "Account", &header)?;
+    for (i, date) in dates.iter().enumerate() {
+        sheet.write_string_with_format(0, value_column(i), options.format_date(date), &header)?;
+    }
+
+    let mut row = 1;
+    for section in sections {
+        let first_row = row;
+        for account in section.accounts {
+            sheet.write_string(row, 0, section.label)?;
+            sheet.write_string(row, 1, &account.name)?;
+
+            let series = dense_data.get(&account.name);
+            for (i, date) in dates.iter().enumerate() {
+                let Some(point) = series.and_then(|s| s.get(date)) else {
+                    continue;
+                };
+                let format = Format::new()
+                    .set_num_format(&num_format)
+                    .set_background_color(origin_color(&point.origin));
+                sheet.write_number_with_format(row, value_column(i), point.value, &format)?;
+                sheet.insert_note(row, value_column(i), &Note::new(cell_note(point)))?;
+            }
+            row += 1;
+        }
+
+        if options.subtotals {
+            sheet.write_string_with_format(row, 0, section.label, &header)?;
+            sheet.write_string_with_format(row, 1, format!("Total {}", section.label), &header)?;
+            for i in 0..dates.len() {
+                let column = column_number_to_name(value_column(i));
+                let formula = format!("=SUM({column}{}:{column}{})", first_row + 1, row);
+                sheet.write_formula_with_format(row, value_column(i), formula.as_str(), &total)?;
+            }
+            row += 1;
+        }
+    }
+
+    sheet.set_column_width(0, 20)?;
+    sheet.set_column_width(1, ACCOUNT_COLUMN_WIDTH)?;
+    for i in 0..dates.len() {
+        sheet.set_column_width(value_column(i), VALUE_COLUMN_WIDTH)?;
+    }
+    sheet.set_freeze_panes(1, 2)?;
+    Ok(())
+}
+
+fn write_chart(sheet: &mut Worksheet, chart: &ChartOfAccounts) -> Result<()> {
+    let header = Format::new().set_bold();
+    let columns = [
+        "Section",
+        "Account",
+        "Account Type",
+        "Balancing Account",
+        "Code",
+    ];
+    for (col, title) in columns.into_iter().enumerate() {
+        sheet.write_string_with_format(0, col as ColNum, title, &header)?;
+    }
+
+    let mut row = 1;
+    for section in sections(chart, ExportScope::All) {
+        for account in section.accounts {
+            sheet.write_string(row, 0, section.label)?;
+            sheet.write_string(row, 1, &account.name)?;
+            sheet.write_string(row, 2, format!("{:?}", account.account_type))?;
+            sheet.write_boolean(row, 3, account.is_balancing_account)?;
+            if let Some(code) = &account.code {
+                sheet.write_string(row, 4, code)?;
+            }
+            row += 1;
+        }
+    }
+
+    sheet.set_column_width(0, 20)?;
+    sheet.set_column_width(1, ACCOUNT_COLUMN_WIDTH)?;
+    sheet.set_column_width(2, 20)?;
+    sheet.set_column_width(3, 18)?;
+    sheet.set_freeze_panes(1, 0)?;
+    Ok(())
+}
+
+/// Note text for a statement cell: origin and logic, then the period total, override and
+/// source, if any.
+pub fn cell_note(point: &MonthlyDataPoint) -> String {
+    let derivation = &point.derivation;
+    let mut lines = vec![format!("{:?}: {}", point.origin, derivation.logic)];
+
+    if let (Some(start), Some(end)) = (derivation.period_start, derivation.period_end) {
+        match derivation.original_period_value {
+            Some(total) => lines.push(format!("Period {} to {} (total {:.2})", start, end, total)),
+            None => lines.push(format!("Period {} to {}", start, end)),
+        }
+    }
+    if let Some(strategy) = derivation.balancing_strategy {
+        lines.push(format!("Balancing strategy: {:?}", strategy));
+    }
+    if let Some(lineage) = &point.override_lineage {
+        lines.push(lineage.to_string());
+    }
+    if let Some(source) = &point.source {
+        match &source.original_text {
+            Some(text) => lines.push(format!(
+                "Source: document {} \"{}\"",
+                source.document_name, text
+            )),
+            None => lines.push(format!("Source: document {}", source.document_name)),
+        }
+    }
+
+    lines.join("\n")
+}
+
+fn excel_number_format(options: &ExportOptions) -> String {
+    let mut format = if options.thousands_separator.is_some() {
+        "#,##0".to_string()
+    } else {
+        "0".to_string()
+    };
+    if options.decimals > 0 {
+        format.push('.');
+        format.push_str(&"0".repeat(options.decimals));
+    }
+    if options.negative_parentheses {
+        format = format!("{format};({format})");
+    }
+    format
+}
+
+fn value_column(index: usize) -> ColNum {
+    (index + 2) as ColNum
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DerivationDetails, SourceMetadata};
+
+    #[test]
+    fn test_cell_note_includes_logic_period_and_source() {
+        let point = MonthlyDataPoint {
+            value: 100.0,
+            origin: DataOrigin::Allocated,
+            source: Some(SourceMetadata {
+                document_name: "2".to_string(),
+                original_text: Some("Sales".to_string()),
+            }),
+            override_lineage: None,
+            derivation: DerivationDetails {
+                original_period_value: Some(1200.0),
+                period_start: NaiveDate::from_ymd_opt(2023, 1, 1),
+                period_end: NaiveDate::from_ymd_opt(2023, 12, 31),
+                logic: "Allocated from annual total".to_string(),
+                balancing_strategy: None,
+            },
+        };
+
+        assert_eq!(
+            cell_note(&point),
+            "Allocated: Allocated from annual total\n\
+             Period 2023-01-01 to 2023-12-31 (total 1200.00)\n\
+             Source: document 2 \"Sales\""
+        );
+    }
+
+    #[test]
+    fn test_excel_number_format() {
+        assert_eq!(excel_number_format(&ExportOptions::new()), "0.00");
+        let accounting = ExportOptions::new()
+            .with_decimals(0)
+            .with_thousands_separator(',')
+            .with_negative_parentheses(true);
+        assert_eq!(excel_number_format(&accounting), "#,##0;(#,##0)");
+    }
+
+    #[test]
+    fn test_workbook_to_buffer_reads_back() {
+        use crate::schema::{AccountType, IncomeStatementAccount, SeasonalityProfileId};
+        use calamine::{open_workbook_from_rs, Data, Reader, Xlsx};
+        use std::io::Cursor;
+
+        let config = FinancialHistoryConfig {
+            organization_name: "Test".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![],
+            income_statement: vec![IncomeStatementAccount {
+                name: "Sales".to_string(),
+                account_type: AccountType::Revenue,
+                seasonality_profile: SeasonalityProfileId::Flat,
+                constraints: vec![],
+                noise_factor: 0.0,
+            }],
+        };
+        let point = |value: f64, origin: DataOrigin| MonthlyDataPoint {
+            value,
+            origin,
+            source: None,
+            override_lineage: None,
+            derivation: DerivationDetails {
+                original_period_value: None,
+                period_start: None,
+                period_end: None,
+                logic: "Test".to_string(),
+                balancing_strategy: None,
+            },
+        };
+        let january = NaiveDate::from_ymd_opt(2023, 1, 31).unwrap();
+        let mut dense = BTreeMap::new();
+        dense.insert(
+            "Sales".to_string(),
+            DenseSeries::from([(january, point(120.0, DataOrigin::Allocated))]),
+        );
+        dense.insert(
+            "Balancing Equity Adjustment".to_string(),
+            DenseSeries::from([(january, point(50.0, DataOrigin::BalancingPlug))]),
+        );
+
+        let options = ExportOptions::new().with_subtotals(true);
+        let bytes = workbook_to_buffer(&config, &dense, &options).unwrap();
+        let mut workbook: Xlsx<_> = open_workbook_from_rs(Cursor::new(bytes)).unwrap();
+        assert_eq!(
+            workbook.sheet_names(),
+            ["Profit and Loss", "Balance Sheet", "Chart of Accounts"]
+        );
+
+        let profit_and_loss = workbook.worksheet_range("Profit and Loss").unwrap();
+        assert_eq!(
+            profit_and_loss.get((0, 2)),
+            Some(&Data::String("2023-01-31".to_string()))
+        );
+        assert_eq!(
+            profit_and_loss.get((1, 1)),
+            Some(&Data::String("Sales".to_string()))
+        );
+        assert_eq!(profit_and_loss.get((1, 2)), Some(&Data::Float(120.0)));
+
+        let balance_sheet = workbook.worksheet_range("Balance Sheet").unwrap();
+        assert_eq!(
+            balance_sheet.get((1, 1)),
+            Some(&Data::String("Balancing Equity Adjustment".to_string()))
+        );
+        assert_eq!(balance_sheet.get((1, 2)), Some(&Data::Float(50.0)));
+    }
+}
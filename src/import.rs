@@ -0,0 +1,898 @@
+//! Trial balance file importers producing [`TrialBalanceRow`]s for [`crate::convert_tb_to_config`].
+//!
+//! ```rust,ignore
+//! let options = TrialBalanceImportOptions::new()
+//!     .with_columns(ColumnMapping::new("Account").with_code("Code").with_debit_credit("Debit", "Credit"))
+//!     .with_code_ranges(CodeRange::standard())
+//!     .with_date(NaiveDate::from_ymd_opt(2023, 6, 30).unwrap());
+//! let import = import_trial_balance_csv("tb_june.csv", &options)?;
+//! for error in &import.errors {
+//!     eprintln!("{}", error);
+//! }
+//! let config = convert_tb_to_config(&import.rows, "Acme".to_string(), 6);
+//! ```
+
+use crate::error::{FinancialHistoryError, Result};
+use crate::ingestion::TrialBalanceRow;
+use crate::schema::AccountType;
+use chrono::NaiveDate;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+
+pub mod reports;
+
+/// How a single amount column is signed in the export.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignConvention {
+    /// Every account is positive in its normal direction (debit for assets and expenses,
+    /// credit for liabilities, equity and income). This is what the config expects.
+    #[default]
+    NaturalBalance,
+    /// Debits positive, credits negative, as in a raw ledger export.
+    DebitPositive,
+    /// Credits positive, debits negative.
+    CreditPositive,
+}
+
+/// Where the amount lives in each row.
+#[derive(Debug, Clone, PartialEq)]
+pub enum AmountColumns {
+    /// One signed column, read with [`TrialBalanceImportOptions::sign_convention`].
+    Single(String),
+    /// Separate debit and credit columns; the net is debit minus credit.
+    DebitCredit { debit: String, credit: String },
+}
+
+/// Header names for each field. Matching is case-insensitive and ignores surrounding spaces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnMapping {
+    pub account_name: String,
+    pub account_code: Option<String>,
+    /// A column holding the account type or class (e.g. "Current Asset", "Expense").
+    pub account_type: Option<String>,
+    /// Per-row balance date. When absent, [`TrialBalanceImportOptions::date`] is used.
+    pub date: Option<String>,
+    pub amount: AmountColumns,
+}
+
+impl Default for ColumnMapping {
+    fn default() -> Self {
+        Self::new("Account")
+    }
+}
+
+impl ColumnMapping {
+    /// Maps the account name column, with "Debit" and "Credit" amount columns.
+    pub fn new(account_name: impl Into<String>) -> Self {
+        Self {
+            account_name: account_name.into(),
+            account_code: None,
+            account_type: None,
+            date: None,
+            amount: AmountColumns::DebitCredit {
+                debit: "Debit".to_string(),
+                credit: "Credit".to_string(),
+            },
+        }
+    }
+
+    pub fn with_code(mut self, column: impl Into<String>) -> Self {
+        self.account_code = Some(column.into());
+        self
+    }
+
+    pub fn with_type(mut self, column: impl Into<String>) -> Self {
+        self.account_type = Some(column.into());
+        self
+    }
+
+    pub fn with_date(mut self, column: impl Into<String>) -> Self {
+        self.date = Some(column.into());
+        self
+    }
+
+    pub fn with_amount(mut self, column: impl Into<String>) -> Self {
+        self.amount = AmountColumns::Single(column.into());
+        self
+    }
+
+    pub fn with_debit_credit(
+        mut self,
+        debit: impl Into<String>,
+        credit: impl Into<String>,
+    ) -> Self {
+        self.amount = AmountColumns::DebitCredit {
+            debit: debit.into(),
+            credit: credit.into(),
+        };
+        self
+    }
+}
+
+/// Assigns an [`AccountType`] to every account code in an inclusive numeric range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeRange {
+    pub from: u32,
+    pub to: u32,
+    pub account_type: AccountType,
+}
+
+impl CodeRange {
+    pub fn new(from: u32, to: u32, account_type: AccountType) -> Self {
+        Self {
+            from,
+            to,
+            account_type,
+        }
+    }
+
+    /// The common four-digit scheme: 1xxx assets, 2xxx liabilities, 3xxx equity, 4xxx revenue,
+    /// 5xxx cost of sales, 6xxx-7xxx operating expenses, 8xxx other income, 9xxx income tax.
+    pub fn standard() -> Vec<Self> {
+        vec![
+            Self::new(1000, 1999, AccountType::Asset),
+            Self::new(2000, 2999, AccountType::Liability),
+            Self::new(3000, 3999, AccountType::Equity),
+            Self::new(4000, 4999, AccountType::Revenue),
+            Self::new(5000, 5999, AccountType::CostOfSales),
+            Self::new(6000, 7999, AccountType::OperatingExpense),
+            Self::new(8000, 8999, AccountType::OtherIncome),
+            Self::new(9000, 9999, AccountType::IncomeTax),
+        ]
+    }
+
+    fn matches(&self, code: &str) -> bool {
+        let digits: String = code.chars().take_while(|c| c.is_ascii_digit()).collect();
+        digits
+            .parse::<u32>()
+            .is_ok_and(|n| (self.from..=self.to).contains(&n))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TrialBalanceImportOptions {
+    pub columns: ColumnMapping,
+    /// Only used with [`AmountColumns::Single`].
+    pub sign_convention: SignConvention,
+    /// Balance date for every row when the file has no date column.
+    pub date: Option<NaiveDate>,
+    /// `chrono` formats tried in order for the date column.
+    pub date_formats: Vec<String>,
+    /// Explicit types keyed by account code or account name. Checked first.
+    pub type_mapping: BTreeMap<String, AccountType>,
+    /// Checked after the mapping and the type column.
+    pub code_ranges: Vec<CodeRange>,
+    /// Recorded as each row's `source_doc`. Defaults to the file name.
+    pub source_doc: Option<String>,
+    pub delimiter: u8,
+}
+
+impl Default for TrialBalanceImportOptions {
+    fn default() -> Self {
+        Self {
+            columns: ColumnMapping::default(),
+            sign_convention: SignConvention::default(),
+            date: None,
+            date_formats: ["%Y-%m-%d", "%d/%m/%Y", "%d %b %Y", "%d-%b-%Y"]
+                .map(String::from)
+                .to_vec(),
+            type_mapping: BTreeMap::new(),
+            code_ranges: Vec::new(),
+            source_doc: None,
+            delimiter: b',',
+        }
+    }
+}
+
+impl TrialBalanceImportOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_columns(mut self, columns: ColumnMapping) -> Self {
+        self.columns = columns;
+        self
+    }
+
+    pub fn with_sign_convention(mut self, convention: SignConvention) -> Self {
+        self.sign_convention = convention;
+        self
+    }
+
+    pub fn with_date(mut self, date: NaiveDate) -> Self {
+        self.date = Some(date);
+        self
+    }
+
+    pub fn with_date_formats(mut self, formats: &[&str]) -> Self {
+        self.date_formats = formats.iter().map(|f| f.to_string()).collect();
+        self
+    }
+
+    pub fn with_type_mapping(mut self, mapping: BTreeMap<String, AccountType>) -> Self {
+        self.type_mapping = mapping;
+        self
+    }
+
+    pub fn with_code_ranges(mut self, ranges: Vec<CodeRange>) -> Self {
+        self.code_ranges = ranges;
+        self
+    }
+
+    pub fn with_source_doc(mut self, source_doc: impl Into<String>) -> Self {
+        self.source_doc = Some(source_doc.into());
+        self
+    }
+
+    pub fn with_delimiter(mut self, delimiter: u8) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+}
+
+/// A row that could not be imported. `line` is 1-based and counts the header.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RowError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for RowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+/// Imported rows plus the rows that were rejected. Blank rows and "Total ..." rows are
+/// skipped without an error.
+#[derive(Debug, Clone, Default)]
+pub struct TrialBalanceImport {
+    pub rows: Vec<TrialBalanceRow>,
+    pub errors: Vec<RowError>,
+}
+
+impl TrialBalanceImport {
+    pub fn is_clean(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+pub fn import_trial_balance_csv(
+    path: impl AsRef<Path>,
+    options: &TrialBalanceImportOptions,
+) -> Result<TrialBalanceImport> {
+    let path = path.as_ref();
+    let file = std::fs::File::open(path)?;
+    let options = with_default_source(options, path);
+    parse_trial_balance_csv(file, &options)
+}
+
+pub fn parse_trial_balance_csv<R: Read>(
+    reader: R,
+    options: &TrialBalanceImportOptions,
+) -> Result<TrialBalanceImport> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .delimiter(options.delimiter)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(reader);
+
+    let headers: Vec<String> = csv_reader.headers()?.iter().map(String::from).collect();
+    let (records, unreadable) = read_csv_records(&mut csv_reader)?;
+
+    let mut import = import_records(&headers, records, options)?;
+    import.errors.extend(unreadable);
+    import.errors.sort_by_key(|e| e.line);
+    Ok(import)
+}
+
+/// Rows of string cells, each with its 1-based line or spreadsheet row number.
+pub(crate) type NumberedRows = Vec<(usize, Vec<String>)>;
+
+/// Every record as strings with its 1-based line number. Records that are not valid UTF-8
+/// (e.g. a Windows-1252 export) are returned as row errors instead of failing the import.
+pub(crate) fn read_csv_records<R: Read>(
+    csv_reader: &mut csv::Reader<R>,
+) -> Result<(NumberedRows, Vec<RowError>)> {
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    for record in csv_reader.records() {
+        match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |p| p.line() as usize);
+                records.push((line, record.iter().map(String::from).collect()));
+            }
+            Err(e) => match e.kind() {
+                csv::ErrorKind::Utf8 { pos, err } => errors.push(RowError {
+                    line: pos.as_ref().map_or(0, |p| p.line() as usize),
+                    message: format!(
+                        "field {} is not valid UTF-8; save the file as UTF-8",
+                        err.field() + 1
+                    ),
+                }),
+                _ => return Err(e.into()),
+            },
+        }
+    }
+    Ok((records, errors))
+}
+
+/// Reads a trial balance from an Excel worksheet (the first sheet unless `sheet` is given).
+#[cfg(feature = "xlsx")]
+pub fn import_trial_balance_xlsx(
+    path: impl AsRef<Path>,
+    sheet: Option<&str>,
+    options: &TrialBalanceImportOptions,
+) -> Result<TrialBalanceImport> {
+    let path = path.as_ref();
+    let options = with_default_source(options, path);
+    let mut rows = read_xlsx_rows(path, sheet)?.into_iter();
+    let Some((_, headers)) = rows.next() else {
+        return Ok(TrialBalanceImport::default());
+    };
+    import_records(&headers, rows.collect(), &options)
+}
+
+/// Every row of a worksheet as strings, with its 1-based spreadsheet row number.
+#[cfg(feature = "xlsx")]
+pub(crate) fn read_xlsx_rows(
+    path: &Path,
+    sheet: Option<&str>,
+) -> Result<Vec<(usize, Vec<String>)>> {
+    use calamine::{open_workbook_auto, Data, Reader};
+
+    let import_error = |e: calamine::Error| FinancialHistoryError::ImportFailed(e.to_string());
+    let mut workbook = open_workbook_auto(path).map_err(import_error)?;
+    let sheet_name =
+        match sheet {
+            Some(name) => name.to_string(),
+            None => workbook.sheet_names().first().cloned().ok_or_else(|| {
+                FinancialHistoryError::ImportFailed("workbook has no sheets".into())
+            })?,
+        };
+    let range = workbook
+        .worksheet_range(&sheet_name)
+        .map_err(import_error)?;
+    let first_row = range.start().map_or(0, |(row, _)| row as usize);
+
+    Ok(range
+        .rows()
+        .enumerate()
+        .map(|(i, cells)| {
+            let values = cells
+                .iter()
+                .map(|cell| match cell {
+                    Data::Empty => String::new(),
+                    Data::DateTime(dt) => dt
+                        .as_datetime()
+                        .map(|d| d.date().format("%Y-%m-%d").to_string())
+                        .unwrap_or_else(|| cell.to_string()),
+                    other => other.to_string().trim().to_string(),
+                })
+                .collect();
+            (first_row + i + 1, values)
+        })
+        .collect())
+}
+
+fn with_default_source(
+    options: &TrialBalanceImportOptions,
+    path: &Path,
+) -> TrialBalanceImportOptions {
+    let mut options = options.clone();
+    if options.source_doc.is_none() {
+        options.source_doc = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+    }
+    options
+}
+
+/// Column positions resolved from the header row.
+struct ColumnIndex {
+    name: usize,
+    code: Option<usize>,
+    account_type: Option<usize>,
+    date: Option<usize>,
+    amount: AmountIndex,
+}
+
+enum AmountIndex {
+    Single(usize),
+    DebitCredit(usize, usize),
+}
+
+impl ColumnIndex {
+    fn resolve(headers: &[String], mapping: &ColumnMapping) -> Result<Self> {
+        let find = |column: &str| {
+            headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(column.trim()))
+                .ok_or_else(|| {
+                    FinancialHistoryError::ImportFailed(format!(
+                        "column '{}' not found in header ({})",
+                        column,
+                        headers.join(", ")
+                    ))
+                })
+        };
+        let find_optional = |column: &Option<String>| column.as_deref().map(find).transpose();
+
+        Ok(Self {
+            name: find(&mapping.account_name)?,
+            code: find_optional(&mapping.account_code)?,
+            account_type: find_optional(&mapping.account_type)?,
+            date: find_optional(&mapping.date)?,
+            amount: match &mapping.amount {
+                AmountColumns::Single(column) => AmountIndex::Single(find(column)?),
+                AmountColumns::DebitCredit { debit, credit } => {
+                    AmountIndex::DebitCredit(find(debit)?, find(credit)?)
+                }
+            },
+        })
+    }
+}
+
+fn import_records(
+    headers: &[String],
+    records: Vec<(usize, Vec<String>)>,
+    options: &TrialBalanceImportOptions,
+) -> Result<TrialBalanceImport> {
+    let index = ColumnIndex::resolve(headers, &options.columns)?;
+    if index.date.is_none() && options.date.is_none() {
+        return Err(FinancialHistoryError::ImportFailed(
+            "no date column mapped and no balance date set".to_string(),
+        ));
+    }
+
+    let mut import = TrialBalanceImport::default();
+    for (line, fields) in records {
+        let cell = |i: usize| fields.get(i).map(|s| s.trim()).unwrap_or("");
+        let name = cell(index.name);
+        if name.is_empty() || name.to_ascii_lowercase().starts_with("total") {
+            continue;
+        }
+
+        match import_row(&index, &cell, options) {
+            Ok(row) => import.rows.push(row),
+            Err(message) => import.errors.push(RowError {
+                line,
+                message: format!("{}: {}", name, message),
+            }),
+        }
+    }
+
+    Ok(import)
+}
+
+fn import_row<'a>(
+    index: &ColumnIndex,
+    cell: &impl Fn(usize) -> &'a str,
+    options: &TrialBalanceImportOptions,
+) -> std::result::Result<TrialBalanceRow, String> {
+    let name = cell(index.name);
+    let code = index.code.map(cell).filter(|c| !c.is_empty());
+
+    let account_type = resolve_type(name, code, index.account_type.map(cell), options)?;
+
+    let date = match index.date {
+        Some(i) => parse_date(cell(i), &options.date_formats)?,
+        None => options.date.expect("checked before import"),
+    };
+
+    let debit_positive = match index.amount {
+        AmountIndex::DebitCredit(debit, credit) => {
+            parse_amount(cell(debit))?.unwrap_or(0.0) - parse_amount(cell(credit))?.unwrap_or(0.0)
+        }
+        AmountIndex::Single(i) => {
+            let value = parse_amount(cell(i))?.ok_or("amount is empty")?;
+            match options.sign_convention {
+                SignConvention::DebitPositive => value,
+                SignConvention::CreditPositive => -value,
+                SignConvention::NaturalBalance if is_credit_normal(&account_type) => -value,
+                SignConvention::NaturalBalance => value,
+            }
+        }
+    };
+    let ytd_value = if is_credit_normal(&account_type) {
+        -debit_positive
+    } else {
+        debit_positive
+    };
+
+    Ok(TrialBalanceRow {
+        account_name: name.to_string(),
+        account_type,
+        date,
+        ytd_value,
+        source_doc: options.source_doc.clone().unwrap_or_default(),
+    })
+}
+
+fn resolve_type(
+    name: &str,
+    code: Option<&str>,
+    type_cell: Option<&str>,
+    options: &TrialBalanceImportOptions,
+) -> std::result::Result<AccountType, String> {
+    let mapped = code
+        .and_then(|c| options.type_mapping.get(c))
+        .or_else(|| options.type_mapping.get(name));
+    if let Some(account_type) = mapped {
+        return Ok(account_type.clone());
+    }
+
+    if let Some(label) = type_cell.filter(|t| !t.is_empty()) {
+        return parse_account_type(label)
+            .ok_or_else(|| format!("unrecognised account type '{}'", label));
+    }
+
+    if let Some(code) = code {
+        if let Some(range) = options.code_ranges.iter().find(|r| r.matches(code)) {
+            return Ok(range.account_type.clone());
+        }
+    }
+
+    Err(match code {
+        Some(code) => format!("no account type for code '{}'", code),
+        None => "no account type (add a type column, code ranges or a mapping)".to_string(),
+    })
+}
+
+/// Accounts whose normal balance is a credit.
+fn is_credit_normal(account_type: &AccountType) -> bool {
+    matches!(
+        account_type,
+        AccountType::Revenue
+            | AccountType::OtherIncome
+            | AccountType::Liability
+            | AccountType::Equity
+    )
+}
+
+/// Maps the account type or class labels used by common accounting packages to an
+/// [`AccountType`], e.g. "Current Asset", "Direct Costs", "Overhead", "Sales".
+///
+/// Known Xero, MYOB and QuickBooks labels match exactly. Anything else is matched by keyword,
+/// balance sheet keywords first, so "Income Tax Payable" is a liability rather than tax expense.
+pub fn parse_account_type(label: &str) -> Option<AccountType> {
+    let label = label.trim().to_ascii_lowercase();
+    if let Some(account_type) = package_account_type(&label) {
+        return Some(account_type);
+    }
+    let has = |keywords: &[&str]| keywords.iter().any(|k| label.contains(k));
+
+    let account_type = if has(&["accumulated", "receivable"]) {
+        AccountType::Asset
+    } else if has(&[
+        "liabilit",
+        "payable",
+        "loan",
+        "current account",
+        "credit card",
+        "accrual",
+    ]) {
+        AccountType::Liability
+    } else if has(&["asset", "bank", "inventory", "prepayment"]) {
+        AccountType::Asset
+    } else if has(&["equity", "capital", "retained"]) {
+        AccountType::Equity
+    } else if has(&["depreciation", "amortisation", "amortization"]) {
+        AccountType::Depreciation
+    } else if has(&["income tax", "tax expense"]) {
+        AccountType::IncomeTax
+    } else if has(&["dividend", "drawings"]) {
+        AccountType::Dividends
+    } else if has(&["shareholder salar", "director", "owner salar"]) {
+        AccountType::ShareholderSalaries
+    } else if has(&["interest expense", "finance cost"]) || label == "interest" {
+        AccountType::Interest
+    } else if has(&["cost of sales", "cost of goods", "direct cost", "cogs"]) {
+        AccountType::CostOfSales
+    } else if has(&["other income", "non-operating income", "interest income"]) {
+        AccountType::OtherIncome
+    } else if has(&["revenue", "income", "sales", "turnover"]) {
+        AccountType::Revenue
+    } else if has(&["expense", "overhead", "operating"]) {
+        AccountType::OperatingExpense
+    } else {
+        return None;
+    };
+    Some(account_type)
+}
+
+/// Account type labels used verbatim by Xero, MYOB and QuickBooks, lowercased.
+fn package_account_type(label: &str) -> Option<AccountType> {
+    let account_type = match label {
+        "bank"
+        | "current asset"
+        | "fixed asset"
+        | "non-current asset"
+        | "inventory"
+        | "prepayment"
+        | "asset"
+        | "accounts receivable (a/r)"
+        | "other current assets"
+        | "fixed assets"
+        | "other assets" => AccountType::Asset,
+        "current liability"
+        | "non-current liability"
+        | "liability"
+        | "accounts payable (a/p)"
+        | "credit card"
+        | "other current liabilities"
+        | "long term liabilities" => AccountType::Liability,
+        "equity" => AccountType::Equity,
+        "revenue" | "sales" | "income" => AccountType::Revenue,
+        "other income" => AccountType::OtherIncome,
+        "direct costs" | "cost of sales" | "cost of goods sold" => AccountType::CostOfSales,
+        "expense" | "expenses" | "overhead" | "other expense" => AccountType::OperatingExpense,
+        "depreciation" => AccountType::Depreciation,
+        _ => return None,
+    };
+    Some(account_type)
+}
+
+/// Parses amounts like `1,234.50`, `$1,234.50`, `(1,234.50)`, `-1234.5` and `1,234.50 CR`.
+/// Returns `None` for an empty cell or a lone dash.
+fn parse_amount(raw: &str) -> std::result::Result<Option<f64>, String> {
+    let mut text = raw.trim().to_ascii_uppercase();
+    if text.is_empty() || text == "-" {
+        return Ok(None);
+    }
+
+    let mut sign = 1.0;
+    if let Some(stripped) = text.strip_suffix("CR") {
+        sign = -sign;
+        text = stripped.trim_end().to_string();
+    } else if let Some(stripped) = text.strip_suffix("DR") {
+        text = stripped.trim_end().to_string();
+    }
+    if text.starts_with('(') && text.ends_with(')') {
+        sign = -sign;
+        text = text[1..text.len() - 1].to_string();
+    }
+
+    let cleaned: String = text
+        .chars()
+        .filter(|c| !matches!(c, ',' | '$' | '£' | '€' | ' '))
+        .collect();
+    cleaned
+        .parse::<f64>()
+        .map(|v| Some(sign * v))
+        .map_err(|_| format!("invalid amount '{}'", raw))
+}
+
+fn parse_date(raw: &str, formats: &[String]) -> std::result::Result<NaiveDate, String> {
+    let raw = raw.trim();
+    formats
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(raw, f).ok())
+        .ok_or_else(|| format!("invalid date '{}'", raw))
+}
+
+/// Reads a two-column CSV of `account name or code, account type` (with a header row)
+/// for [`TrialBalanceImportOptions::with_type_mapping`].
+pub fn load_type_mapping(path: impl AsRef<Path>) -> Result<BTreeMap<String, AccountType>> {
+    let mut reader = csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_path(path)?;
+    let mut mapping = BTreeMap::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |p| p.line());
+        let (Some(key), Some(label)) = (record.get(0), record.get(1)) else {
+            return Err(FinancialHistoryError::ImportFailed(format!(
+                "type mapping line {}: expected two columns",
+                line
+            )));
+        };
+        let account_type = parse_account_type(label).ok_or_else(|| {
+            FinancialHistoryError::ImportFailed(format!(
+                "type mapping line {}: unrecognised account type '{}'",
+                line, label
+            ))
+        })?;
+        mapping.insert(key.to_string(), account_type);
+    }
+
+    Ok(mapping)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    #[test]
+    fn test_debit_credit_columns_with_code_ranges() {
+        let csv = "\
+Code,Account,Debit,Credit
+1000,Cash at Bank,\"12,500.00\",
+2100,Accounts Payable,,4000
+3000,Share Capital,,1000
+4000,Sales,,\"9,000.00\"
+6100,Rent,1500,
+,Total,14000,14000
+";
+        let options = TrialBalanceImportOptions::new()
+            .with_columns(ColumnMapping::new("Account").with_code("Code"))
+            .with_code_ranges(CodeRange::standard())
+            .with_date(date(2023, 3, 31))
+            .with_source_doc("tb.csv");
+
+        let import = parse_trial_balance_csv(csv.as_bytes(), &options).unwrap();
+        assert!(import.is_clean(), "{:?}", import.errors);
+        assert_eq!(import.rows.len(), 5);
+
+        let value = |name: &str| {
+            import
+                .rows
+                .iter()
+                .find(|r| r.account_name == name)
+                .unwrap()
+                .ytd_value
+        };
+        assert_eq!(value("Cash at Bank"), 12500.0);
+        assert_eq!(value("Accounts Payable"), 4000.0);
+        assert_eq!(value("Sales"), 9000.0);
+        assert_eq!(value("Rent"), 1500.0);
+        assert_eq!(import.rows[3].account_type, AccountType::Revenue);
+        assert_eq!(import.rows[0].source_doc, "tb.csv");
+    }
+
+    #[test]
+    fn test_single_signed_column_with_type_column_and_dates() {
+        let csv = "\
+Date,Account,Type,Balance
+31/03/2023,Cash,Current Asset,5000
+31/03/2023,GST Payable,Current Liability,-750
+31/03/2023,Consulting Income,Revenue,(2000)
+31/03/2023,Wages,Expense,1200 DR
+";
+        let options = TrialBalanceImportOptions::new()
+            .with_columns(
+                ColumnMapping::new("Account")
+                    .with_type("Type")
+                    .with_date("Date")
+                    .with_amount("Balance"),
+            )
+            .with_sign_convention(SignConvention::DebitPositive);
+
+        let import = parse_trial_balance_csv(csv.as_bytes(), &options).unwrap();
+        assert!(import.is_clean(), "{:?}", import.errors);
+        let values: Vec<(AccountType, f64)> = import
+            .rows
+            .iter()
+            .map(|r| (r.account_type.clone(), r.ytd_value))
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                (AccountType::Asset, 5000.0),
+                (AccountType::Liability, 750.0),
+                (AccountType::Revenue, 2000.0),
+                (AccountType::OperatingExpense, 1200.0),
+            ]
+        );
+        assert!(import.rows.iter().all(|r| r.date == date(2023, 3, 31)));
+    }
+
+    #[test]
+    fn test_row_errors_report_line_numbers() {
+        let csv = "\
+Code,Account,Debit,Credit
+1000,Cash,abc,
+
+4000,Sales,,100
+9999x,Mystery,10,
+";
+        let mut mapping = BTreeMap::new();
+        mapping.insert("Sales".to_string(), AccountType::Revenue);
+        let options = TrialBalanceImportOptions::new()
+            .with_columns(ColumnMapping::new("Account").with_code("Code"))
+            .with_type_mapping(mapping)
+            .with_code_ranges(vec![CodeRange::new(1000, 1999, AccountType::Asset)])
+            .with_date(date(2023, 3, 31));
+
+        let import = parse_trial_balance_csv(csv.as_bytes(), &options).unwrap();
+        assert_eq!(import.rows.len(), 1);
+        assert_eq!(import.errors.len(), 2);
+        assert_eq!(import.errors[0].line, 2);
+        assert!(import.errors[0].message.contains("invalid amount 'abc'"));
+        assert_eq!(import.errors[1].line, 5);
+        assert!(import.errors[1]
+            .message
+            .contains("no account type for code '9999x'"));
+    }
+
+    #[test]
+    fn test_non_utf8_rows_are_reported_and_skipped() {
+        // "Caf\xe9" is Windows-1252, as exported by some accounting packages
+        let mut csv = b"Account,Debit,Credit\nCash,100,\n".to_vec();
+        csv.extend_from_slice(b"Caf\xe9 Sales,,40\nSales,,60\n");
+        let mut mapping = BTreeMap::new();
+        mapping.insert("Cash".to_string(), AccountType::Asset);
+        mapping.insert("Sales".to_string(), AccountType::Revenue);
+        let options = TrialBalanceImportOptions::new()
+            .with_type_mapping(mapping)
+            .with_date(date(2023, 3, 31));
+
+        let import = parse_trial_balance_csv(csv.as_slice(), &options).unwrap();
+        assert_eq!(import.rows.len(), 2);
+        assert_eq!(import.errors.len(), 1);
+        assert_eq!(import.errors[0].line, 3);
+        assert!(import.errors[0]
+            .message
+            .contains("field 1 is not valid UTF-8"));
+    }
+
+    #[test]
+    fn test_missing_column_is_fatal() {
+        let csv = "Account,Amount\nCash,10\n";
+        let options = TrialBalanceImportOptions::new().with_date(date(2023, 3, 31));
+        let err = parse_trial_balance_csv(csv.as_bytes(), &options).unwrap_err();
+        assert!(err.to_string().contains("column 'Debit' not found"));
+    }
+
+    #[test]
+    fn test_parse_account_type_labels() {
+        assert_eq!(parse_account_type("Fixed Asset"), Some(AccountType::Asset));
+        assert_eq!(
+            parse_account_type("Direct Costs"),
+            Some(AccountType::CostOfSales)
+        );
+        assert_eq!(
+            parse_account_type("Overhead"),
+            Some(AccountType::OperatingExpense)
+        );
+        assert_eq!(
+            parse_account_type("Depreciation"),
+            Some(AccountType::Depreciation)
+        );
+        assert_eq!(
+            parse_account_type("Other Income"),
+            Some(AccountType::OtherIncome)
+        );
+        assert_eq!(parse_account_type("Sales"), Some(AccountType::Revenue));
+        assert_eq!(
+            parse_account_type("Income Tax"),
+            Some(AccountType::IncomeTax)
+        );
+        assert_eq!(
+            parse_account_type("Income Tax Payable"),
+            Some(AccountType::Liability)
+        );
+        assert_eq!(
+            parse_account_type("Dividends Payable"),
+            Some(AccountType::Liability)
+        );
+        assert_eq!(
+            parse_account_type("Accumulated Depreciation"),
+            Some(AccountType::Asset)
+        );
+        assert_eq!(
+            parse_account_type("Director Loan"),
+            Some(AccountType::Liability)
+        );
+        assert_eq!(
+            parse_account_type("Director's Current Account"),
+            Some(AccountType::Liability)
+        );
+        assert_eq!(
+            parse_account_type("Credit Card"),
+            Some(AccountType::Liability)
+        );
+        assert_eq!(
+            parse_account_type("Bank Loan"),
+            Some(AccountType::Liability)
+        );
+        assert_eq!(parse_account_type("Widgets"), None);
+    }
+}
@@ -0,0 +1,511 @@
+//! Importers for the multi-column monthly Profit and Loss and Balance Sheet reports exported by
+//! Xero, MYOB and QuickBooks.
+//!
+//! Reports are read row by row. Rows without amounts are headings: a heading listed in the
+//! [`ReportLayout`] starts a section with its [`AccountType`], any other heading becomes the
+//! `category` of the accounts below it. "Total ..." rows and computed lines such as
+//! "Gross Profit" are skipped. Every other row is an account with one value per month column.
+
+use super::{parse_amount, RowError};
+use crate::error::{FinancialHistoryError, Result};
+use crate::schema::{
+    AccountType, BalanceSheetAccount, BalanceSheetSnapshot, ExtrapolationPolicy,
+    FinancialHistoryConfig, IncomeStatementAccount, InterpolationMethod, PeriodConstraint,
+    SeasonalityProfileId, SourceMetadata,
+};
+use crate::utils::last_day_of_month;
+use chrono::{Datelike, NaiveDate};
+use std::io::Read;
+use std::path::Path;
+
+/// Section headings and computed lines for one accounting package's report exports.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReportLayout {
+    /// Heading text (case-insensitive) that starts a section of the given type.
+    pub sections: Vec<(String, AccountType)>,
+    /// Subtotal lines that are not accounts, e.g. "Gross Profit" or "Net Assets".
+    /// Ignored inside an Equity section, where QuickBooks lists "Net Income" as an account.
+    pub computed_lines: Vec<String>,
+}
+
+impl ReportLayout {
+    fn new(sections: &[(&str, AccountType)], computed_lines: &[&str]) -> Self {
+        Self {
+            sections: sections
+                .iter()
+                .map(|(heading, ty)| (heading.to_string(), ty.clone()))
+                .collect(),
+            computed_lines: computed_lines.iter().map(|l| l.to_string()).collect(),
+        }
+    }
+
+    pub fn xero() -> Self {
+        Self::new(
+            &[
+                ("Income", AccountType::Revenue),
+                ("Trading Income", AccountType::Revenue),
+                ("Revenue", AccountType::Revenue),
+                ("Cost of Sales", AccountType::CostOfSales),
+                ("Other Income", AccountType::OtherIncome),
+                ("Operating Expenses", AccountType::OperatingExpense),
+                ("Expenses", AccountType::OperatingExpense),
+                ("Assets", AccountType::Asset),
+                ("Liabilities", AccountType::Liability),
+                ("Equity", AccountType::Equity),
+            ],
+            &["Gross Profit", "Net Profit", "Net Loss", "Net Assets"],
+        )
+    }
+
+    pub fn myob() -> Self {
+        Self::new(
+            &[
+                ("Income", AccountType::Revenue),
+                ("Cost Of Sales", AccountType::CostOfSales),
+                ("Expenses", AccountType::OperatingExpense),
+                ("Other Income", AccountType::OtherIncome),
+                ("Other Expenses", AccountType::OperatingExpense),
+                ("Assets", AccountType::Asset),
+                ("Liabilities", AccountType::Liability),
+                ("Equity", AccountType::Equity),
+            ],
+            &[
+                "Gross Profit",
+                "Operating Profit",
+                "Net Profit/(Loss)",
+                "Net Profit",
+                "Net Assets",
+            ],
+        )
+    }
+
+    pub fn quickbooks() -> Self {
+        Self::new(
+            &[
+                ("Income", AccountType::Revenue),
+                ("Cost of Goods Sold", AccountType::CostOfSales),
+                ("Cost of Sales", AccountType::CostOfSales),
+                ("Expenses", AccountType::OperatingExpense),
+                ("Other Income", AccountType::OtherIncome),
+                ("Other Expenses", AccountType::OperatingExpense),
+                ("Assets", AccountType::Asset),
+                ("Liabilities", AccountType::Liability),
+                ("Equity", AccountType::Equity),
+            ],
+            &[
+                "Gross Profit",
+                "Net Operating Income",
+                "Net Other Income",
+                "Net Income",
+                "Liabilities and Equity",
+            ],
+        )
+    }
+
+    fn section_type(&self, heading: &str) -> Option<AccountType> {
+        let heading = strip_prefix_word(heading);
+        self.sections
+            .iter()
+            .find(|(h, _)| h.eq_ignore_ascii_case(heading))
+            .map(|(_, ty)| ty.clone())
+    }
+
+    fn is_computed(&self, label: &str) -> bool {
+        self.computed_lines
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(label))
+    }
+}
+
+/// Accounts read from one report, not yet merged into a config.
+#[derive(Debug, Clone, Default)]
+pub struct ReportImport {
+    /// The first title row above the column headings that is not the report name or period.
+    pub organization_name: Option<String>,
+    pub balance_sheet: Vec<BalanceSheetAccount>,
+    pub income_statement: Vec<IncomeStatementAccount>,
+    /// Rows that looked like accounts but had unreadable amounts, or were not valid UTF-8.
+    pub errors: Vec<RowError>,
+}
+
+pub fn import_report_csv(path: impl AsRef<Path>, layout: &ReportLayout) -> Result<ReportImport> {
+    let path = path.as_ref();
+    parse_report_csv(std::fs::File::open(path)?, layout, &file_name(path))
+}
+
+pub fn parse_report_csv<R: Read>(
+    reader: R,
+    layout: &ReportLayout,
+    source_doc: &str,
+) -> Result<ReportImport> {
+    let mut csv_reader = csv::ReaderBuilder::new()
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(reader);
+
+    let (mut rows, unreadable) = super::read_csv_records(&mut csv_reader)?;
+    for (_, cells) in &mut rows {
+        for cell in cells.iter_mut() {
+            *cell = cell.trim().to_string();
+        }
+    }
+
+    let mut import = parse_report_rows(rows, layout, source_doc)?;
+    import.errors.extend(unreadable);
+    import.errors.sort_by_key(|e| e.line);
+    Ok(import)
+}
+
+/// Reads a report from an Excel worksheet (the first sheet unless `sheet` is given).
+#[cfg(feature = "xlsx")]
+pub fn import_report_xlsx(
+    path: impl AsRef<Path>,
+    sheet: Option<&str>,
+    layout: &ReportLayout,
+) -> Result<ReportImport> {
+    let path = path.as_ref();
+    let rows = super::read_xlsx_rows(path, sheet)?;
+    parse_report_rows(rows, layout, &file_name(path))
+}
+
+/// Merges Profit and Loss and Balance Sheet imports into one config. Accounts that appear in
+/// several reports keep every snapshot and constraint, with later reports winning on the
+/// same date or period.
+pub fn reports_to_config(
+    reports: &[ReportImport],
+    fiscal_year_end_month: u32,
+) -> FinancialHistoryConfig {
+    let mut balance_sheet: Vec<BalanceSheetAccount> = Vec::new();
+    let mut income_statement: Vec<IncomeStatementAccount> = Vec::new();
+
+    for report in reports {
+        for account in &report.balance_sheet {
+            match balance_sheet.iter_mut().find(|a| a.name == account.name) {
+                Some(existing) => {
+                    for snapshot in &account.snapshots {
+                        existing.snapshots.retain(|s| s.date != snapshot.date);
+                        existing.snapshots.push(snapshot.clone());
+                    }
+                    existing.snapshots.sort_by_key(|s| s.date);
+                }
+                None => balance_sheet.push(account.clone()),
+            }
+        }
+        for account in &report.income_statement {
+            match income_statement.iter_mut().find(|a| a.name == account.name) {
+                Some(existing) => {
+                    for constraint in &account.constraints {
+                        existing
+                            .constraints
+                            .retain(|c| c.period != constraint.period);
+                        existing.constraints.push(constraint.clone());
+                    }
+                }
+                None => income_statement.push(account.clone()),
+            }
+        }
+    }
+
+    FinancialHistoryConfig {
+        organization_name: reports
+            .iter()
+            .find_map(|r| r.organization_name.clone())
+            .unwrap_or_default(),
+        fiscal_year_end_month,
+        history_start: None,
+        history_end: None,
+        balance_sheet,
+        income_statement,
+    }
+}
+
+fn parse_report_rows(
+    rows: Vec<(usize, Vec<String>)>,
+    layout: &ReportLayout,
+    source_doc: &str,
+) -> Result<ReportImport> {
+    let header_pos = rows
+        .iter()
+        .position(|(_, cells)| cells.iter().skip(1).any(|c| parse_column_date(c).is_some()))
+        .ok_or_else(|| {
+            FinancialHistoryError::ImportFailed(format!(
+                "{}: no header row with month columns found",
+                source_doc
+            ))
+        })?;
+
+    let mut import = ReportImport {
+        organization_name: organization_name(&rows[..header_pos]),
+        ..Default::default()
+    };
+
+    // Column index -> month end. Non-date columns such as "Total" are ignored.
+    let columns: Vec<(usize, NaiveDate)> = rows[header_pos]
+        .1
+        .iter()
+        .enumerate()
+        .skip(1)
+        .filter_map(|(i, c)| parse_column_date(c).map(|d| (i, d)))
+        .collect();
+
+    let mut section: Option<AccountType> = None;
+    let mut categories: Vec<String> = Vec::new();
+
+    for (line, cells) in &rows[header_pos + 1..] {
+        let label = cells.first().map(String::as_str).unwrap_or("");
+        if label.is_empty() {
+            continue;
+        }
+        if let Some(heading) = strip_total(label) {
+            if let Some(pos) = categories
+                .iter()
+                .rposition(|c| c.eq_ignore_ascii_case(heading))
+            {
+                categories.truncate(pos);
+            }
+            continue;
+        }
+        if layout.is_computed(label) && section != Some(AccountType::Equity) {
+            categories.clear();
+            continue;
+        }
+
+        let has_values = columns
+            .iter()
+            .any(|(i, _)| cells.get(*i).is_some_and(|c| !c.is_empty()));
+        if !has_values {
+            let heading = strip_account_code(label);
+            match layout.section_type(heading) {
+                Some(ty) => {
+                    section = Some(ty);
+                    categories.clear();
+                }
+                None if section.is_some() => categories.push(heading.to_string()),
+                None => {}
+            }
+            continue;
+        }
+
+        let Some(section_type) = section.clone() else {
+            import.errors.push(RowError {
+                line: *line,
+                message: format!("{}: account before any section heading", label),
+            });
+            continue;
+        };
+
+        let mut values = Vec::new();
+        let mut bad_cell = None;
+        for (i, date) in &columns {
+            match parse_amount(cells.get(*i).map(String::as_str).unwrap_or("")) {
+                Ok(Some(value)) => values.push((*date, value)),
+                Ok(None) => {}
+                Err(message) => bad_cell = Some(message),
+            }
+        }
+        if let Some(message) = bad_cell {
+            import.errors.push(RowError {
+                line: *line,
+                message: format!("{}: {}", label, message),
+            });
+            continue;
+        }
+
+        let name = strip_account_code(label).to_string();
+        let source = || {
+            Some(SourceMetadata {
+                document_name: source_doc.to_string(),
+                original_text: (name != label).then(|| label.to_string()),
+            })
+        };
+
+        if section_type.is_balance_sheet() {
+            import.balance_sheet.push(BalanceSheetAccount {
+                name: name.clone(),
+                category: categories.first().cloned(),
+                account_type: section_type,
+                method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
+                snapshots: values
+                    .iter()
+                    .map(|(date, value)| BalanceSheetSnapshot {
+                        date: *date,
+                        value: *value,
+                        source: source(),
+                        override_lineage: None,
+                    })
+                    .collect(),
+                is_balancing_account: false,
+                noise_factor: 0.0,
+            });
+        } else {
+            import.income_statement.push(IncomeStatementAccount {
+                name: name.clone(),
+                account_type: refine_income_statement_type(&name, section_type),
+                seasonality_profile: SeasonalityProfileId::Flat,
+                constraints: values
+                    .iter()
+                    .map(|(date, value)| PeriodConstraint {
+                        period: date.format("%Y-%m").to_string(),
+                        value: *value,
+                        source: source(),
+                        override_lineage: None,
+                    })
+                    .collect(),
+                noise_factor: 0.0,
+            });
+        }
+    }
+
+    Ok(import)
+}
+
+/// Picks out the expense lines the engine treats specially. Reports file depreciation,
+/// interest and tax under their general expense sections.
+fn refine_income_statement_type(name: &str, section: AccountType) -> AccountType {
+    if !matches!(section, AccountType::OperatingExpense) {
+        return section;
+    }
+    let name = name.to_ascii_lowercase();
+    let has = |keywords: &[&str]| keywords.iter().any(|k| name.contains(k));
+
+    if has(&["depreciation", "amortisation", "amortization"]) {
+        AccountType::Depreciation
+    } else if has(&["income tax"]) {
+        AccountType::IncomeTax
+    } else if has(&["interest"]) {
+        AccountType::Interest
+    } else if has(&["dividend"]) {
+        AccountType::Dividends
+    } else if has(&[
+        "shareholder salar",
+        "director salar",
+        "directors' fees",
+        "directors fees",
+    ]) {
+        AccountType::ShareholderSalaries
+    } else {
+        section
+    }
+}
+
+/// Month end for column headings like "31 Jan 2023", "Jan-23", "Jan 2023", "January 2023",
+/// "2023-01" or "31/01/2023".
+fn parse_column_date(raw: &str) -> Option<NaiveDate> {
+    let text = raw.trim();
+    if text.is_empty() {
+        return None;
+    }
+
+    const FULL_DATES: [&str; 7] = [
+        "%d %b %Y", "%d %B %Y", "%d-%b-%Y", "%d-%b-%y", "%d %b %y", "%d/%m/%Y", "%Y-%m-%d",
+    ];
+    const MONTHS: [&str; 5] = ["%d %b %Y", "%d %B %Y", "%d %b-%y", "%d %b %y", "%d %B-%y"];
+
+    // `%Y` also accepts short and signed years ("Jan-23" would parse as year -23).
+    let parse = |text: &str, format: &str| {
+        NaiveDate::parse_from_str(text, format)
+            .ok()
+            .filter(|d| d.year() >= 1900)
+    };
+    let with_day = format!("1 {}", text);
+    let date = FULL_DATES
+        .iter()
+        .find_map(|f| parse(text, f))
+        .or_else(|| MONTHS.iter().find_map(|f| parse(&with_day, f)))
+        .or_else(|| parse(&format!("{}-01", text), "%Y-%m-%d"))?;
+
+    Some(last_day_of_month(date.year(), date.month()))
+}
+
+fn organization_name(title_rows: &[(usize, Vec<String>)]) -> Option<String> {
+    const REPORT_WORDS: [&str; 5] = [
+        "profit",
+        "loss",
+        "balance sheet",
+        "income statement",
+        "as at",
+    ];
+    title_rows
+        .iter()
+        .filter_map(|(_, cells)| cells.first())
+        .map(|t| t.trim())
+        .find(|t| {
+            let lower = t.to_ascii_lowercase();
+            !t.is_empty()
+                && !t.chars().any(|c| c.is_ascii_digit())
+                && !REPORT_WORDS.iter().any(|w| lower.contains(w))
+        })
+        .map(String::from)
+}
+
+/// `Some("Current Assets")` for "Total Current Assets".
+fn strip_total(label: &str) -> Option<&str> {
+    let lower = label.to_ascii_lowercase();
+    if lower == "total" {
+        Some("")
+    } else if lower.starts_with("total ") {
+        Some(label[6..].trim())
+    } else {
+        None
+    }
+}
+
+/// Drops the "Less" / "Plus" some Xero layouts put in front of section headings.
+fn strip_prefix_word(heading: &str) -> &str {
+    let lower = heading.to_ascii_lowercase();
+    if lower.starts_with("less ") || lower.starts_with("plus ") {
+        heading[5..].trim()
+    } else {
+        heading
+    }
+}
+
+/// Removes a leading account number such as MYOB's "6-1100" or "200 -".
+fn strip_account_code(label: &str) -> &str {
+    let Some((first, rest)) = label.split_once(' ') else {
+        return label;
+    };
+    let is_code = first.chars().any(|c| c.is_ascii_digit())
+        && first
+            .chars()
+            .all(|c| c.is_ascii_digit() || c == '-' || c == '.')
+        && (first.contains('-') || rest.starts_with("- "));
+    if is_code {
+        rest.trim_start_matches([' ', '-']).trim()
+    } else {
+        label
+    }
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_column_date() {
+        let jan = NaiveDate::from_ymd_opt(2023, 1, 31);
+        assert_eq!(parse_column_date("31 Jan 2023"), jan);
+        assert_eq!(parse_column_date("Jan-23"), jan);
+        assert_eq!(parse_column_date("Jan 2023"), jan);
+        assert_eq!(parse_column_date("January 2023"), jan);
+        assert_eq!(parse_column_date("2023-01"), jan);
+        assert_eq!(parse_column_date("15/01/2023"), jan);
+        assert_eq!(parse_column_date("Total"), None);
+        assert_eq!(parse_column_date("Account"), None);
+    }
+
+    #[test]
+    fn test_strip_account_code() {
+        assert_eq!(strip_account_code("6-1100 Advertising"), "Advertising");
+        assert_eq!(strip_account_code("200 - Sales"), "Sales");
+        assert_eq!(strip_account_code("Office Supplies"), "Office Supplies");
+        assert_eq!(strip_account_code("2023 Bonus Pool"), "2023 Bonus Pool");
+    }
+}
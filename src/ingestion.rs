@@ -1,7 +1,11 @@
 use crate::schema::{
-    AccountType, BalanceSheetAccount, BalanceSheetSnapshot, FinancialHistoryConfig,
-    IncomeStatementAccount, InterpolationMethod, PeriodConstraint, SeasonalityProfileId,
-    SourceMetadata,
+    AccountType, BalanceSheetAccount, BalanceSheetSnapshot, ExtrapolationPolicy,
+    FinancialHistoryConfig, IncomeStatementAccount, InterpolationMethod, PeriodConstraint,
+    SeasonalityProfileId, SourceMetadata,
+};
+use crate::utils::{
+    fiscal_year_start, format_period_string, get_fiscal_year_end_for_date, last_day_of_month,
+    next_month_end,
 };
 use chrono::{Datelike, NaiveDate};
 use std::collections::BTreeMap;
@@ -15,6 +19,70 @@ pub struct TrialBalanceRow {
     pub source_doc: String,
 }
 
+/// The change in an income statement account's year-to-date balance between two consecutive
+/// trial balances of the same fiscal year.
+#[derive(Debug, Clone, PartialEq)]
+pub struct YtdMovement {
+    pub account_name: String,
+    pub account_type: AccountType,
+    /// First month-end covered by the movement.
+    pub start: NaiveDate,
+    /// Month-end of the trial balance the movement ends on.
+    pub end: NaiveDate,
+    pub value: f64,
+    pub source_doc: String,
+}
+
+impl YtdMovement {
+    /// The movement as a constraint period string (`YYYY-MM` or `YYYY-MM:YYYY-MM`).
+    pub fn period(&self) -> String {
+        format_period_string(self.start, self.end)
+    }
+}
+
+/// Converts cumulative YTD income statement balances into non-overlapping movements.
+///
+/// The first trial balance of each fiscal year covers the fiscal year start to its date; each
+/// later one covers the months since the previous trial balance, valued at the difference in
+/// YTD balances. Balance sheet rows are ignored. When an account has two rows for the same
+/// month, the later row wins.
+pub fn ytd_to_movements(rows: &[TrialBalanceRow], fiscal_year_end_month: u32) -> Vec<YtdMovement> {
+    type FiscalYearRows<'a> = BTreeMap<NaiveDate, &'a TrialBalanceRow>;
+    let mut by_year: BTreeMap<(&str, NaiveDate), FiscalYearRows> = BTreeMap::new();
+
+    for row in rows.iter().filter(|r| !r.account_type.is_balance_sheet()) {
+        let month_end = last_day_of_month(row.date.year(), row.date.month());
+        let fy_end = get_fiscal_year_end_for_date(month_end, fiscal_year_end_month);
+        by_year
+            .entry((row.account_name.as_str(), fy_end))
+            .or_default()
+            .insert(month_end, row);
+    }
+
+    let mut movements = Vec::new();
+    for ((_, fy_end), year_rows) in by_year {
+        let mut start = fiscal_year_start(fy_end);
+        let mut previous_ytd = 0.0;
+
+        for (month_end, row) in year_rows {
+            movements.push(YtdMovement {
+                account_name: row.account_name.clone(),
+                account_type: row.account_type.clone(),
+                start,
+                end: month_end,
+                value: row.ytd_value - previous_ytd,
+                source_doc: row.source_doc.clone(),
+            });
+            previous_ytd = row.ytd_value;
+            start = next_month_end(month_end);
+        }
+    }
+
+    movements
+}
+
+/// Builds a config from trial balance rows. Balance sheet rows become snapshots; income
+/// statement YTD balances become fiscal-year-aware movements (see [`ytd_to_movements`]).
 pub fn convert_tb_to_config(
     rows: &[TrialBalanceRow],
     organization_name: String,
@@ -33,6 +101,7 @@ pub fn convert_tb_to_config(
                         category: None,
                         account_type: row.account_type.clone(),
                         method: InterpolationMethod::Linear,
+                        extrapolation: ExtrapolationPolicy::Hold,
                         snapshots: Vec::new(),
                         is_balancing_account: false,
                         noise_factor: 0.0,
@@ -45,10 +114,11 @@ pub fn convert_tb_to_config(
                         document_name: row.source_doc.clone(),
                         original_text: None,
                     }),
+                    override_lineage: None,
                 });
             }
             _ => {
-                let account = income_statement_map
+                income_statement_map
                     .entry(row.account_name.clone())
                     .or_insert_with(|| IncomeStatementAccount {
                         name: row.account_name.clone(),
@@ -57,32 +127,150 @@ pub fn convert_tb_to_config(
                         constraints: Vec::new(),
                         noise_factor: 0.0,
                     });
-
-                let fiscal_year_start =
-                    NaiveDate::from_ymd_opt(row.date.year(), 1, 1).unwrap_or(row.date);
-
-                let period_str = format!(
-                    "{}:{}",
-                    fiscal_year_start.format("%Y-%m"),
-                    row.date.format("%Y-%m")
-                );
-
-                account.constraints.push(PeriodConstraint {
-                    period: period_str,
-                    value: row.ytd_value,
-                    source: Some(SourceMetadata {
-                        document_name: row.source_doc.clone(),
-                        original_text: None,
-                    }),
-                });
             }
         }
     }
 
+    for movement in ytd_to_movements(rows, fiscal_year_end_month) {
+        if let Some(account) = income_statement_map.get_mut(&movement.account_name) {
+            account.constraints.push(PeriodConstraint {
+                period: movement.period(),
+                value: movement.value,
+                source: Some(SourceMetadata {
+                    document_name: movement.source_doc,
+                    original_text: None,
+                }),
+                override_lineage: None,
+            });
+        }
+    }
+
     FinancialHistoryConfig {
         organization_name,
         fiscal_year_end_month,
+        history_start: None,
+        history_end: None,
         balance_sheet: balance_sheet_map.into_values().collect(),
         income_statement: income_statement_map.into_values().collect(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(
+        name: &str,
+        account_type: AccountType,
+        date: (i32, u32, u32),
+        ytd: f64,
+    ) -> TrialBalanceRow {
+        TrialBalanceRow {
+            account_name: name.to_string(),
+            account_type,
+            date: NaiveDate::from_ymd_opt(date.0, date.1, date.2).unwrap(),
+            ytd_value: ytd,
+            source_doc: format!("tb_{}_{:02}", date.0, date.1),
+        }
+    }
+
+    fn periods(config: &FinancialHistoryConfig, name: &str) -> Vec<(String, f64)> {
+        config
+            .income_statement
+            .iter()
+            .find(|a| a.name == name)
+            .unwrap()
+            .constraints
+            .iter()
+            .map(|c| (c.period.clone(), c.value))
+            .collect()
+    }
+
+    #[test]
+    fn test_june_year_end_uses_fiscal_year_start() {
+        let rows = vec![
+            row("Sales", AccountType::Revenue, (2023, 9, 30), 30_000.0),
+            row("Sales", AccountType::Revenue, (2023, 12, 31), 70_000.0),
+            row("Sales", AccountType::Revenue, (2024, 6, 30), 150_000.0),
+        ];
+        let config = convert_tb_to_config(&rows, "June Co".to_string(), 6);
+
+        assert_eq!(
+            periods(&config, "Sales"),
+            vec![
+                ("2023-07:2023-09".to_string(), 30_000.0),
+                ("2023-10:2023-12".to_string(), 40_000.0),
+                ("2024-01:2024-06".to_string(), 80_000.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_march_year_end_restarts_ytd_each_fiscal_year() {
+        let rows = vec![
+            row(
+                "Rent",
+                AccountType::OperatingExpense,
+                (2023, 2, 28),
+                11_000.0,
+            ),
+            row(
+                "Rent",
+                AccountType::OperatingExpense,
+                (2023, 3, 31),
+                12_000.0,
+            ),
+            row(
+                "Rent",
+                AccountType::OperatingExpense,
+                (2023, 4, 30),
+                1_100.0,
+            ),
+            row(
+                "Rent",
+                AccountType::OperatingExpense,
+                (2023, 6, 30),
+                3_300.0,
+            ),
+        ];
+        let config = convert_tb_to_config(&rows, "March Co".to_string(), 3);
+
+        assert_eq!(
+            periods(&config, "Rent"),
+            vec![
+                ("2022-04:2023-02".to_string(), 11_000.0),
+                ("2023-03".to_string(), 1_000.0),
+                ("2023-04".to_string(), 1_100.0),
+                ("2023-05:2023-06".to_string(), 2_200.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_december_year_end_movements_and_balance_sheet_snapshots() {
+        let rows = vec![
+            row("Cash", AccountType::Asset, (2023, 3, 31), 5_000.0),
+            row("Cash", AccountType::Asset, (2023, 6, 30), 6_000.0),
+            row("Sales", AccountType::Revenue, (2023, 6, 30), 50_000.0),
+            row("Sales", AccountType::Revenue, (2023, 3, 31), 20_000.0),
+            row("Sales", AccountType::Revenue, (2023, 12, 31), 100_000.0),
+        ];
+        let config = convert_tb_to_config(&rows, "Calendar Co".to_string(), 12);
+
+        assert_eq!(
+            periods(&config, "Sales"),
+            vec![
+                ("2023-01:2023-03".to_string(), 20_000.0),
+                ("2023-04:2023-06".to_string(), 30_000.0),
+                ("2023-07:2023-12".to_string(), 50_000.0),
+            ]
+        );
+        let cash = &config.balance_sheet[0];
+        assert_eq!(cash.snapshots.len(), 2);
+        assert_eq!(cash.snapshots[1].value, 6_000.0);
+
+        let movements = ytd_to_movements(&rows, 12);
+        assert_eq!(movements.len(), 3);
+        assert_eq!(movements[1].source_doc, "tb_2023_06");
+    }
+}
@@ -0,0 +1,301 @@
+//! Curves through balance sheet snapshots, one per [`InterpolationMethod`].
+//!
+//! Linear, step and cosine curves come from the `splines` crate. The monotone cubic and
+//! compound growth curves are not available there and are evaluated directly. Driver-linked
+//! balances follow income statement series through [`DriverLink`].
+
+use crate::schema::{BalanceSheetSnapshot, InterpolationMethod};
+use crate::utils::last_day_of_month;
+use crate::DenseSeries;
+use chrono::{Datelike, NaiveDate};
+use splines::{Interpolation, Key, Spline};
+use std::collections::BTreeMap;
+
+/// A curve through `(t, value)` points, sampled between the first and last point.
+pub(crate) enum BalanceCurve {
+    Spline(Spline<f64, f64>),
+    /// Fritsch-Carlson monotone cubic Hermite curve with the slope at every point.
+    MonotoneCubic {
+        points: Vec<(f64, f64)>,
+        slopes: Vec<f64>,
+    },
+    CompoundGrowth(Vec<(f64, f64)>),
+}
+
+impl BalanceCurve {
+    /// Builds the curve for `method`; `points` must be sorted by `t`.
+    pub(crate) fn new(method: &InterpolationMethod, points: &[(f64, f64)]) -> Self {
+        // CatmullRom needs outer "ghost" points; financial snapshots typically don't have them,
+        // leading to zeros at the ends. Cosine uses only the two bounding points for a smooth fit.
+        let interpolation = match method {
+            InterpolationMethod::Step => Interpolation::Step(1.0),
+            InterpolationMethod::StepNext => Interpolation::Step(0.0),
+            InterpolationMethod::Curve => Interpolation::Cosine,
+            // Driver-linked accounts fall back to a straight line where no driver value exists
+            InterpolationMethod::Linear | InterpolationMethod::DriverLinked { .. } => {
+                Interpolation::Linear
+            }
+            InterpolationMethod::MonotoneCubic => {
+                return BalanceCurve::MonotoneCubic {
+                    points: points.to_vec(),
+                    slopes: monotone_slopes(points),
+                }
+            }
+            InterpolationMethod::CompoundGrowth => {
+                return BalanceCurve::CompoundGrowth(points.to_vec())
+            }
+        };
+
+        let keys = points
+            .iter()
+            .map(|&(t, value)| Key::new(t, value, interpolation))
+            .collect();
+        BalanceCurve::Spline(Spline::from_vec(keys))
+    }
+
+    pub(crate) fn sample(&self, t: f64) -> f64 {
+        match self {
+            BalanceCurve::Spline(spline) => spline.clamped_sample(t).unwrap_or(0.0),
+            BalanceCurve::MonotoneCubic { points, slopes } => {
+                let Some((i, s)) = locate(points, t) else {
+                    return clamp_to_ends(points, t);
+                };
+                let ((t0, v0), (t1, v1)) = (points[i], points[i + 1]);
+                let h = t1 - t0;
+                let s2 = s * s;
+                let s3 = s2 * s;
+                (2.0 * s3 - 3.0 * s2 + 1.0) * v0
+                    + (s3 - 2.0 * s2 + s) * h * slopes[i]
+                    + (-2.0 * s3 + 3.0 * s2) * v1
+                    + (s3 - s2) * h * slopes[i + 1]
+            }
+            BalanceCurve::CompoundGrowth(points) => {
+                let Some((i, s)) = locate(points, t) else {
+                    return clamp_to_ends(points, t);
+                };
+                let ((_, v0), (_, v1)) = (points[i], points[i + 1]);
+                // A constant growth rate only exists between two non-zero values of the same sign
+                if v0 * v1 > 0.0 {
+                    v0 * (v1 / v0).powf(s)
+                } else {
+                    v0 + (v1 - v0) * s
+                }
+            }
+        }
+    }
+}
+
+/// A balance that moves with a monthly income statement flow, like days sales outstanding.
+///
+/// Each snapshot implies a number of days of its month's driver flow. Those days are
+/// interpolated linearly between snapshots and turned back into a balance with each month's
+/// flow, so snapshots are met exactly while the months between follow the driver's seasonality.
+pub(crate) struct DriverLink {
+    /// Month end to the summed value of the driver accounts.
+    flow: BTreeMap<NaiveDate, f64>,
+    /// Snapshot date to the days of flow it implies.
+    anchors: Vec<(NaiveDate, f64)>,
+}
+
+impl DriverLink {
+    /// `None` if a snapshot falls in a month without driver flow, as no days can be implied.
+    /// `snapshots` must be sorted by date.
+    pub(crate) fn new(
+        snapshots: &[BalanceSheetSnapshot],
+        drivers: &[&DenseSeries],
+    ) -> Option<Self> {
+        let mut flow = BTreeMap::new();
+        for series in drivers {
+            for (date, point) in series.iter() {
+                *flow.entry(*date).or_insert(0.0) += point.value;
+            }
+        }
+
+        let anchors = snapshots
+            .iter()
+            .map(|s| {
+                let month_end = last_day_of_month(s.date.year(), s.date.month());
+                let month_flow = *flow.get(&month_end)?;
+                (month_flow.abs() > f64::EPSILON)
+                    .then(|| (s.date, s.value / month_flow * month_end.day() as f64))
+            })
+            .collect::<Option<Vec<_>>>()?;
+
+        Some(Self { flow, anchors })
+    }
+
+    /// Balance and implied days at month end `date`, if it lies between two snapshots and
+    /// has a driver value.
+    pub(crate) fn sample(&self, date: NaiveDate) -> Option<(f64, f64)> {
+        let month_flow = *self.flow.get(&date)?;
+        let i = self
+            .anchors
+            .windows(2)
+            .position(|w| w[0].0 <= date && date <= w[1].0)?;
+        let ((d0, days0), (d1, days1)) = (self.anchors[i], self.anchors[i + 1]);
+        let span = (d1 - d0).num_days();
+        let s = if span > 0 {
+            (date - d0).num_days() as f64 / span as f64
+        } else {
+            0.0
+        };
+        let days = days0 + (days1 - days0) * s;
+        Some((days * month_flow / date.day() as f64, days))
+    }
+}
+
+/// Segment containing `t` and the position within it (0 to 1), if `t` lies inside the points.
+fn locate(points: &[(f64, f64)], t: f64) -> Option<(usize, f64)> {
+    let i = points.windows(2).position(|w| w[0].0 <= t && t <= w[1].0)?;
+    let (t0, t1) = (points[i].0, points[i + 1].0);
+    let s = if t1 > t0 { (t - t0) / (t1 - t0) } else { 0.0 };
+    Some((i, s))
+}
+
+fn clamp_to_ends(points: &[(f64, f64)], t: f64) -> f64 {
+    match (points.first(), points.last()) {
+        (Some(&(t0, v0)), _) if t <= t0 => v0,
+        (_, Some(&(_, v))) => v,
+        _ => 0.0,
+    }
+}
+
+/// Point slopes that keep the cubic Hermite curve monotone between every pair of points
+/// (Fritsch-Carlson), so it never overshoots a snapshot.
+fn monotone_slopes(points: &[(f64, f64)]) -> Vec<f64> {
+    let n = points.len();
+    if n < 2 {
+        return vec![0.0; n];
+    }
+
+    let h: Vec<f64> = points.windows(2).map(|w| w[1].0 - w[0].0).collect();
+    let delta: Vec<f64> = points
+        .windows(2)
+        .zip(&h)
+        .map(|(w, &h)| if h > 0.0 { (w[1].1 - w[0].1) / h } else { 0.0 })
+        .collect();
+    if n == 2 {
+        return vec![delta[0]; 2];
+    }
+
+    let mut slopes = vec![0.0; n];
+    for k in 1..n - 1 {
+        if delta[k - 1] * delta[k] > 0.0 {
+            let w1 = 2.0 * h[k] + h[k - 1];
+            let w2 = h[k] + 2.0 * h[k - 1];
+            slopes[k] = (w1 + w2) / (w1 / delta[k - 1] + w2 / delta[k]);
+        }
+    }
+    slopes[0] = end_slope(h[0], h[1], delta[0], delta[1]);
+    slopes[n - 1] = end_slope(h[n - 2], h[n - 3], delta[n - 2], delta[n - 3]);
+    slopes
+}
+
+/// One-sided three-point slope at an end point, limited so the end segment stays monotone.
+fn end_slope(h0: f64, h1: f64, delta0: f64, delta1: f64) -> f64 {
+    let slope = ((2.0 * h0 + h1) * delta0 - h0 * delta1) / (h0 + h1);
+    if slope * delta0 <= 0.0 {
+        0.0
+    } else if delta0 * delta1 <= 0.0 && slope.abs() > 3.0 * delta0.abs() {
+        3.0 * delta0
+    } else {
+        slope
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::{DataOrigin, DerivationDetails, MonthlyDataPoint};
+
+    fn samples(method: InterpolationMethod, points: &[(f64, f64)]) -> Vec<f64> {
+        let curve = BalanceCurve::new(&method, points);
+        (0..=40).map(|i| curve.sample(i as f64 * 0.25)).collect()
+    }
+
+    #[test]
+    fn test_monotone_cubic_never_overshoots() {
+        // A flat stretch after a jump: cosine and Catmull-Rom style fits bulge past 100 here
+        let points = [(0.0, 0.0), (2.0, 100.0), (4.0, 100.0), (10.0, 120.0)];
+        let values = samples(InterpolationMethod::MonotoneCubic, &points);
+
+        assert!(values.windows(2).all(|w| w[1] >= w[0] - 1e-9));
+        for (i, &(t, value)) in points.iter().enumerate() {
+            assert!(
+                (values[(t * 4.0) as usize] - value).abs() < 1e-9,
+                "point {}",
+                i
+            );
+        }
+        assert!(values[12..=16].iter().all(|&v| (v - 100.0).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_compound_growth_has_constant_rate() {
+        let curve = BalanceCurve::new(
+            &InterpolationMethod::CompoundGrowth,
+            &[(0.0, 100.0), (2.0, 121.0)],
+        );
+        assert!((curve.sample(1.0) - 110.0).abs() < 1e-9);
+
+        // Crossing zero has no growth rate, so it falls back to a straight line
+        let curve = BalanceCurve::new(
+            &InterpolationMethod::CompoundGrowth,
+            &[(0.0, -100.0), (2.0, 100.0)],
+        );
+        assert!(curve.sample(1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_step_holds_and_step_next_jumps() {
+        let points = [(0.0, 10.0), (4.0, 20.0)];
+        let step = BalanceCurve::new(&InterpolationMethod::Step, &points);
+        let step_next = BalanceCurve::new(&InterpolationMethod::StepNext, &points);
+
+        assert_eq!(step.sample(1.0), 10.0);
+        assert_eq!(step.sample(4.0), 20.0);
+        assert_eq!(step_next.sample(1.0), 20.0);
+    }
+
+    #[test]
+    fn test_driver_link_follows_flow_and_hits_snapshots() {
+        let month_end = |month| last_day_of_month(2023, month);
+        let revenue: DenseSeries = (1..=12)
+            .map(|month| {
+                let value = if month == 6 { 3000.0 } else { 1000.0 };
+                let point = MonthlyDataPoint {
+                    value,
+                    origin: DataOrigin::Allocated,
+                    source: None,
+                    override_lineage: None,
+                    derivation: DerivationDetails {
+                        original_period_value: None,
+                        period_start: None,
+                        period_end: None,
+                        logic: String::new(),
+                        balancing_strategy: None,
+                    },
+                };
+                (month_end(month), point)
+            })
+            .collect();
+        let snapshot = |month, value| BalanceSheetSnapshot {
+            date: month_end(month),
+            value,
+            source: None,
+            override_lineage: None,
+        };
+        let snapshots = [snapshot(1, 1000.0), snapshot(12, 1000.0)];
+
+        let link = DriverLink::new(&snapshots, &[&revenue]).unwrap();
+        let (jan, jan_days) = link.sample(month_end(1)).unwrap();
+        assert!((jan - 1000.0).abs() < 1e-9);
+        assert!((jan_days - 31.0).abs() < 1e-9);
+        assert!((link.sample(month_end(12)).unwrap().0 - 1000.0).abs() < 1e-9);
+        // Constant 31 days of June's tripled revenue, over June's 30 days
+        assert!((link.sample(month_end(6)).unwrap().0 - 3100.0).abs() < 1e-6);
+
+        assert!(DriverLink::new(&[snapshot(1, 1000.0)], &[]).is_none());
+    }
+}
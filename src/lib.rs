@@ -20,11 +20,14 @@
 //! let config = FinancialHistoryConfig {
 //!     organization_name: "ACME Corp".to_string(),
 //!     fiscal_year_end_month: 12,
+//!     history_start: None,
+//!     history_end: None,
 //!     balance_sheet: vec![
 //!         BalanceSheetAccount {
 //!             name: "Cash".to_string(),
 //!             account_type: AccountType::Asset,
 //!             method: InterpolationMethod::Linear,
+//!             extrapolation: ExtrapolationPolicy::Hold,
 //!             snapshots: vec![
 //!                 BalanceSheetSnapshot {
 //!                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
@@ -62,30 +65,74 @@
 //! ```
 
 pub mod balancer;
+pub mod cashflow;
 pub mod chart_of_accounts;
+pub mod diff;
 pub mod engine;
 pub mod error;
+pub mod export;
+pub mod import;
 pub mod ingestion;
+mod interpolation;
+pub mod options;
 pub mod overrides;
 pub mod schema;
 pub mod seasonality;
+mod solver;
 pub mod utils;
+pub mod validation;
 pub mod llm;
 
 pub use balancer::{
-    enforce_accounting_equation, verify_accounting_equation, AccountingBalancer, VerificationResult,
+    enforce_accounting_equation, verification_report, verify_accounting_equation,
+    AccountingBalancer, AnchorConflict, CheckResult, VerificationCheck, VerificationReport,
+    VerificationResult, VerificationRow, SUSPENSE_ACCOUNT_NAME,
+};
+pub use cashflow::{
+    generate_cash_flow, CashFlowLine, CashFlowSection, CashFlowStatement, CashReconciliation,
 };
 pub use chart_of_accounts::{AccountEntry, ChartOfAccounts};
-pub use engine::{process_config, Densifier};
+pub use diff::{
+    diff, overrides_between, AccountChange, ConfigDiff, MergedAccounts, MetadataChange,
+    RenamedAccount, SettingChange, ValueChange,
+};
+pub use engine::{
+    evaluate_constraints, process_config, process_config_with_seed, ConstraintOutcome,
+    ConstraintStatus, Densifier, IncomeStatementSolution, IncomeStatementSolver,
+};
 pub use error::{FinancialHistoryError, Result};
+#[cfg(feature = "xlsx")]
+pub use export::xlsx::{workbook_to_buffer, write_workbook};
+pub use export::{
+    to_long_csv, to_wide_csv, write_long_csv, write_wide_csv, ExportOptions, ExportScope,
+};
+#[cfg(feature = "xlsx")]
+pub use import::import_trial_balance_xlsx;
+#[cfg(feature = "xlsx")]
+pub use import::reports::import_report_xlsx;
+pub use import::reports::{
+    import_report_csv, parse_report_csv, reports_to_config, ReportImport, ReportLayout,
+};
+pub use import::{
+    import_trial_balance_csv, load_type_mapping, parse_account_type, parse_trial_balance_csv,
+    AmountColumns, CodeRange, ColumnMapping, RowError, SignConvention, TrialBalanceImport,
+    TrialBalanceImportOptions,
+};
 pub use ingestion::*;
+pub use options::{
+    BalancingStrategy, NoiseMode, PlugSelection, ProcessingOptions, RetainedEarningsMode,
+    RoundingPolicy,
+};
 pub use overrides::*;
 pub use schema::*;
-pub use seasonality::{get_profile_weights, rotate_weights_for_fiscal_year};
+pub use seasonality::{
+    get_profile_weights, infer_profile, rotate_weights_for_fiscal_year, SeasonalityRegistry,
+};
 pub use utils::*;
+pub use validation::{validate, validate_with_registry, Diagnostic, DiagnosticCode, Severity};
 
 use chrono::NaiveDate;
-use log::{debug, info};
+use log::{debug, info, warn};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
 
@@ -95,11 +142,17 @@ pub enum DataOrigin {
     Anchor,
     /// Mathematically derived from surrounding points (Balance Sheet)
     Interpolated,
+    /// Carried outside the snapshot range to fill the history window (Balance Sheet)
+    /// e.g., the first reported balance held back to the start of the history
+    Extrapolated,
     /// Distributed from a larger time period (Income Statement)
     /// e.g., A monthly value derived from an Annual Total
     Allocated,
     /// Generated to force Assets = Liabilities + Equity
     BalancingPlug,
+    /// Computed from other series by an accounting identity
+    /// e.g., Retained Earnings rolled forward from net income
+    Derived,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -112,6 +165,9 @@ pub struct DerivationDetails {
     pub period_end: Option<NaiveDate>,
     /// Human readable explanation (e.g. "Allocated from Annual Total")
     pub logic: String,
+    /// For balancing adjustments, the strategy that produced this value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub balancing_strategy: Option<BalancingStrategy>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -120,18 +176,109 @@ pub struct MonthlyDataPoint {
     pub origin: DataOrigin,
     /// The specific document and text snippet this came from (if applicable)
     pub source: Option<SourceMetadata>,
+    /// The override that produced this value, if any. Its `Display` reads like
+    /// "set by override #3: merge of Telephone, Internet".
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub override_lineage: Option<OverrideLineage>,
     /// How we calculated this specific number
     pub derivation: DerivationDetails,
 }
 
 pub type DenseSeries = BTreeMap<NaiveDate, MonthlyDataPoint>;
 
+const DEFAULT_REPORT_TOLERANCE: f64 = 0.01;
+
+/// What the final output actually honours, for review alongside the dense data.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessingReport {
+    /// Income statement constraint outcomes keyed by account name, in constraint order.
+    pub constraints: BTreeMap<String, Vec<ConstraintOutcome>>,
+    /// Months where balancing disagreed with a balance sheet snapshot.
+    pub anchor_conflicts: Vec<AnchorConflict>,
+    pub balancing_warnings: Vec<String>,
+    /// Month-by-month checks of the final output, using the options' verification tolerance
+    /// (or one cent when none is set).
+    pub verification: Option<VerificationReport>,
+}
+
+impl ProcessingReport {
+    /// Every constraint whose status is not `Satisfied`, with its account name.
+    pub fn issues(&self) -> impl Iterator<Item = (&str, &ConstraintOutcome)> {
+        self.constraints.iter().flat_map(|(account, outcomes)| {
+            outcomes
+                .iter()
+                .filter(|o| o.status != ConstraintStatus::Satisfied)
+                .map(move |o| (account.as_str(), o))
+        })
+    }
+}
+
 pub struct FinancialHistoryProcessor;
 
 impl FinancialHistoryProcessor {
     pub fn process(config: &FinancialHistoryConfig) -> Result<BTreeMap<String, DenseSeries>> {
+        Self::process_with_options(config, &ProcessingOptions::default())
+    }
+
+    /// Runs the full pipeline with reproducible noise.
+    /// Two runs with the same config and seed produce bit-identical output.
+    pub fn process_with_seed(
+        config: &FinancialHistoryConfig,
+        seed: u64,
+    ) -> Result<BTreeMap<String, DenseSeries>> {
+        Self::process_with_options(config, &ProcessingOptions::new().with_seed(seed))
+    }
+
+    /// Runs the pipeline with explicit policy choices instead of the defaults:
+    /// densify, clip to the date range, round, balance, then optionally verify.
+    pub fn process_with_options(
+        config: &FinancialHistoryConfig,
+        options: &ProcessingOptions,
+    ) -> Result<BTreeMap<String, DenseSeries>> {
+        Self::run_pipeline(config, options).map(|(dense_data, _)| dense_data)
+    }
+
+    /// Same as [`Self::process_with_options`], also reporting how each income statement
+    /// constraint is reflected in the final (clipped and rounded) output.
+    pub fn process_with_report(
+        config: &FinancialHistoryConfig,
+        options: &ProcessingOptions,
+    ) -> Result<(BTreeMap<String, DenseSeries>, ProcessingReport)> {
+        let (dense_data, balancing) = Self::run_pipeline(config, options)?;
+
+        let mut report = ProcessingReport::default();
+        if let Some(balancing) = balancing {
+            report.balancing_warnings = balancing.warnings;
+            report.anchor_conflicts = balancing.anchor_conflicts;
+        }
+
+        let tolerance = options
+            .verification_tolerance
+            .unwrap_or(DEFAULT_REPORT_TOLERANCE);
+        let verification = options
+            .balancer(config)
+            .verification_report(&dense_data, tolerance);
+        report.verification = Some(verification);
+
+        for account in &config.income_statement {
+            let empty = DenseSeries::new();
+            let series = dense_data.get(&account.name).unwrap_or(&empty);
+            let outcomes =
+                engine::evaluate_constraints_with_slack(account, series, options.rounding_slack())?;
+            report.constraints.insert(account.name.clone(), outcomes);
+        }
+
+        Ok((dense_data, report))
+    }
+
+    /// The pipeline behind every entry point, returning the balancer's result when it ran.
+    fn run_pipeline(
+        config: &FinancialHistoryConfig,
+        options: &ProcessingOptions,
+    ) -> Result<(BTreeMap<String, DenseSeries>, Option<VerificationResult>)> {
         validate_config_integrity(config)?;
         validate_fiscal_year_end_month(config.fiscal_year_end_month)?;
+        options.validate()?;
 
         info!(
             "Processing financial history for organization: {}",
@@ -143,28 +290,58 @@ impl FinancialHistoryProcessor {
             config.income_statement.len()
         );
 
-        let mut dense_data = process_config(config)?;
+        let mut dense_data = options
+            .densifier(config.fiscal_year_end_month)
+            .densify_config(config)?;
 
-        let verification = enforce_accounting_equation_new(config, &mut dense_data)?;
+        options.clip(&mut dense_data);
+        options.round(&mut dense_data);
 
-        if !verification.warnings.is_empty() {
-            for warning in verification.warnings {
+        let mut balancing = None;
+        if options.balancing {
+            let verification = options
+                .balancer(config)
+                .enforce_accounting_equation(&mut dense_data)?;
+
+            for warning in &verification.warnings {
                 debug!("Balancing adjustment details: {}", warning);
             }
+            for conflict in verification
+                .anchor_conflicts
+                .iter()
+                .filter(|c| c.routed_to.is_none())
+            {
+                warn!(
+                    "{} on {} no longer matches its document value {:.2} (now {:.2})",
+                    conflict.account,
+                    conflict.date,
+                    conflict.document_value,
+                    conflict.required_value
+                );
+            }
+            balancing = Some(verification);
+
+            // Plug values are derived from already-rounded balances; round again to drop float residue.
+            options.round(&mut dense_data);
+        }
+
+        if let Some(tolerance) = options.verification_tolerance {
+            options
+                .balancer(config)
+                .verify_accounting_equation(&dense_data, tolerance)?;
         }
 
-        Ok(dense_data)
+        Ok((dense_data, balancing))
     }
 
     pub fn process_with_verification(
         config: &FinancialHistoryConfig,
         tolerance: f64,
     ) -> Result<BTreeMap<String, DenseSeries>> {
-        let dense_data = Self::process(config)?;
-
-        verify_accounting_equation_new(config, &dense_data, tolerance)?;
-
-        Ok(dense_data)
+        Self::process_with_options(
+            config,
+            &ProcessingOptions::new().with_verification(tolerance),
+        )
     }
 }
 
@@ -174,6 +351,13 @@ pub fn process_financial_history(
     FinancialHistoryProcessor::process(config)
 }
 
+pub fn process_financial_history_with_seed(
+    config: &FinancialHistoryConfig,
+    seed: u64,
+) -> Result<BTreeMap<String, DenseSeries>> {
+    FinancialHistoryProcessor::process_with_seed(config, seed)
+}
+
 pub fn process_with_verification(
     config: &FinancialHistoryConfig,
     tolerance: f64,
@@ -182,6 +366,30 @@ pub fn process_with_verification(
 }
 
 fn validate_config_integrity(config: &FinancialHistoryConfig) -> Result<()> {
+    // The balancer only reads balance sheet types from the balance sheet and income statement
+    // types from the income statement; anything else would silently drop out of the totals.
+    let mut misplaced = config
+        .balance_sheet
+        .iter()
+        .filter(|a| !a.account_type.is_balance_sheet())
+        .map(|a| (&a.name, &a.account_type, "balance sheet"))
+        .chain(
+            config
+                .income_statement
+                .iter()
+                .filter(|a| a.account_type.is_balance_sheet())
+                .map(|a| (&a.name, &a.account_type, "income statement")),
+        );
+    if let Some((name, account_type, statement)) = misplaced.next() {
+        return Err(FinancialHistoryError::ValidationError {
+            account: name.clone(),
+            details: format!(
+                "{:?} accounts do not belong on the {}; see repair_statement_placement.",
+                account_type, statement
+            ),
+        });
+    }
+
     for account in &config.income_statement {
         for (idx, constraint) in account.constraints.iter().enumerate() {
             let (start, end) =
@@ -226,49 +434,37 @@ fn validate_config_integrity(config: &FinancialHistoryConfig) -> Result<()> {
     Ok(())
 }
 
-fn enforce_accounting_equation_new(
-    config: &FinancialHistoryConfig,
-    dense_data: &mut BTreeMap<String, DenseSeries>,
-) -> Result<crate::balancer::VerificationResult> {
-    let balancer = AccountingBalancer::new(config);
-    balancer.enforce_accounting_equation(dense_data)
-}
-
-fn verify_accounting_equation_new(
-    config: &FinancialHistoryConfig,
-    dense_data: &BTreeMap<String, DenseSeries>,
-    tolerance: f64,
-) -> Result<()> {
-    let balancer = AccountingBalancer::new(config);
-    balancer.verify_accounting_equation(dense_data, tolerance)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::NaiveDate;
+    use chrono::{Datelike, NaiveDate};
 
     #[test]
     fn test_end_to_end_processing() {
         let config = FinancialHistoryConfig {
             organization_name: "Test Company".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![
                 BalanceSheetAccount {
                     name: "Cash".to_string(),
                     category: None,
                     account_type: AccountType::Asset,
                     method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![
                         BalanceSheetSnapshot {
                             date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                             value: 50000.0,
                             source: None,
+                            override_lineage: None,
                         },
                         BalanceSheetSnapshot {
                             date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                             value: 75000.0,
                             source: None,
+                            override_lineage: None,
                         },
                     ],
                     is_balancing_account: true,
@@ -279,16 +475,19 @@ mod tests {
                     category: None,
                     account_type: AccountType::Liability,
                     method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![
                         BalanceSheetSnapshot {
                             date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                             value: 20000.0,
                             source: None,
+                            override_lineage: None,
                         },
                         BalanceSheetSnapshot {
                             date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                             value: 25000.0,
                             source: None,
+                            override_lineage: None,
                         },
                     ],
                     is_balancing_account: false,
@@ -299,16 +498,19 @@ mod tests {
                     category: None,
                     account_type: AccountType::Equity,
                     method: InterpolationMethod::Step,
+                    extrapolation: ExtrapolationPolicy::Hold,
                     snapshots: vec![
                         BalanceSheetSnapshot {
                             date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                             value: 30000.0,
                             source: None,
+                            override_lineage: None,
                         },
                         BalanceSheetSnapshot {
                             date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                             value: 30000.0,
                             source: None,
+                            override_lineage: None,
                         },
                     ],
                     is_balancing_account: false,
@@ -326,7 +528,7 @@ mod tests {
         assert!(dense.contains_key("Accounts Payable"));
         assert!(dense.contains_key("Share Capital"));
 
-        let verification = verify_accounting_equation_new(&config, &dense, 10.0);
+        let verification = verify_accounting_equation(&config, &dense, 10.0);
         if let Err(e) = &verification {
             println!("Verification error: {:?}", e);
         }
@@ -338,6 +540,8 @@ mod tests {
         let config = FinancialHistoryConfig {
             organization_name: "Revenue Test".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![],
             income_statement: vec![IncomeStatementAccount {
                 name: "Sales".to_string(),
@@ -347,6 +551,7 @@ mod tests {
                     period: "2023-01:2023-12".to_string(),
                     value: 1_200_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.0,
             }],
@@ -369,6 +574,8 @@ mod tests {
         let config = FinancialHistoryConfig {
             organization_name: "Constraint Test".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![],
             income_statement: vec![IncomeStatementAccount {
                 name: "Revenue".to_string(),
@@ -379,16 +586,19 @@ mod tests {
                         period: "2023-02".to_string(),
                         value: 5000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: "2023-01:2023-03".to_string(),
                         value: 13000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: "2023-01:2023-12".to_string(),
                         value: 50000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.0,
@@ -418,4 +628,195 @@ mod tests {
             feb
         );
     }
+
+    fn options_test_config() -> FinancialHistoryConfig {
+        FinancialHistoryConfig {
+            organization_name: "Options Test".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![
+                BalanceSheetAccount {
+                    name: "Cash".to_string(),
+                    category: None,
+                    account_type: AccountType::Asset,
+                    method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
+                    snapshots: vec![
+                        BalanceSheetSnapshot {
+                            date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+                            value: 10000.0,
+                            source: None,
+                            override_lineage: None,
+                        },
+                        BalanceSheetSnapshot {
+                            date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+                            value: 21000.0,
+                            source: None,
+                            override_lineage: None,
+                        },
+                    ],
+                    is_balancing_account: false,
+                    noise_factor: 0.05,
+                },
+                BalanceSheetAccount {
+                    name: "Loan".to_string(),
+                    category: None,
+                    account_type: AccountType::Liability,
+                    method: InterpolationMethod::Linear,
+                    extrapolation: ExtrapolationPolicy::Hold,
+                    snapshots: vec![BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+                        value: 5000.0,
+                        source: None,
+                        override_lineage: None,
+                    }],
+                    is_balancing_account: false,
+                    noise_factor: 0.0,
+                },
+            ],
+            income_statement: vec![],
+        }
+    }
+
+    #[test]
+    fn test_process_with_options_disables_noise_and_balancing() {
+        let config = options_test_config();
+        let options = ProcessingOptions::new()
+            .without_noise()
+            .with_balancing(false);
+
+        let dense = FinancialHistoryProcessor::process_with_options(&config, &options).unwrap();
+
+        assert_eq!(dense.len(), 2, "No plug account should be created");
+        let june = dense["Cash"][&NaiveDate::from_ymd_opt(2023, 6, 30).unwrap()].value;
+        let expected = 10000.0 + 11000.0 * (150.0 / 334.0);
+        assert!((june - expected).abs() < 1e-6, "Got {}", june);
+    }
+
+    #[test]
+    fn test_process_with_options_clips_rounds_and_plugs() {
+        let config = options_test_config();
+        let options = ProcessingOptions::new()
+            .with_seed(9)
+            .with_date_range(
+                NaiveDate::from_ymd_opt(2023, 3, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+            )
+            .with_rounding(RoundingPolicy::DecimalPlaces(2))
+            .with_plug_account("Owner Funds")
+            .with_verification(0.001);
+
+        let dense = FinancialHistoryProcessor::process_with_options(&config, &options).unwrap();
+
+        let cash = &dense["Cash"];
+        assert_eq!(cash.len(), 10);
+        assert!(cash.keys().all(|d| d.month() >= 3));
+        for point in cash.values() {
+            assert!(((point.value * 100.0).round() - point.value * 100.0).abs() < 1e-6);
+        }
+
+        let plug = &dense["Owner Funds"];
+        assert!(plug.values().all(|p| p.origin == DataOrigin::BalancingPlug));
+        let dec = NaiveDate::from_ymd_opt(2023, 12, 31).unwrap();
+        assert!((plug[&dec].value - 16000.0).abs() < 0.001);
+    }
+
+    #[test]
+    fn test_process_with_report_flags_constraint_statuses() {
+        let mut config = options_test_config();
+        let constraints = [
+            ("2023-01", 100.0),
+            ("2023-02", 100.0),
+            ("2023-01:2023-02", 200.0),
+            ("2023-01:2023-03", 150.0),
+            ("2023-04:2023-06", 300.0),
+            ("2023-11:2024-01", 300.0),
+        ];
+        config.income_statement.push(IncomeStatementAccount {
+            name: "Sales".to_string(),
+            account_type: AccountType::Revenue,
+            seasonality_profile: SeasonalityProfileId::Flat,
+            constraints: constraints
+                .iter()
+                .map(|(period, value)| PeriodConstraint {
+                    period: period.to_string(),
+                    value: *value,
+                    source: None,
+                    override_lineage: None,
+                })
+                .collect(),
+            noise_factor: 0.0,
+        });
+        let options = ProcessingOptions::new()
+            .without_noise()
+            .with_balancing(false)
+            .with_rounding(RoundingPolicy::DecimalPlaces(2))
+            .with_date_range(
+                NaiveDate::from_ymd_opt(2023, 1, 1).unwrap(),
+                NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+            );
+
+        let (_, report) =
+            FinancialHistoryProcessor::process_with_report(&config, &options).unwrap();
+
+        let statuses: Vec<ConstraintStatus> = report.constraints["Sales"]
+            .iter()
+            .map(|o| o.status)
+            .collect();
+        assert_eq!(
+            statuses,
+            vec![
+                ConstraintStatus::Satisfied,
+                ConstraintStatus::Satisfied,
+                ConstraintStatus::OverDetermined,
+                ConstraintStatus::Conflicting,
+                ConstraintStatus::Satisfied,
+                ConstraintStatus::OutOfRange,
+            ]
+        );
+        assert_eq!(report.issues().count(), 3);
+    }
+
+    #[test]
+    fn test_process_with_options_rejects_inverted_date_range() {
+        let config = options_test_config();
+        let options = ProcessingOptions::new().with_date_range(
+            NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
+            NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
+        );
+
+        let result = FinancialHistoryProcessor::process_with_options(&config, &options);
+        assert!(matches!(result, Err(FinancialHistoryError::DateError(_))));
+    }
+
+    #[test]
+    fn test_processing_rejects_account_on_wrong_statement() {
+        let config = FinancialHistoryConfig {
+            organization_name: "Test Company".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![],
+            income_statement: vec![IncomeStatementAccount {
+                name: "Equipment".to_string(),
+                account_type: AccountType::Asset,
+                seasonality_profile: SeasonalityProfileId::Flat,
+                constraints: vec![PeriodConstraint {
+                    period: "2023-01:2023-12".to_string(),
+                    value: 12000.0,
+                    source: None,
+                    override_lineage: None,
+                }],
+                noise_factor: 0.0,
+            }],
+        };
+
+        match process_financial_history(&config) {
+            Err(FinancialHistoryError::ValidationError { account, .. }) => {
+                assert_eq!(account, "Equipment")
+            }
+            other => panic!("expected a validation error, got {:?}", other.map(|_| ())),
+        }
+    }
 }
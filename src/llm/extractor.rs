@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
 
 use futures::future::{try_join, try_join_all};
 use gemini_rust::FileHandle;
@@ -12,6 +12,7 @@ use crate::schema::{
     AccountType, BalanceSheetAccount, BalanceSheetExtractionResponse, DiscoveryResponse,
     FinancialHistoryConfig, IncomeStatementAccount, IncomeStatementExtractionResponse,
 };
+use crate::validation::{validate, DiagnosticCode, Severity};
 use crate::{process_financial_history, verify_accounting_equation};
 
 pub struct FinancialExtractor {
@@ -82,6 +83,8 @@ impl FinancialExtractor {
         let mut config = FinancialHistoryConfig {
             organization_name: discovery.organization_name,
             fiscal_year_end_month: discovery.fiscal_year_end_month,
+            history_start: discovery.forecast_start_date,
+            history_end: discovery.forecast_end_date,
             balance_sheet: bs_result.balance_sheet,
             income_statement: is_result.income_statement,
         };
@@ -478,45 +481,18 @@ fn render_dense_table(
 }
 
 fn validate_financial_logic(cfg: &FinancialHistoryConfig) -> std::result::Result<(), String> {
-    for acc in &cfg.balance_sheet {
-        for (i, snap) in acc.snapshots.iter().enumerate() {
-            if snap.source.is_none() {
-                return Err(format!(
-                    "Balance Sheet '{}' snapshot #{} missing `source`.",
-                    acc.name, i
-                ));
-            }
-        }
-    }
-    for acc in &cfg.income_statement {
-        for (i, cons) in acc.constraints.iter().enumerate() {
-            if cons.source.is_none() {
-                return Err(format!(
-                    "Income Statement '{}' constraint #{} missing `source`.",
-                    acc.name, i
-                ));
-            }
-        }
-    }
-
-    let mut seen_bs = HashSet::new();
-    for acc in &cfg.balance_sheet {
-        if !seen_bs.insert(&acc.name) {
-            return Err(format!(
-                "Duplicate Balance Sheet account detected: '{}'. Account names must be unique.",
-                acc.name
-            ));
-        }
-    }
-
-    let mut seen_is = HashSet::new();
-    for acc in &cfg.income_statement {
-        if !seen_is.insert(&acc.name) {
-            return Err(format!(
-                "Duplicate Income Statement account detected: '{}'. Account names must be unique.",
-                acc.name
-            ));
-        }
+    // Extracted values must always be traceable, so missing sources block the draft here.
+    let problems: Vec<String> = validate(cfg)
+        .into_iter()
+        .filter(|d| d.severity == Severity::Error || d.code == DiagnosticCode::MissingSource)
+        .map(|d| format!("- {}", d))
+        .collect();
+    if !problems.is_empty() {
+        return Err(format!(
+            "The configuration has {} problem(s):\n{}",
+            problems.len(),
+            problems.join("\n")
+        ));
     }
 
     match process_financial_history(cfg) {
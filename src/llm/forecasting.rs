@@ -18,6 +18,7 @@ const FORECASTING_EXAMPLE: &str = r#"
 2. *Liabilities:* The raw data missed "GST Payable" and "Shareholder Current Account". I must create these. For GST Payable, I'll estimate based on the company's revenue/expense levels (approximately $5,000 seems reasonable for a business this size).
 3. *Naming:* "Ministry of Education - 20 Hours..." is too long. Rename to "Government Funding".
 4. *Balancing Account:* The raw data is missing a "Cash at Bank" account, so I'll add it with `is_balancing_account: true`. This is where the accounting equation will be balanced. (Note: If Cash already existed in the raw data, I would use an `UpdateMetadata` modification instead to set its `is_balancing_account` flag to true.)
+5. *Wages:* The P&L has a single "Wages" line, but the notes say 30% of staff time is administration. Split it into "Wages - Admin" (30%) and "Wages - Teaching" (70%) so the forecast can drive them separately.
 
 **Correct Output (JSON):**
 {
@@ -62,6 +63,14 @@ const FORECASTING_EXAMPLE: &str = r#"
       "action": "rename",
       "target": "Wages & Temporary Staff Expenses",
       "new_name": "Wages"
+    },
+    {
+      "action": "split",
+      "target": "Wages",
+      "parts": [
+        { "name": "Wages - Admin", "percentage": 30.0 },
+        { "name": "Wages - Teaching", "percentage": 70.0 }
+      ]
     }
   ]
 }
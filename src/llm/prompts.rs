@@ -137,10 +137,27 @@ Choose the method that best represents how the account changes:
 - Use for: Fixed assets with infrequent purchases, long-term investments
 - Pattern: Flat periods with sudden changes
 
+**StepNext**: Value jumps to the next snapshot at the start of the period
+- Use for: Loans drawn down or share issues early in the period
+- Pattern: Sudden change followed by a flat period
+
 **Curve**: Smooth, organic growth
 - Use for: Cash (influenced by many small transactions), Retained Earnings
 - Pattern: Natural acceleration/deceleration
 
+**MonotoneCubic**: Smooth, but never overshoots the snapshots
+- Use for: Accounts with many snapshots where Curve would wobble (e.g. monthly Inventory)
+- Pattern: Smooth trend that stays between neighbouring snapshots
+
+**CompoundGrowth**: Grows or shrinks by a constant percentage
+- Use for: Investments, balances accruing interest
+- Pattern: Exponential growth/decay
+
+**DriverLinked**: Moves with income statement accounts (days outstanding)
+- Use for: Accounts Receivable (drivers: Revenue accounts), Accounts Payable and Inventory (drivers: Cost of Sales accounts)
+- Pattern: Follows the drivers' seasonality between snapshots
+- Format: `"method": { "DriverLinked": { "drivers": ["Sales"] } }` using exact income statement account names
+
 ### 4. Balancing Account Selection
 **EXACTLY ONE account MUST have `is_balancing_account: true`**
 
@@ -320,13 +337,24 @@ Choose the pattern that best represents the account's behavior:
 - Pattern: Massive December spike (Black Friday/Christmas)
 
 **SummerHigh** (High Q2/Q3, low Q1/Q4):
-- Use for: Tourism revenue, outdoor recreation, seasonal services
-- Pattern: Peak in summer months
+- Use for: Tourism revenue, outdoor recreation, seasonal services in the Northern Hemisphere
+- Pattern: Peak in the northern summer months
 
 **SaasGrowth** (Ramps from 6% to 10% over fiscal year):
 - Use for: Subscription revenue, growing service businesses
 - Pattern: Gradual increase as customers accumulate
 
+**Learned** (shape taken from the account's own monthly values):
+- Use when: You extracted a value for EVERY month of at least one fiscal year, and other years only have quarterly or annual totals
+- Pattern: Other years follow the same monthly shape as the complete year(s)
+
+**Named** (a registered profile, written as `{"Named": "SouthernSummerHigh"}`):
+- `SouthernSummerHigh`: Summer peak for NZ/AU businesses (high Dec-Mar, low Jun-Aug)
+- `WinterHigh` / `SouthernWinterHigh`: Heating, ski fields, winter clothing (north / south)
+- `SchoolTerm`: Education and childcare, dipping in NZ/AU school holidays
+- `Agricultural`: Farm income peaking with the Southern Hemisphere harvest (Feb-May)
+- Only use these exact names
+
 **When in doubt, use Flat.**
 
 ### 5. Source Attribution
@@ -376,6 +404,7 @@ Set `noise` based on account variability:
 - **Depreciation**: Depreciation and Amortisation expense
 - **ShareholderSalaries**: Salaries paid specifically to owners, directors, or shareholders (distinct from regular employee wages)
 - **IncomeTax**: Corporate Income Tax expense
+- **Dividends**: Dividends declared or owner drawings (list under `income_statement` with period constraints; they reduce Retained Earnings, not profit)
 
 ## EXAMPLE OUTPUT STRUCTURE
 ```json
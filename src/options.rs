@@ -0,0 +1,260 @@
+use crate::balancer::{AccountingBalancer, SUSPENSE_ACCOUNT_NAME};
+use crate::engine::{Densifier, IncomeStatementSolver};
+use crate::error::{FinancialHistoryError, Result};
+use crate::schema::FinancialHistoryConfig;
+use crate::seasonality::SeasonalityRegistry;
+use crate::DenseSeries;
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// How the balancer chooses the account that absorbs the accounting equation imbalance.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum PlugSelection {
+    /// The designated `is_balancing_account`, then Retained Earnings / adjustment equity,
+    /// then any equity account, finally a generated "Balancing Equity Adjustment".
+    #[default]
+    Heuristic,
+    /// Always plug into the named account. It is treated as Equity if it is not in the config.
+    Account(String),
+}
+
+/// Where the balancer puts each month's accounting equation imbalance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BalancingStrategy {
+    /// Overwrite one plug account (see [`PlugSelection`]) with whatever balances the month.
+    #[default]
+    SinglePlug,
+    /// Spread the imbalance over every `is_balancing_account`, in proportion to their balances.
+    ProRata,
+    /// Like `SinglePlug`, but months where the plug account has a document snapshot keep it
+    /// exactly; the imbalance there goes to the suspense account instead. Use this when the
+    /// plug is a bank account that must match its statements.
+    BetweenAnchors,
+    /// Leave every account as densified and show the imbalance as its own equity line
+    /// (the suspense account, [`crate::balancer::SUSPENSE_ACCOUNT_NAME`] unless renamed).
+    Suspense,
+}
+
+/// Controls the random variation added by `noise_factor`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum NoiseMode {
+    /// Fresh entropy on every run.
+    #[default]
+    Random,
+    /// Reproducible noise derived from the seed (see [`Densifier::with_seed`]).
+    Seeded(u64),
+    /// Ignore every account's `noise_factor`.
+    Disabled,
+}
+
+/// How Retained Earnings relates to the income statement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RetainedEarningsMode {
+    /// Keep the interpolated balances and warn when their movement differs from net income
+    /// less dividends.
+    #[default]
+    Check,
+    /// Roll Retained Earnings forward from its opening balance with net income less dividends,
+    /// sweeping any "Current Year Earnings" account into it at the start of each fiscal year.
+    /// Neither account is used as the balancing plug.
+    Derive,
+}
+
+/// Rounding applied to every dense value before balancing.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum RoundingPolicy {
+    /// Keep full floating point precision.
+    #[default]
+    None,
+    /// Round half away from zero to the given number of decimal places (2 = cents).
+    /// Period totals may then differ from their constraints by sub-unit amounts.
+    DecimalPlaces(u32),
+}
+
+/// Policy choices for [`crate::FinancialHistoryProcessor::process_with_options`].
+///
+/// ```rust,ignore
+/// let options = ProcessingOptions::new()
+///     .with_seed(42)
+///     .with_plug_account("Cash at Bank")
+///     .with_rounding(RoundingPolicy::DecimalPlaces(2))
+///     .with_verification(0.01);
+/// let dense = FinancialHistoryProcessor::process_with_options(&config, &options)?;
+/// ```
+#[derive(Debug, Clone)]
+pub struct ProcessingOptions {
+    /// Run the accounting equation balancer after densification.
+    pub balancing: bool,
+    pub balancing_strategy: BalancingStrategy,
+    pub plug_selection: PlugSelection,
+    /// Equity line receiving differences the chosen strategy does not plug.
+    pub suspense_account: String,
+    pub noise: NoiseMode,
+    pub solver: IncomeStatementSolver,
+    /// Inclusive window; months outside it are dropped before balancing.
+    pub date_range: Option<(NaiveDate, NaiveDate)>,
+    pub rounding: RoundingPolicy,
+    pub retained_earnings: RetainedEarningsMode,
+    /// Allowed gap between a Retained Earnings movement and net income (or, when deriving,
+    /// between a derived balance and its snapshot) before a warning is raised.
+    pub retained_earnings_tolerance: f64,
+    /// When set, the output is verified against the accounting equation with this tolerance.
+    pub verification_tolerance: Option<f64>,
+    /// Profiles available to [`crate::SeasonalityProfileId::Named`].
+    pub seasonality: SeasonalityRegistry,
+}
+
+impl Default for ProcessingOptions {
+    fn default() -> Self {
+        Self {
+            balancing: true,
+            balancing_strategy: BalancingStrategy::SinglePlug,
+            plug_selection: PlugSelection::Heuristic,
+            suspense_account: SUSPENSE_ACCOUNT_NAME.to_string(),
+            noise: NoiseMode::Random,
+            solver: IncomeStatementSolver::Hierarchical,
+            date_range: None,
+            rounding: RoundingPolicy::None,
+            retained_earnings: RetainedEarningsMode::Check,
+            retained_earnings_tolerance: 1.0,
+            verification_tolerance: None,
+            seasonality: SeasonalityRegistry::new(),
+        }
+    }
+}
+
+impl ProcessingOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_balancing(mut self, enabled: bool) -> Self {
+        self.balancing = enabled;
+        self
+    }
+
+    pub fn with_balancing_strategy(mut self, strategy: BalancingStrategy) -> Self {
+        self.balancing_strategy = strategy;
+        self
+    }
+
+    pub fn with_suspense_account(mut self, name: impl Into<String>) -> Self {
+        self.suspense_account = name.into();
+        self
+    }
+
+    pub fn with_plug_selection(mut self, selection: PlugSelection) -> Self {
+        self.plug_selection = selection;
+        self
+    }
+
+    pub fn with_plug_account(self, name: impl Into<String>) -> Self {
+        self.with_plug_selection(PlugSelection::Account(name.into()))
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.noise = NoiseMode::Seeded(seed);
+        self
+    }
+
+    pub fn without_noise(mut self) -> Self {
+        self.noise = NoiseMode::Disabled;
+        self
+    }
+
+    pub fn with_solver(mut self, solver: IncomeStatementSolver) -> Self {
+        self.solver = solver;
+        self
+    }
+
+    pub fn with_date_range(mut self, start: NaiveDate, end: NaiveDate) -> Self {
+        self.date_range = Some((start, end));
+        self
+    }
+
+    pub fn with_rounding(mut self, rounding: RoundingPolicy) -> Self {
+        self.rounding = rounding;
+        self
+    }
+
+    pub fn with_retained_earnings_mode(mut self, mode: RetainedEarningsMode) -> Self {
+        self.retained_earnings = mode;
+        self
+    }
+
+    pub fn with_retained_earnings_tolerance(mut self, tolerance: f64) -> Self {
+        self.retained_earnings_tolerance = tolerance;
+        self
+    }
+
+    pub fn with_verification(mut self, tolerance: f64) -> Self {
+        self.verification_tolerance = Some(tolerance);
+        self
+    }
+
+    pub fn with_seasonality_registry(mut self, registry: SeasonalityRegistry) -> Self {
+        self.seasonality = registry;
+        self
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        if let Some((start, end)) = self.date_range {
+            if end < start {
+                return Err(FinancialHistoryError::DateError(format!(
+                    "Processing date range ends ({}) before it starts ({})",
+                    end, start
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn densifier(&self, fiscal_year_end_month: u32) -> Densifier {
+        let densifier = Densifier::new(fiscal_year_end_month)
+            .with_solver(self.solver)
+            .with_seasonality_registry(self.seasonality.clone());
+        match self.noise {
+            NoiseMode::Random => densifier,
+            NoiseMode::Seeded(seed) => densifier.with_seed(seed),
+            NoiseMode::Disabled => densifier.without_noise(),
+        }
+    }
+
+    pub(crate) fn balancer<'a>(
+        &self,
+        config: &'a FinancialHistoryConfig,
+    ) -> AccountingBalancer<'a> {
+        AccountingBalancer::new(config)
+            .with_strategy(self.balancing_strategy)
+            .with_suspense_account(self.suspense_account.clone())
+            .with_plug_selection(self.plug_selection.clone())
+            .with_retained_earnings_mode(self.retained_earnings)
+            .with_retained_earnings_tolerance(self.retained_earnings_tolerance)
+    }
+
+    pub(crate) fn clip(&self, dense_data: &mut BTreeMap<String, DenseSeries>) {
+        if let Some((start, end)) = self.date_range {
+            for series in dense_data.values_mut() {
+                series.retain(|date, _| *date >= start && *date <= end);
+            }
+        }
+    }
+
+    /// Largest per-month change rounding can make, used to widen constraint checks.
+    pub(crate) fn rounding_slack(&self) -> f64 {
+        match self.rounding {
+            RoundingPolicy::None => 0.0,
+            RoundingPolicy::DecimalPlaces(places) => 0.5 * 10f64.powi(-(places as i32)),
+        }
+    }
+
+    pub(crate) fn round(&self, dense_data: &mut BTreeMap<String, DenseSeries>) {
+        if let RoundingPolicy::DecimalPlaces(places) = self.rounding {
+            let scale = 10f64.powi(places as i32);
+            for point in dense_data.values_mut().flat_map(|s| s.values_mut()) {
+                point.value = (point.value * scale).round() / scale;
+            }
+        }
+    }
+}
@@ -1,11 +1,18 @@
+use crate::error::{FinancialHistoryError, Result};
+use crate::ingestion::{ytd_to_movements, TrialBalanceRow};
 use crate::schema::{
-    AccountType, BalanceSheetAccount, BalanceSheetSnapshot, FinancialHistoryConfig,
-    IncomeStatementAccount, PeriodConstraint,
+    AccountType, BalanceSheetAccount, BalanceSheetSnapshot, ExtrapolationPolicy,
+    FinancialHistoryConfig, IncomeStatementAccount, InterpolationMethod, OverrideLineage,
+    PeriodConstraint, SeasonalityProfileId, SourceMetadata,
 };
-use chrono::NaiveDate;
+use crate::utils::{format_period_string, parse_period_string, shift_month_end};
+use chrono::{Datelike, NaiveDate};
+use log::warn;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
+use std::fmt;
+use std::slice;
 
 /// The master container for all strategic adjustments.
 /// This struct is serialized to JSON Schema and passed to the LLM.
@@ -75,7 +82,7 @@ pub enum AccountModification {
 
     /// Manually set/override a specific value.
     /// - BS: Adds/Replaces a snapshot at the date.
-    /// - IS: Adds a constraint for the period.
+    /// - IS: Adds a constraint for the period, or replaces the one holding `previous_value`.
     SetValue {
         target: String,
         #[schemars(
@@ -83,13 +90,178 @@ pub enum AccountModification {
         )]
         date_or_period: String,
         value: f64,
+        #[schemars(
+            description = "IS only: the current value of the constraint for this period to replace. Omit to add a new constraint."
+        )]
+        #[serde(default)]
+        previous_value: Option<f64>,
+        #[schemars(description = "Why the value is being set (optional), kept with the value.")]
+        #[serde(default)]
+        reason: Option<String>,
+    },
+
+    /// Split one account into several by percentage (e.g., 'Wages' -> 30% 'Wages - Admin',
+    /// 70% 'Wages - Production'). Every snapshot or constraint is scaled by each part's share.
+    /// The original account is removed unless a part keeps its name.
+    Split {
+        #[schemars(description = "The exact current name of the account to split.")]
+        target: String,
+        #[schemars(
+            description = "The accounts to split into, with their share of every value. Percentages must add up to 100."
+        )]
+        parts: Vec<SplitPart>,
+    },
+
+    /// Move the values of one account that fall within a period to another account on the same
+    /// statement.
+    /// - BS: Moves snapshots dated within the period.
+    /// - IS: Moves constraints whose period lies entirely within it.
+    ///
+    /// Moved amounts are added to any value the target already has for the same date or period.
+    ReclassifyPeriod {
+        #[schemars(description = "The account to move values FROM.")]
+        source: String,
+        #[schemars(description = "The account to move values TO. It must already exist.")]
+        target: String,
+        #[schemars(
+            description = "'YYYY-MM' or 'YYYY-MM:YYYY-MM' (inclusive) selecting the values to move."
+        )]
+        period: String,
+    },
+
+    /// Shift every snapshot date or constraint period of an account by a number of months
+    /// (e.g., -12 when the extraction used the wrong year). Snapshots move to the month-end.
+    ShiftDates {
+        #[schemars(description = "The exact current name of the account.")]
+        target: String,
+        #[schemars(description = "Months to shift by. Negative values move dates earlier.")]
+        months: i32,
     },
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SplitPart {
+    #[schemars(description = "Name of the resulting account.")]
+    pub name: String,
+    #[schemars(description = "Share of the original account, in percent (e.g., 30.0).")]
+    pub percentage: f64,
+}
+
+/// How far split percentages may add up from 100 and still be accepted.
+const SPLIT_PERCENTAGE_TOLERANCE: f64 = 0.01;
+
+/// How close a constraint must be to `SetValue::previous_value` to be replaced by it.
+const PREVIOUS_VALUE_TOLERANCE: f64 = 1e-6;
+
+/// How [`FinancialHistoryOverrides::try_apply`] treats modifications that cannot be applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ApplyMode {
+    /// Skip them, record why in the report and apply the rest.
+    #[default]
+    Lenient,
+    /// Return an error for the first one; nothing is applied.
+    Strict,
+}
+
+/// What happened to a single modification. Each one is applied in full or not at all.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ModificationOutcome {
+    Applied,
+    /// No account of this name exists at the point the modification runs.
+    TargetMissing(String),
+    /// The date or period string could not be parsed.
+    InvalidDate(String),
+    /// The new name is already used by another account.
+    NameCollision(String),
+    /// The new type belongs to the other statement; see [`repair_statement_placement`].
+    WrongStatement(AccountType),
+    /// The modification's own arguments cannot be carried out, e.g. split percentages that
+    /// do not add up to 100.
+    InvalidArguments(String),
+}
+
+impl ModificationOutcome {
+    pub fn is_applied(&self) -> bool {
+        matches!(self, ModificationOutcome::Applied)
+    }
+}
+
+impl fmt::Display for ModificationOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ModificationOutcome::Applied => write!(f, "applied"),
+            ModificationOutcome::TargetMissing(name) => write!(f, "no account named '{}'", name),
+            ModificationOutcome::InvalidDate(value) => {
+                write!(f, "'{}' is not a valid date or period", value)
+            }
+            ModificationOutcome::NameCollision(name) => {
+                write!(f, "an account named '{}' already exists", name)
+            }
+            ModificationOutcome::WrongStatement(account_type) => {
+                write!(f, "{:?} belongs on the other statement", account_type)
+            }
+            ModificationOutcome::InvalidArguments(reason) => write!(f, "{}", reason),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApplyReport {
+    pub config: FinancialHistoryConfig,
+    /// One outcome per modification, in order.
+    pub outcomes: Vec<ModificationOutcome>,
+}
+
+impl ApplyReport {
+    pub fn is_clean(&self) -> bool {
+        self.outcomes.iter().all(ModificationOutcome::is_applied)
+    }
+
+    /// Index and outcome of every modification that was skipped.
+    pub fn failures(&self) -> impl Iterator<Item = (usize, &ModificationOutcome)> {
+        self.outcomes
+            .iter()
+            .enumerate()
+            .filter(|(_, outcome)| !outcome.is_applied())
+    }
+}
+
 impl FinancialHistoryOverrides {
     /// Applies the overrides to a base configuration, returning a new configuration.
     /// The original config is immutable, preserving the audit trail.
+    ///
+    /// Modifications that cannot be applied are skipped with a warning; use
+    /// [`try_apply`](Self::try_apply) to find out which.
     pub fn apply(&self, base_config: &FinancialHistoryConfig) -> FinancialHistoryConfig {
+        let report = self.apply_lenient(base_config);
+        for (index, outcome) in report.failures() {
+            warn!("Skipped override #{}: {}", index, outcome);
+        }
+        report.config
+    }
+
+    /// Applies the overrides and reports the outcome of every modification.
+    ///
+    /// In [`ApplyMode::Strict`] the first modification that cannot be applied is returned as
+    /// [`FinancialHistoryError::OverrideFailed`].
+    pub fn try_apply(
+        &self,
+        base_config: &FinancialHistoryConfig,
+        mode: ApplyMode,
+    ) -> Result<ApplyReport> {
+        let report = self.apply_lenient(base_config);
+        if mode == ApplyMode::Strict {
+            if let Some((index, outcome)) = report.failures().next() {
+                return Err(FinancialHistoryError::OverrideFailed {
+                    index,
+                    reason: outcome.to_string(),
+                });
+            }
+        }
+        Ok(report)
+    }
+
+    fn apply_lenient(&self, base_config: &FinancialHistoryConfig) -> ApplyReport {
         let mut config = base_config.clone();
 
         // 1. Inject New Accounts
@@ -102,11 +274,16 @@ impl FinancialHistoryOverrides {
             .extend(self.new_income_statement_accounts.clone());
 
         // 2. Apply Modifications
-        for modification in &self.modifications {
-            apply_single_modification(&mut config, modification);
-        }
+        let outcomes = self
+            .modifications
+            .iter()
+            .enumerate()
+            .map(|(index, modification)| {
+                apply_single_modification(&mut config, index, modification)
+            })
+            .collect();
 
-        config
+        ApplyReport { config, outcomes }
     }
 
     /// Generates a Gemini-compatible JSON schema (no $ref, $schema, or definitions)
@@ -117,20 +294,32 @@ impl FinancialHistoryOverrides {
     }
 }
 
+/// Checks everything a modification needs before changing anything, so a failed modification
+/// leaves the config untouched.
 fn apply_single_modification(
     config: &mut FinancialHistoryConfig,
+    index: usize,
     modification: &AccountModification,
-) {
+) -> ModificationOutcome {
     match modification {
         AccountModification::Rename { target, new_name } => {
+            if new_name != target && account_exists(config, new_name) {
+                return ModificationOutcome::NameCollision(new_name.clone());
+            }
             if let Some(acc) = find_bs_mut(config, target) {
                 acc.name = new_name.clone();
             } else if let Some(acc) = find_is_mut(config, target) {
                 acc.name = new_name.clone();
+                replace_drivers(config, slice::from_ref(target), slice::from_ref(new_name));
+            } else {
+                return ModificationOutcome::TargetMissing(target.clone());
             }
         }
 
         AccountModification::Delete { target } => {
+            if !account_exists(config, target) {
+                return ModificationOutcome::TargetMissing(target.clone());
+            }
             config.balance_sheet.retain(|a| &a.name != target);
             config.income_statement.retain(|a| &a.name != target);
         }
@@ -142,6 +331,9 @@ fn apply_single_modification(
             new_is_balancing_account,
         } => {
             if let Some(acc) = find_bs_mut(config, target) {
+                if let Some(t) = new_type.as_ref().filter(|t| !t.is_balance_sheet()) {
+                    return ModificationOutcome::WrongStatement(t.clone());
+                }
                 if let Some(c) = new_category {
                     acc.category = Some(c.clone());
                 }
@@ -154,20 +346,30 @@ fn apply_single_modification(
             } else if let Some(acc) = find_is_mut(config, target) {
                 // IS accounts don't currently have a 'category' field in schema, but we update type
                 if let Some(t) = new_type {
+                    if t.is_balance_sheet() {
+                        return ModificationOutcome::WrongStatement(t.clone());
+                    }
                     acc.account_type = t.clone();
                 }
+            } else {
+                return ModificationOutcome::TargetMissing(target.clone());
             }
         }
 
         AccountModification::ScaleValues { target, factor } => {
+            let action = format!("scaled by {}", factor);
             if let Some(acc) = find_bs_mut(config, target) {
                 for s in &mut acc.snapshots {
                     s.value *= factor;
+                    s.override_lineage = lineage(index, &action, target, s.source.as_ref());
                 }
             } else if let Some(acc) = find_is_mut(config, target) {
                 for c in &mut acc.constraints {
                     c.value *= factor;
+                    c.override_lineage = lineage(index, &action, target, c.source.as_ref());
                 }
+            } else {
+                return ModificationOutcome::TargetMissing(target.clone());
             }
         }
 
@@ -175,25 +377,58 @@ fn apply_single_modification(
             target,
             date_or_period,
             value,
+            previous_value,
+            reason,
         } => {
+            // No document reported this value, so it only carries its lineage
+            let override_lineage = Some(OverrideLineage {
+                modification_index: index,
+                action: "value set manually".to_string(),
+                accounts: vec![target.clone()],
+                sources: Vec::new(),
+                reason: reason.clone(),
+            });
             if let Some(acc) = find_bs_mut(config, target) {
                 // Parse date for BS
-                if let Ok(date) = NaiveDate::parse_from_str(date_or_period, "%Y-%m-%d") {
-                    // Remove existing snapshot at this date if any
-                    acc.snapshots.retain(|s| s.date != date);
-                    acc.snapshots.push(BalanceSheetSnapshot {
-                        date,
-                        value: *value,
-                        source: None, // Manual override
-                    });
-                }
+                let Ok(date) = NaiveDate::parse_from_str(date_or_period, "%Y-%m-%d") else {
+                    return ModificationOutcome::InvalidDate(date_or_period.clone());
+                };
+                // Remove existing snapshot at this date if any
+                acc.snapshots.retain(|s| s.date != date);
+                acc.snapshots.push(BalanceSheetSnapshot {
+                    date,
+                    value: *value,
+                    source: None,
+                    override_lineage,
+                });
             } else if let Some(acc) = find_is_mut(config, target) {
-                // IS simply accepts the string period
-                acc.constraints.push(PeriodConstraint {
+                if parse_period_string(date_or_period).is_err() {
+                    return ModificationOutcome::InvalidDate(date_or_period.clone());
+                }
+                let constraint = PeriodConstraint {
                     period: date_or_period.clone(),
                     value: *value,
                     source: None,
-                });
+                    override_lineage,
+                };
+                match previous_value {
+                    // A period can hold several constraints, e.g. after a merge
+                    Some(previous) => {
+                        let Some(existing) = acc.constraints.iter_mut().find(|c| {
+                            &c.period == date_or_period
+                                && (c.value - previous).abs() <= PREVIOUS_VALUE_TOLERANCE
+                        }) else {
+                            return ModificationOutcome::InvalidArguments(format!(
+                                "'{}' has no {} constraint of {}",
+                                target, date_or_period, previous
+                            ));
+                        };
+                        *existing = constraint;
+                    }
+                    None => acc.constraints.push(constraint),
+                }
+            } else {
+                return ModificationOutcome::TargetMissing(target.clone());
             }
         }
 
@@ -206,13 +441,312 @@ fn apply_single_modification(
                 .balance_sheet
                 .iter()
                 .any(|a| sources.contains(&a.name));
+
+            // Every source must be on the same statement, and the target must not be on the other
+            let (same, other): (Vec<&str>, Vec<&str>) = if is_bs {
+                (
+                    config
+                        .balance_sheet
+                        .iter()
+                        .map(|a| a.name.as_str())
+                        .collect(),
+                    config
+                        .income_statement
+                        .iter()
+                        .map(|a| a.name.as_str())
+                        .collect(),
+                )
+            } else {
+                (
+                    config
+                        .income_statement
+                        .iter()
+                        .map(|a| a.name.as_str())
+                        .collect(),
+                    config
+                        .balance_sheet
+                        .iter()
+                        .map(|a| a.name.as_str())
+                        .collect(),
+                )
+            };
+            if let Some(missing) = sources.iter().find(|s| !same.contains(&s.as_str())) {
+                return ModificationOutcome::TargetMissing(missing.clone());
+            }
+            if other.contains(&target_name.as_str()) {
+                return ModificationOutcome::NameCollision(target_name.clone());
+            }
+
             if is_bs {
-                merge_balance_sheet(config, sources, target_name);
+                merge_balance_sheet(config, index, sources, target_name);
+            } else {
+                merge_income_statement(config, index, sources, target_name);
+                replace_drivers(config, sources, slice::from_ref(target_name));
+            }
+        }
+
+        AccountModification::Split { target, parts } => {
+            return split_account(config, index, target, parts);
+        }
+
+        AccountModification::ReclassifyPeriod {
+            source,
+            target,
+            period,
+        } => {
+            return reclassify_period(config, index, source, target, period);
+        }
+
+        AccountModification::ShiftDates { target, months } => {
+            let action = format!("dates shifted by {} months", months);
+            let out_of_range = || {
+                ModificationOutcome::InvalidArguments(format!(
+                    "shifting by {} months leaves the supported date range",
+                    months
+                ))
+            };
+            if let Some(acc) = find_bs_mut(config, target) {
+                let Some(dates) = acc
+                    .snapshots
+                    .iter()
+                    .map(|s| shift_month_end(s.date, *months))
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    return out_of_range();
+                };
+                for (s, date) in acc.snapshots.iter_mut().zip(dates) {
+                    s.date = date;
+                    s.override_lineage = lineage(index, &action, target, s.source.as_ref());
+                }
+            } else if let Some(acc) = find_is_mut(config, target) {
+                let mut periods = Vec::new();
+                for c in &acc.constraints {
+                    let Ok((start, end)) = c.resolve_dates() else {
+                        return ModificationOutcome::InvalidDate(c.period.clone());
+                    };
+                    let (Some(start), Some(end)) = (
+                        shift_month_end(start, *months),
+                        shift_month_end(end, *months),
+                    ) else {
+                        return out_of_range();
+                    };
+                    periods.push(format_period_string(start, end));
+                }
+                for (c, period) in acc.constraints.iter_mut().zip(periods) {
+                    c.period = period;
+                    c.override_lineage = lineage(index, &action, target, c.source.as_ref());
+                }
             } else {
-                merge_income_statement(config, sources, target_name);
+                return ModificationOutcome::TargetMissing(target.clone());
+            }
+        }
+    }
+
+    ModificationOutcome::Applied
+}
+
+fn split_account(
+    config: &mut FinancialHistoryConfig,
+    index: usize,
+    target: &str,
+    parts: &[SplitPart],
+) -> ModificationOutcome {
+    if let Some(part) = parts.iter().find(|p| p.percentage < 0.0) {
+        return ModificationOutcome::InvalidArguments(format!(
+            "'{}' has a negative share of {}%",
+            part.name, part.percentage
+        ));
+    }
+    let total: f64 = parts.iter().map(|p| p.percentage).sum();
+    if (total - 100.0).abs() > SPLIT_PERCENTAGE_TOLERANCE {
+        return ModificationOutcome::InvalidArguments(format!(
+            "split percentages add up to {} rather than 100",
+            total
+        ));
+    }
+    for (n, part) in parts.iter().enumerate() {
+        if parts[..n].iter().any(|p| p.name == part.name) {
+            return ModificationOutcome::InvalidArguments(format!(
+                "'{}' appears twice in the split",
+                part.name
+            ));
+        }
+        if part.name != target && account_exists(config, &part.name) {
+            return ModificationOutcome::NameCollision(part.name.clone());
+        }
+    }
+
+    let action = |part: &SplitPart| format!("split of {} ({}%)", target, part.percentage);
+    if let Some(position) = config.balance_sheet.iter().position(|a| a.name == target) {
+        let original = config.balance_sheet.remove(position);
+        for (n, part) in parts.iter().enumerate() {
+            let mut account = original.clone();
+            account.name = part.name.clone();
+            // Only one part can carry the balancing flag
+            account.is_balancing_account = original.is_balancing_account && n == 0;
+            for s in &mut account.snapshots {
+                s.value *= part.percentage / 100.0;
+                s.override_lineage = lineage(index, &action(part), target, s.source.as_ref());
             }
+            config.balance_sheet.insert(position + n, account);
         }
+    } else if let Some(position) = config
+        .income_statement
+        .iter()
+        .position(|a| a.name == target)
+    {
+        let original = config.income_statement.remove(position);
+        for (n, part) in parts.iter().enumerate() {
+            let mut account = original.clone();
+            account.name = part.name.clone();
+            for c in &mut account.constraints {
+                c.value *= part.percentage / 100.0;
+                c.override_lineage = lineage(index, &action(part), target, c.source.as_ref());
+            }
+            config.income_statement.insert(position + n, account);
+        }
+        let names: Vec<String> = parts.iter().map(|p| p.name.clone()).collect();
+        replace_drivers(config, &[target.to_string()], &names);
+    } else {
+        return ModificationOutcome::TargetMissing(target.to_string());
     }
+
+    ModificationOutcome::Applied
+}
+
+fn reclassify_period(
+    config: &mut FinancialHistoryConfig,
+    index: usize,
+    source: &str,
+    target: &str,
+    period: &str,
+) -> ModificationOutcome {
+    let Ok((start, end)) = parse_period_string(period) else {
+        return ModificationOutcome::InvalidDate(period.to_string());
+    };
+    if source == target {
+        return ModificationOutcome::InvalidArguments(format!(
+            "'{}' cannot be reclassified into itself",
+            source
+        ));
+    }
+    let (Some(statement), Some(target_statement)) =
+        (statement_of(config, source), statement_of(config, target))
+    else {
+        let missing = if account_exists(config, source) {
+            target
+        } else {
+            source
+        };
+        return ModificationOutcome::TargetMissing(missing.to_string());
+    };
+    if statement != target_statement {
+        return ModificationOutcome::InvalidArguments(format!(
+            "'{}' and '{}' are on different statements",
+            source, target
+        ));
+    }
+    let action = format!("reclassified from {} for {}", source, period);
+    let nothing_to_move = || {
+        ModificationOutcome::InvalidArguments(format!("'{}' has no values in {}", source, period))
+    };
+
+    if statement == Statement::BalanceSheet {
+        let Some(from) = find_bs_mut(config, source) else {
+            return ModificationOutcome::TargetMissing(source.to_string());
+        };
+        let (moved, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut from.snapshots)
+            .into_iter()
+            .partition(|s| s.date >= start && s.date <= end);
+        from.snapshots = kept;
+        if moved.is_empty() {
+            return nothing_to_move();
+        }
+
+        let Some(to) = find_bs_mut(config, target) else {
+            return ModificationOutcome::TargetMissing(target.to_string());
+        };
+        for snap in moved {
+            match to.snapshots.iter_mut().find(|s| s.date == snap.date) {
+                Some(existing) => {
+                    existing.value += snap.value;
+                    existing.override_lineage =
+                        lineage(index, &action, source, existing.source.as_ref());
+                }
+                None => to.snapshots.push(BalanceSheetSnapshot {
+                    override_lineage: lineage(index, &action, source, snap.source.as_ref()),
+                    ..snap
+                }),
+            }
+        }
+    } else {
+        let Some(from) = find_is_mut(config, source) else {
+            return ModificationOutcome::TargetMissing(source.to_string());
+        };
+        let (moved, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut from.constraints)
+            .into_iter()
+            .partition(|c| c.resolve_dates().is_ok_and(|(s, e)| s >= start && e <= end));
+        from.constraints = kept;
+        if moved.is_empty() {
+            return nothing_to_move();
+        }
+
+        let Some(to) = find_is_mut(config, target) else {
+            return ModificationOutcome::TargetMissing(target.to_string());
+        };
+        for constraint in moved {
+            match to
+                .constraints
+                .iter_mut()
+                .find(|c| c.period == constraint.period)
+            {
+                Some(existing) => {
+                    existing.value += constraint.value;
+                    existing.override_lineage =
+                        lineage(index, &action, source, existing.source.as_ref());
+                }
+                None => to.constraints.push(PeriodConstraint {
+                    override_lineage: lineage(index, &action, source, constraint.source.as_ref()),
+                    ..constraint
+                }),
+            }
+        }
+    }
+
+    ModificationOutcome::Applied
+}
+
+fn statement_of(config: &FinancialHistoryConfig, name: &str) -> Option<Statement> {
+    if config.balance_sheet.iter().any(|a| a.name == name) {
+        Some(Statement::BalanceSheet)
+    } else if config.income_statement.iter().any(|a| a.name == name) {
+        Some(Statement::IncomeStatement)
+    } else {
+        None
+    }
+}
+
+/// Points driver-linked balance sheet accounts that follow any of `old` at `new` instead.
+fn replace_drivers(config: &mut FinancialHistoryConfig, old: &[String], new: &[String]) {
+    for account in &mut config.balance_sheet {
+        let InterpolationMethod::DriverLinked { drivers } = &mut account.method else {
+            continue;
+        };
+        if !drivers.iter().any(|d| old.contains(d)) {
+            continue;
+        }
+        drivers.retain(|d| !old.contains(d));
+        for name in new {
+            if !drivers.contains(name) {
+                drivers.push(name.clone());
+            }
+        }
+    }
+}
+
+fn account_exists(config: &FinancialHistoryConfig, name: &str) -> bool {
+    config.balance_sheet.iter().any(|a| a.name == name)
+        || config.income_statement.iter().any(|a| a.name == name)
 }
 
 fn find_bs_mut<'a>(
@@ -229,7 +763,12 @@ fn find_is_mut<'a>(
     config.income_statement.iter_mut().find(|a| a.name == name)
 }
 
-fn merge_balance_sheet(config: &mut FinancialHistoryConfig, sources: &[String], target_name: &str) {
+fn merge_balance_sheet(
+    config: &mut FinancialHistoryConfig,
+    index: usize,
+    sources: &[String],
+    target_name: &str,
+) {
     let mut collected_snapshots = Vec::new();
     let mut properties_template = None;
     let mut indices_to_remove = Vec::new();
@@ -237,7 +776,7 @@ fn merge_balance_sheet(config: &mut FinancialHistoryConfig, sources: &[String],
     // 1. Collect data
     for (i, acc) in config.balance_sheet.iter().enumerate() {
         if sources.contains(&acc.name) || acc.name == target_name {
-            collected_snapshots.extend(acc.snapshots.clone());
+            collected_snapshots.extend(acc.snapshots.iter().map(|s| (acc.name.clone(), s.clone())));
             if properties_template.is_none() {
                 properties_template = Some(acc.clone());
             }
@@ -257,19 +796,33 @@ fn merge_balance_sheet(config: &mut FinancialHistoryConfig, sources: &[String],
     if let Some(mut template) = properties_template {
         template.name = target_name.to_string();
 
-        // Sum snapshots by date
-        let mut sums: BTreeMap<NaiveDate, f64> = BTreeMap::new();
-        for snap in collected_snapshots {
-            *sums.entry(snap.date).or_default() += snap.value;
+        // Sum snapshots by date, remembering which accounts and sources went into each
+        let mut sums: BTreeMap<NaiveDate, (f64, Vec<String>, Vec<SourceMetadata>)> =
+            BTreeMap::new();
+        for (account, snap) in collected_snapshots {
+            let entry = sums.entry(snap.date).or_default();
+            entry.0 += snap.value;
+            entry.1.push(account);
+            entry.2.extend(snap.source);
         }
 
+        // The first document stands in for each sum; its lineage lists them all
         template.snapshots = sums
             .into_iter()
-            .map(|(date, value)| BalanceSheetSnapshot {
-                date,
-                value,
-                source: None,
-            })
+            .map(
+                |(date, (value, accounts, contributing))| BalanceSheetSnapshot {
+                    date,
+                    value,
+                    source: contributing.first().cloned(),
+                    override_lineage: Some(OverrideLineage {
+                        modification_index: index,
+                        action: format!("merge of {}", accounts.join(", ")),
+                        accounts,
+                        sources: contributing,
+                        reason: None,
+                    }),
+                },
+            )
             .collect();
 
         config.balance_sheet.push(template);
@@ -278,16 +831,28 @@ fn merge_balance_sheet(config: &mut FinancialHistoryConfig, sources: &[String],
 
 fn merge_income_statement(
     config: &mut FinancialHistoryConfig,
+    index: usize,
     sources: &[String],
     target_name: &str,
 ) {
     let mut collected_constraints = Vec::new();
     let mut properties_template = None;
     let mut indices_to_remove = Vec::new();
+    let merged: Vec<&str> = config
+        .income_statement
+        .iter()
+        .filter(|a| sources.contains(&a.name) || a.name == target_name)
+        .map(|a| a.name.as_str())
+        .collect();
+    let action = format!("merge of {}", merged.join(", "));
 
     for (i, acc) in config.income_statement.iter().enumerate() {
         if sources.contains(&acc.name) || acc.name == target_name {
-            collected_constraints.extend(acc.constraints.clone());
+            // Constraints are kept as they are, so each keeps its own source
+            collected_constraints.extend(acc.constraints.iter().map(|c| PeriodConstraint {
+                override_lineage: lineage(index, &action, &acc.name, c.source.as_ref()),
+                ..c.clone()
+            }));
             if properties_template.is_none() {
                 properties_template = Some(acc.clone());
             }
@@ -307,3 +872,623 @@ fn merge_income_statement(
         config.income_statement.push(template);
     }
 }
+
+/// Records that modification `index` changed a value taken from `account`. The value keeps
+/// the document it originally came from as its `source`.
+fn lineage(
+    index: usize,
+    action: &str,
+    account: &str,
+    source: Option<&SourceMetadata>,
+) -> Option<OverrideLineage> {
+    Some(OverrideLineage {
+        modification_index: index,
+        action: action.to_string(),
+        accounts: vec![account.to_string()],
+        sources: source.into_iter().cloned().collect(),
+        reason: None,
+    })
+}
+
+/// The statement an account was moved to by [`repair_statement_placement`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Statement {
+    BalanceSheet,
+    IncomeStatement,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StatementMove {
+    pub account: String,
+    pub account_type: AccountType,
+    pub to: Statement,
+    /// Snapshots or constraints carried over to the new statement.
+    pub converted: usize,
+    /// Snapshots or constraints that had no equivalent and were discarded.
+    pub dropped: usize,
+}
+
+/// Moves every account whose type belongs to the other statement, returning the repaired
+/// config and one [`StatementMove`] per account moved.
+///
+/// See [`constraints_to_snapshots`] and [`snapshots_to_constraints`] for how values are
+/// converted. An account is left in place if the other statement already has one of that name.
+pub fn repair_statement_placement(
+    config: &FinancialHistoryConfig,
+) -> (FinancialHistoryConfig, Vec<StatementMove>) {
+    let mut repaired = config.clone();
+    let mut moves = Vec::new();
+
+    let (misplaced, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut repaired.balance_sheet)
+        .into_iter()
+        .partition(|a| {
+            !a.account_type.is_balance_sheet()
+                && !config.income_statement.iter().any(|i| i.name == a.name)
+        });
+    repaired.balance_sheet = kept;
+    let mut moved_to_is = Vec::new();
+    for account in misplaced {
+        let converted = snapshots_to_constraints(&account, config.fiscal_year_end_month);
+        moves.push(StatementMove {
+            account: account.name.clone(),
+            account_type: account.account_type.clone(),
+            to: Statement::IncomeStatement,
+            converted: converted.constraints.len(),
+            dropped: account.snapshots.len() - converted.constraints.len(),
+        });
+        moved_to_is.push(converted);
+    }
+
+    let (misplaced, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut repaired.income_statement)
+        .into_iter()
+        .partition(|a| {
+            a.account_type.is_balance_sheet()
+                && !config.balance_sheet.iter().any(|b| b.name == a.name)
+        });
+    repaired.income_statement = kept;
+    for account in misplaced {
+        let converted = constraints_to_snapshots(&account);
+        moves.push(StatementMove {
+            account: account.name.clone(),
+            account_type: account.account_type.clone(),
+            to: Statement::BalanceSheet,
+            converted: converted.snapshots.len(),
+            dropped: account.constraints.len() - converted.snapshots.len(),
+        });
+        repaired.balance_sheet.push(converted);
+    }
+    repaired.income_statement.extend(moved_to_is);
+
+    (repaired, moves)
+}
+
+/// Converts an income statement account into a balance sheet account.
+///
+/// Each constraint's value is read as the closing balance on the last day of its period. When
+/// several periods end in the same month the longest one wins, since that is the figure a
+/// balance sheet for that date reports. Constraints with unparseable periods are dropped.
+pub fn constraints_to_snapshots(account: &IncomeStatementAccount) -> BalanceSheetAccount {
+    let mut closing: BTreeMap<NaiveDate, (NaiveDate, &PeriodConstraint)> = BTreeMap::new();
+    for constraint in &account.constraints {
+        let Ok((start, end)) = constraint.resolve_dates() else {
+            continue;
+        };
+        match closing.get(&end) {
+            Some((existing_start, _)) if *existing_start <= start => {}
+            _ => {
+                closing.insert(end, (start, constraint));
+            }
+        }
+    }
+
+    BalanceSheetAccount {
+        name: account.name.clone(),
+        category: None,
+        account_type: account.account_type.clone(),
+        method: InterpolationMethod::Linear,
+        extrapolation: ExtrapolationPolicy::Hold,
+        snapshots: closing
+            .into_iter()
+            .map(|(date, (_, constraint))| BalanceSheetSnapshot {
+                date,
+                value: constraint.value,
+                source: constraint.source.clone(),
+                override_lineage: constraint.override_lineage.clone(),
+            })
+            .collect(),
+        is_balancing_account: false,
+        noise_factor: account.noise_factor,
+    }
+}
+
+/// Converts a balance sheet account into an income statement account.
+///
+/// Each snapshot is read as a fiscal year-to-date total, the same way trial balances are read by
+/// [`convert_tb_to_config`](crate::convert_tb_to_config): the first snapshot in a fiscal year
+/// becomes a constraint from the year start to its month, and each later one a constraint for
+/// the months since the previous snapshot, valued at the difference.
+pub fn snapshots_to_constraints(
+    account: &BalanceSheetAccount,
+    fiscal_year_end_month: u32,
+) -> IncomeStatementAccount {
+    let rows: Vec<TrialBalanceRow> = account
+        .snapshots
+        .iter()
+        .map(|s| TrialBalanceRow {
+            account_name: account.name.clone(),
+            account_type: account.account_type.clone(),
+            date: s.date,
+            ytd_value: s.value,
+            source_doc: String::new(),
+        })
+        .collect();
+
+    let constraints = ytd_to_movements(&rows, fiscal_year_end_month)
+        .into_iter()
+        .map(|movement| {
+            let snapshot = account.snapshots.iter().rev().find(|s| {
+                s.date.year() == movement.end.year() && s.date.month() == movement.end.month()
+            });
+            PeriodConstraint {
+                period: movement.period(),
+                value: movement.value,
+                source: snapshot.and_then(|s| s.source.clone()),
+                override_lineage: snapshot.and_then(|s| s.override_lineage.clone()),
+            }
+        })
+        .collect();
+
+    IncomeStatementAccount {
+        name: account.name.clone(),
+        account_type: account.account_type.clone(),
+        seasonality_profile: SeasonalityProfileId::Flat,
+        constraints,
+        noise_factor: account.noise_factor,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(y, m, d).unwrap()
+    }
+
+    fn config() -> FinancialHistoryConfig {
+        FinancialHistoryConfig {
+            organization_name: "Test".to_string(),
+            fiscal_year_end_month: 6,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![BalanceSheetAccount {
+                name: "Consulting Fees".to_string(),
+                category: Some("Current Assets".to_string()),
+                account_type: AccountType::Revenue,
+                method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
+                snapshots: vec![
+                    BalanceSheetSnapshot {
+                        date: date(2023, 9, 30),
+                        value: 300.0,
+                        source: Some(SourceMetadata {
+                            document_name: "0".to_string(),
+                            original_text: None,
+                        }),
+                        override_lineage: None,
+                    },
+                    BalanceSheetSnapshot {
+                        date: date(2023, 12, 31),
+                        value: 700.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                ],
+                is_balancing_account: false,
+                noise_factor: 0.0,
+            }],
+            income_statement: vec![IncomeStatementAccount {
+                name: "Bank Loan".to_string(),
+                account_type: AccountType::Liability,
+                seasonality_profile: SeasonalityProfileId::Flat,
+                constraints: vec![
+                    PeriodConstraint {
+                        period: "2022-07:2023-06".to_string(),
+                        value: 50000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                    PeriodConstraint {
+                        period: "2023-06".to_string(),
+                        value: 1000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                    PeriodConstraint {
+                        period: "2023-13".to_string(),
+                        value: 1.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                ],
+                noise_factor: 0.0,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_update_metadata_keeps_types_on_their_statement() {
+        let overrides = FinancialHistoryOverrides {
+            modifications: vec![
+                AccountModification::UpdateMetadata {
+                    target: "Consulting Fees".to_string(),
+                    new_category: None,
+                    new_type: Some(AccountType::OtherIncome),
+                    new_is_balancing_account: None,
+                },
+                AccountModification::UpdateMetadata {
+                    target: "Bank Loan".to_string(),
+                    new_category: None,
+                    new_type: Some(AccountType::Interest),
+                    new_is_balancing_account: None,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let result = overrides.apply(&config());
+        assert_eq!(result.balance_sheet[0].account_type, AccountType::Revenue);
+        assert_eq!(
+            result.income_statement[0].account_type,
+            AccountType::Interest
+        );
+    }
+
+    #[test]
+    fn test_repair_moves_accounts_to_their_statement() {
+        let (repaired, moves) = repair_statement_placement(&config());
+        assert_eq!(moves.len(), 2);
+
+        let fees = &repaired.income_statement[0];
+        assert_eq!(fees.name, "Consulting Fees");
+        let periods: Vec<(&str, f64)> = fees
+            .constraints
+            .iter()
+            .map(|c| (c.period.as_str(), c.value))
+            .collect();
+        assert_eq!(
+            periods,
+            [("2023-07:2023-09", 300.0), ("2023-10:2023-12", 400.0)]
+        );
+        assert!(fees.constraints[0].source.is_some());
+        assert_eq!(moves[0].to, Statement::IncomeStatement);
+        assert_eq!((moves[0].converted, moves[0].dropped), (2, 0));
+
+        let loan = &repaired.balance_sheet[0];
+        assert_eq!(loan.name, "Bank Loan");
+        assert_eq!(loan.snapshots.len(), 1);
+        assert_eq!(loan.snapshots[0].date, date(2023, 6, 30));
+        assert_eq!(loan.snapshots[0].value, 50000.0);
+        assert_eq!(moves[1].to, Statement::BalanceSheet);
+        assert_eq!((moves[1].converted, moves[1].dropped), (1, 2));
+
+        assert!(crate::validation::validate(&repaired)
+            .iter()
+            .all(|d| d.code != crate::validation::DiagnosticCode::WrongStatement));
+    }
+
+    #[test]
+    fn test_try_apply_reports_each_outcome() {
+        let overrides = FinancialHistoryOverrides {
+            modifications: vec![
+                AccountModification::Rename {
+                    target: "Consulting Fee".to_string(),
+                    new_name: "Fees".to_string(),
+                },
+                AccountModification::SetValue {
+                    target: "Consulting Fees".to_string(),
+                    date_or_period: "2023-12".to_string(),
+                    value: 5.0,
+                    previous_value: None,
+                    reason: None,
+                },
+                AccountModification::Rename {
+                    target: "Bank Loan".to_string(),
+                    new_name: "Consulting Fees".to_string(),
+                },
+                AccountModification::ScaleValues {
+                    target: "Bank Loan".to_string(),
+                    factor: -1.0,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let report = overrides.try_apply(&config(), ApplyMode::Lenient).unwrap();
+        assert_eq!(
+            report.outcomes,
+            [
+                ModificationOutcome::TargetMissing("Consulting Fee".to_string()),
+                ModificationOutcome::InvalidDate("2023-12".to_string()),
+                ModificationOutcome::NameCollision("Consulting Fees".to_string()),
+                ModificationOutcome::Applied,
+            ]
+        );
+        assert!(!report.is_clean());
+        assert_eq!(report.failures().count(), 3);
+        assert_eq!(report.config.income_statement[0].name, "Bank Loan");
+        assert_eq!(
+            report.config.income_statement[0].constraints[0].value,
+            -50000.0
+        );
+
+        match overrides.try_apply(&config(), ApplyMode::Strict) {
+            Err(FinancialHistoryError::OverrideFailed { index, reason }) => {
+                assert_eq!(index, 0);
+                assert_eq!(reason, "no account named 'Consulting Fee'");
+            }
+            other => panic!(
+                "expected OverrideFailed, got {:?}",
+                other.map(|r| r.outcomes)
+            ),
+        }
+    }
+
+    #[test]
+    fn test_set_value_adds_or_replaces_by_previous_value() {
+        let set = |value: f64, previous_value: Option<f64>| AccountModification::SetValue {
+            target: "Bank Loan".to_string(),
+            date_or_period: "2023-06".to_string(),
+            value,
+            previous_value,
+            reason: None,
+        };
+        let overrides = FinancialHistoryOverrides {
+            modifications: vec![
+                set(2000.0, None),
+                set(1500.0, Some(1000.0)),
+                set(1.0, Some(7.0)),
+            ],
+            ..Default::default()
+        };
+
+        let report = overrides.try_apply(&config(), ApplyMode::Lenient).unwrap();
+        assert!(matches!(
+            report.outcomes[..],
+            [
+                ModificationOutcome::Applied,
+                ModificationOutcome::Applied,
+                ModificationOutcome::InvalidArguments(_)
+            ]
+        ));
+        let june: Vec<f64> = report.config.income_statement[0]
+            .constraints
+            .iter()
+            .filter(|c| c.period == "2023-06")
+            .map(|c| c.value)
+            .collect();
+        assert_eq!(june, [1500.0, 2000.0]);
+    }
+
+    #[test]
+    fn test_merge_with_missing_source_is_not_applied() {
+        let overrides = FinancialHistoryOverrides {
+            modifications: vec![AccountModification::Merge {
+                sources: vec!["Consulting Fees".to_string(), "Travel".to_string()],
+                target_name: "Other".to_string(),
+            }],
+            ..Default::default()
+        };
+
+        let report = overrides.try_apply(&config(), ApplyMode::Lenient).unwrap();
+        assert_eq!(
+            report.outcomes,
+            [ModificationOutcome::TargetMissing("Travel".to_string())]
+        );
+        assert_eq!(report.config.balance_sheet[0].name, "Consulting Fees");
+    }
+
+    #[test]
+    fn test_merge_and_set_value_keep_lineage() {
+        let mut base = config();
+        let mut accrued = base.balance_sheet[0].clone();
+        accrued.name = "Accrued Fees".to_string();
+        accrued.snapshots[0].source = Some(SourceMetadata {
+            document_name: "1".to_string(),
+            original_text: None,
+        });
+        base.balance_sheet.push(accrued);
+
+        let overrides = FinancialHistoryOverrides {
+            modifications: vec![
+                AccountModification::Merge {
+                    sources: vec!["Consulting Fees".to_string(), "Accrued Fees".to_string()],
+                    target_name: "Fees".to_string(),
+                },
+                AccountModification::SetValue {
+                    target: "Bank Loan".to_string(),
+                    date_or_period: "2023-07".to_string(),
+                    value: 10.0,
+                    previous_value: None,
+                    reason: Some("per loan statement".to_string()),
+                },
+            ],
+            ..Default::default()
+        };
+        let result = overrides.apply(&base);
+
+        let fees = &result.balance_sheet[0];
+        let merged = &fees.snapshots[0];
+        assert_eq!(merged.source.as_ref().unwrap().document_name, "0");
+        let lineage = merged.override_lineage.as_ref().unwrap();
+        assert_eq!(
+            lineage.to_string(),
+            "set by override #0: merge of Consulting Fees, Accrued Fees"
+        );
+        let documents: Vec<&str> = lineage
+            .sources
+            .iter()
+            .map(|s| s.document_name.as_str())
+            .collect();
+        assert_eq!(documents, vec!["0", "1"]);
+
+        let undocumented = &fees.snapshots[1];
+        assert_eq!(undocumented.value, 1400.0);
+        assert!(undocumented.source.is_none());
+        assert!(undocumented.override_lineage.is_some());
+
+        let manual = result.income_statement[0].constraints.last().unwrap();
+        assert!(manual.source.is_none());
+        assert_eq!(
+            manual.override_lineage.as_ref().unwrap().to_string(),
+            "set by override #1: value set manually (per loan statement)"
+        );
+
+        let dense = crate::engine::Densifier::new(6)
+            .without_noise()
+            .densify_balance_sheet(fees)
+            .unwrap();
+        let point = &dense[&date(2023, 9, 30)];
+        assert_eq!(
+            point.override_lineage.as_ref().unwrap().modification_index,
+            0
+        );
+    }
+
+    #[test]
+    fn test_split_reclassify_and_shift_dates() {
+        let json = r#"[
+            {"action": "split", "target": "Consulting Fees", "parts": [
+                {"name": "Fees - Advisory", "percentage": 30.0},
+                {"name": "Fees - Audit", "percentage": 70.0}
+            ]},
+            {"action": "reclassify_period", "source": "Fees - Audit",
+             "target": "Fees - Advisory", "period": "2023-10:2023-12"},
+            {"action": "shift_dates", "target": "Fees - Advisory", "months": -12},
+            {"action": "shift_dates", "target": "Bank Loan", "months": 1}
+        ]"#;
+        let overrides = FinancialHistoryOverrides {
+            modifications: serde_json::from_str(json).unwrap(),
+            ..Default::default()
+        };
+
+        let report = overrides.try_apply(&config(), ApplyMode::Lenient).unwrap();
+        assert_eq!(
+            report.outcomes[..3],
+            [
+                ModificationOutcome::Applied,
+                ModificationOutcome::Applied,
+                ModificationOutcome::Applied
+            ]
+        );
+        assert_eq!(
+            report.outcomes[3],
+            ModificationOutcome::InvalidDate("2023-13".to_string())
+        );
+
+        let values = |name: &str| -> Vec<(NaiveDate, f64)> {
+            let account = report.config.balance_sheet.iter().find(|a| a.name == name);
+            account
+                .unwrap()
+                .snapshots
+                .iter()
+                .map(|s| (s.date, (s.value * 100.0).round() / 100.0))
+                .collect()
+        };
+        assert_eq!(values("Fees - Audit"), [(date(2023, 9, 30), 210.0)]);
+        assert_eq!(
+            values("Fees - Advisory"),
+            [(date(2022, 9, 30), 90.0), (date(2022, 12, 31), 700.0)]
+        );
+        assert!(report
+            .config
+            .balance_sheet
+            .iter()
+            .all(|a| a.name != "Consulting Fees"));
+        assert_eq!(
+            report.config.income_statement[0].constraints[0].period,
+            "2022-07:2023-06"
+        );
+    }
+
+    #[test]
+    fn test_split_and_shift_reject_invalid_arguments() {
+        let split = |a: f64, b: f64| AccountModification::Split {
+            target: "Bank Loan".to_string(),
+            parts: vec![
+                SplitPart {
+                    name: "Loan A".to_string(),
+                    percentage: a,
+                },
+                SplitPart {
+                    name: "Loan B".to_string(),
+                    percentage: b,
+                },
+            ],
+        };
+        let overrides = FinancialHistoryOverrides {
+            modifications: vec![
+                split(30.0, 60.0),
+                split(150.0, -50.0),
+                AccountModification::ShiftDates {
+                    target: "Bank Loan".to_string(),
+                    months: 5_000_000,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let report = overrides.try_apply(&config(), ApplyMode::Lenient).unwrap();
+        assert!(report
+            .outcomes
+            .iter()
+            .all(|o| matches!(o, ModificationOutcome::InvalidArguments(_))));
+        assert_eq!(report.config.income_statement[0].name, "Bank Loan");
+        let periods = |config: &FinancialHistoryConfig| {
+            config.income_statement[0]
+                .constraints
+                .iter()
+                .map(|c| (c.period.clone(), c.value))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(periods(&report.config), periods(&config()));
+    }
+
+    #[test]
+    fn test_driver_references_follow_renames_and_splits() {
+        let mut base = config();
+        base.balance_sheet[0].method = InterpolationMethod::DriverLinked {
+            drivers: vec!["Bank Loan".to_string()],
+        };
+        let overrides = FinancialHistoryOverrides {
+            modifications: vec![
+                AccountModification::Rename {
+                    target: "Bank Loan".to_string(),
+                    new_name: "Interest".to_string(),
+                },
+                AccountModification::Split {
+                    target: "Interest".to_string(),
+                    parts: vec![
+                        SplitPart {
+                            name: "Interest A".to_string(),
+                            percentage: 40.0,
+                        },
+                        SplitPart {
+                            name: "Interest B".to_string(),
+                            percentage: 60.0,
+                        },
+                    ],
+                },
+            ],
+            ..Default::default()
+        };
+
+        let report = overrides.try_apply(&base, ApplyMode::Strict).unwrap();
+        assert_eq!(
+            report.config.balance_sheet[0].method,
+            InterpolationMethod::DriverLinked {
+                drivers: vec!["Interest A".to_string(), "Interest B".to_string()],
+            }
+        );
+    }
+}
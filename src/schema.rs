@@ -20,6 +20,37 @@ pub struct SourceMetadata {
     pub original_text: Option<String>,
 }
 
+/// How an override produced a value: which modification, from which accounts and sources.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct OverrideLineage {
+    /// Position of the modification in `FinancialHistoryOverrides::modifications`.
+    pub modification_index: usize,
+    /// What the modification did, e.g. "merge of Telephone, Internet".
+    pub action: String,
+    /// Accounts whose values contributed.
+    #[serde(default)]
+    pub accounts: Vec<String>,
+    /// Sources of the values that contributed, if any had one.
+    #[serde(default)]
+    pub sources: Vec<SourceMetadata>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+impl std::fmt::Display for OverrideLineage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "set by override #{}: {}",
+            self.modification_index, self.action
+        )?;
+        if let Some(reason) = &self.reason {
+            write!(f, " ({})", reason)?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub enum AccountType {
@@ -61,6 +92,11 @@ pub enum AccountType {
     )]
     IncomeTax,
 
+    #[schemars(
+        description = "Dividends or drawings distributed to owners. Reduces retained earnings but is not an expense (Income Statement-style period flow, debit balance)"
+    )]
+    Dividends,
+
     #[schemars(
         description = "Resources owned by the company: cash, accounts receivable, inventory, equipment (Balance Sheet, debit balance)"
     )]
@@ -77,6 +113,16 @@ pub enum AccountType {
     Equity,
 }
 
+impl AccountType {
+    /// Whether accounts of this type belong on the balance sheet rather than the income statement.
+    pub fn is_balance_sheet(&self) -> bool {
+        matches!(
+            self,
+            AccountType::Asset | AccountType::Liability | AccountType::Equity
+        )
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
 #[serde(rename_all = "PascalCase")]
 pub enum SeasonalityProfileId {
@@ -91,7 +137,7 @@ pub enum SeasonalityProfileId {
     RetailPeak,
 
     #[schemars(
-        description = "Summer tourism pattern: Low in Q1 (5% each), high in Q2/Q3 (12% each), moderate Q4 (7% each). For hospitality, travel, outdoor recreation."
+        description = "Summer tourism pattern: Low in Q1 (5% each), high in Q2/Q3 (12% each), moderate Q4 (7% each). For hospitality, travel, outdoor recreation in the Northern Hemisphere; for NZ/AU use Named('SouthernSummerHigh')."
     )]
     SummerHigh,
 
@@ -109,6 +155,16 @@ pub enum SeasonalityProfileId {
         )]
         Vec<f64>,
     ),
+
+    #[schemars(
+        description = "Learn the shape from this account's own monthly values. Use when at least one full fiscal year has a constraint for every single month; other years' quarterly or annual totals are then spread in the same pattern. Falls back to Flat if no full year of months exists."
+    )]
+    Learned,
+
+    #[schemars(
+        description = "A named profile from the seasonality registry. Built-ins: 'SouthernSummerHigh' (NZ/AU summer peak Dec-Mar), 'WinterHigh' (Northern winter peak Dec-Feb), 'SouthernWinterHigh' (NZ/AU winter peak Jun-Aug), 'SchoolTerm' (dips in NZ/AU school holidays), 'Agricultural' (NZ/AU harvest peak Feb-May). Prefer these over SummerHigh for Southern Hemisphere businesses."
+    )]
+    Named(#[schemars(description = "Exact name of the registered profile")] String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -124,6 +180,11 @@ pub struct BalanceSheetSnapshot {
     #[serde(default)]
     #[schemars(description = "Metadata to trace this value back to the source document.")]
     pub source: Option<SourceMetadata>,
+
+    /// Set when the value was written by an override rather than read from a document.
+    #[serde(default, rename = "override", skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub override_lineage: Option<OverrideLineage>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, JsonSchema)]
@@ -140,9 +201,56 @@ pub enum InterpolationMethod {
     Step,
 
     #[schemars(
-        description = "Smooth curve (Catmull-Rom) between snapshots. Best for organic changes in balance sheet accounts."
+        description = "Jump to the next snapshot's value straight after each snapshot. Use for balances that change at the start of a period (e.g. a loan drawn down early in the year, or a new share issue)."
+    )]
+    StepNext,
+
+    #[schemars(
+        description = "Smooth S-shaped (cosine) ease between each pair of snapshots. Best for organic changes in balance sheet accounts."
     )]
     Curve,
+
+    #[schemars(
+        description = "Smooth, shape-preserving curve (monotone cubic) that never overshoots or dips between snapshots. Use when several snapshots exist and Curve would look wavy."
+    )]
+    MonotoneCubic,
+
+    #[schemars(
+        description = "Constant percentage growth between snapshots. Use for balances that compound (e.g. investments, accumulating interest). Falls back to Linear when a snapshot is zero or the sign changes."
+    )]
+    CompoundGrowth,
+
+    #[schemars(
+        description = "Move with income statement accounts between snapshots, like days sales outstanding. Use for Accounts Receivable (driven by Revenue), Accounts Payable (driven by Cost of Sales) and Inventory (driven by Cost of Sales). Snapshots are still met exactly."
+    )]
+    DriverLinked {
+        #[schemars(
+            description = "Names of the income statement accounts whose monthly values drive this balance. Their values are added together."
+        )]
+        drivers: Vec<String>,
+    },
+}
+
+/// How a balance sheet account is filled in for months of the history window that lie
+/// before its first or after its last snapshot.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "PascalCase")]
+pub enum ExtrapolationPolicy {
+    #[default]
+    #[schemars(
+        description = "Repeat the first snapshot backwards and the last snapshot forwards. Use for most accounts."
+    )]
+    Hold,
+
+    #[schemars(
+        description = "Treat the balance as zero outside the snapshots. Use for accounts that did not exist yet or were closed (e.g. a loan drawn down in the second year)."
+    )]
+    Zero,
+
+    #[schemars(
+        description = "Continue the trend of the two nearest snapshots. Use for accounts that grow or run down steadily (e.g. accumulated depreciation)."
+    )]
+    LinearTrend,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -164,6 +272,12 @@ pub struct BalanceSheetAccount {
     #[schemars(description = "How to interpolate values between snapshots")]
     pub method: InterpolationMethod,
 
+    #[serde(default)]
+    #[schemars(
+        description = "How to fill months of the history window before the first or after the last snapshot. Defaults to Hold."
+    )]
+    pub extrapolation: ExtrapolationPolicy,
+
     #[schemars(
         description = "Array of known balance sheet snapshots. Must have at least one snapshot. These are point-in-time balances, not cumulative totals."
     )]
@@ -200,6 +314,11 @@ pub struct PeriodConstraint {
     #[serde(default)]
     #[schemars(description = "Metadata to trace this value back to the source document.")]
     pub source: Option<SourceMetadata>,
+
+    /// Set when the value was written by an override rather than read from a document.
+    #[serde(default, rename = "override", skip_serializing_if = "Option::is_none")]
+    #[schemars(skip)]
+    pub override_lineage: Option<OverrideLineage>,
 }
 
 impl PeriodConstraint {
@@ -311,6 +430,18 @@ pub struct FinancialHistoryConfig {
     )]
     pub fiscal_year_end_month: u32,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Optional start of the financial history (YYYY-MM-DD). Balance sheet accounts are extrapolated back to this month."
+    )]
+    pub history_start: Option<NaiveDate>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schemars(
+        description = "Optional end of the financial history (YYYY-MM-DD). Balance sheet accounts are extrapolated forward to this month."
+    )]
+    pub history_end: Option<NaiveDate>,
+
     #[schemars(
         description = "Array of Balance Sheet accounts (Assets, Liabilities, Equity) with their snapshots"
     )]
@@ -368,21 +499,26 @@ mod tests {
         let config = FinancialHistoryConfig {
             organization_name: "Test Corp".to_string(),
             fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
             balance_sheet: vec![BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 50000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 75000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: true,
@@ -396,6 +532,7 @@ mod tests {
                     period: "2023-01:2023-12".to_string(),
                     value: 1200000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.05,
             }],
@@ -1,5 +1,9 @@
 use crate::error::{FinancialHistoryError, Result};
-use crate::schema::SeasonalityProfileId;
+use crate::schema::{PeriodConstraint, SeasonalityProfileId};
+use crate::utils::{get_fiscal_month_index, get_fiscal_year_end_for_date};
+use chrono::{Datelike, NaiveDate};
+use std::collections::BTreeMap;
+use std::path::Path;
 
 pub fn get_profile_weights(profile: &SeasonalityProfileId) -> Result<Vec<f64>> {
     let weights = match profile {
@@ -44,11 +48,195 @@ pub fn get_profile_weights(profile: &SeasonalityProfileId) -> Result<Vec<f64>> {
             validate_custom_weights(custom_weights)?;
             custom_weights.clone()
         }
+
+        SeasonalityProfileId::Named(_) => return SeasonalityRegistry::new().weights(profile),
+
+        SeasonalityProfileId::Learned => {
+            return Err(FinancialHistoryError::InvalidSeasonalityWeights(
+                "Learned weights depend on the account's constraints; use infer_profile"
+                    .to_string(),
+            ))
+        }
     };
 
     Ok(weights)
 }
 
+/// Named seasonality profiles that configs can reference with
+/// [`SeasonalityProfileId::Named`].
+///
+/// Weights are 12 calendar-month values (January first) summing to 1.0, the same layout as
+/// [`SeasonalityProfileId::Custom`]. [`SeasonalityRegistry::new`] holds the built-ins below;
+/// teams add their own with [`insert`](Self::insert) or from a JSON file mapping names to
+/// weight arrays, e.g. `{"Ski Season": [0.15, 0.15, ...]}`.
+///
+/// | Name                 | Shape                                                          |
+/// | :------------------- | :------------------------------------------------------------- |
+/// | `SouthernSummerHigh` | High December to March, low June to August                     |
+/// | `WinterHigh`         | High December to February (Northern Hemisphere winter)         |
+/// | `SouthernWinterHigh` | High June to August (Southern Hemisphere winter)               |
+/// | `SchoolTerm`         | Dips in the NZ/AU school holidays: Jan, Apr, Jul, Oct and Dec  |
+/// | `Agricultural`       | Peaks with the Southern Hemisphere harvest, February to May    |
+#[derive(Debug, Clone)]
+pub struct SeasonalityRegistry {
+    profiles: BTreeMap<String, Vec<f64>>,
+}
+
+impl Default for SeasonalityRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SeasonalityRegistry {
+    /// A registry holding the built-in profiles.
+    pub fn new() -> Self {
+        let builtins: [(&str, [f64; 12]); 5] = [
+            (
+                "SouthernSummerHigh",
+                [
+                    0.12, 0.12, 0.12, 0.07, 0.07, 0.07, 0.05, 0.05, 0.05, 0.12, 0.12, 0.12,
+                ],
+            ),
+            (
+                "WinterHigh",
+                [
+                    0.13, 0.12, 0.09, 0.07, 0.06, 0.05, 0.05, 0.05, 0.06, 0.08, 0.10, 0.14,
+                ],
+            ),
+            (
+                "SouthernWinterHigh",
+                [
+                    0.05, 0.05, 0.06, 0.08, 0.10, 0.14, 0.13, 0.12, 0.09, 0.07, 0.06, 0.05,
+                ],
+            ),
+            (
+                "SchoolTerm",
+                [
+                    0.03, 0.10, 0.11, 0.07, 0.11, 0.10, 0.06, 0.11, 0.10, 0.07, 0.10, 0.04,
+                ],
+            ),
+            (
+                "Agricultural",
+                [
+                    0.08, 0.12, 0.15, 0.14, 0.10, 0.05, 0.04, 0.04, 0.05, 0.06, 0.08, 0.09,
+                ],
+            ),
+        ];
+
+        let profiles = builtins
+            .into_iter()
+            .map(|(name, weights)| (name.to_string(), normalize_weights(&weights)))
+            .collect();
+        Self { profiles }
+    }
+
+    /// A registry without the built-ins.
+    pub fn empty() -> Self {
+        Self {
+            profiles: BTreeMap::new(),
+        }
+    }
+
+    /// Adds or replaces a profile after checking it has 12 non-negative weights summing to 1.0.
+    pub fn insert(&mut self, name: impl Into<String>, weights: Vec<f64>) -> Result<()> {
+        let name = name.into();
+        validate_custom_weights(&weights).map_err(|e| match e {
+            FinancialHistoryError::InvalidSeasonalityWeights(reason) => {
+                FinancialHistoryError::InvalidSeasonalityWeights(format!("'{}': {}", name, reason))
+            }
+            other => other,
+        })?;
+        self.profiles.insert(name, weights);
+        Ok(())
+    }
+
+    /// Adds every profile in a JSON object of names to weight arrays. Nothing is added if
+    /// any profile is invalid.
+    pub fn extend_from_json_str(&mut self, json: &str) -> Result<()> {
+        let loaded: BTreeMap<String, Vec<f64>> = serde_json::from_str(json)?;
+        let mut extended = self.clone();
+        for (name, weights) in loaded {
+            extended.insert(name, weights)?;
+        }
+        *self = extended;
+        Ok(())
+    }
+
+    /// Same as [`extend_from_json_str`](Self::extend_from_json_str), reading the JSON from a file.
+    pub fn extend_from_json_file(&mut self, path: impl AsRef<Path>) -> Result<()> {
+        self.extend_from_json_str(&std::fs::read_to_string(path)?)
+    }
+
+    /// The built-ins plus the profiles in a JSON file.
+    pub fn load_json(path: impl AsRef<Path>) -> Result<Self> {
+        let mut registry = Self::new();
+        registry.extend_from_json_file(path)?;
+        Ok(registry)
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[f64]> {
+        self.profiles.get(name).map(Vec::as_slice)
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+
+    /// Calendar weights for any profile, looking [`SeasonalityProfileId::Named`] up here.
+    pub fn weights(&self, profile: &SeasonalityProfileId) -> Result<Vec<f64>> {
+        match profile {
+            SeasonalityProfileId::Named(name) => self
+                .get(name)
+                .map(<[f64]>::to_vec)
+                .ok_or_else(|| FinancialHistoryError::UnknownSeasonalityProfile(name.clone())),
+            other => get_profile_weights(other),
+        }
+    }
+}
+
+/// Learns seasonality weights from the single-month constraints of an account.
+///
+/// Only fiscal years with all twelve months constrained are used. Each year's months are
+/// turned into shares of its total and the shares are averaged across years, so a growing
+/// business does not weight its latest year more heavily. Negative months count as zero.
+///
+/// The weights are in fiscal order (index 0 is the first month after the fiscal year end).
+/// Returns `None` when no fiscal year is complete or every complete year totals zero.
+pub fn infer_profile(
+    constraints: &[PeriodConstraint],
+    fiscal_year_end_month: u32,
+) -> Option<Vec<f64>> {
+    let mut years: BTreeMap<NaiveDate, [Option<f64>; 12]> = BTreeMap::new();
+    for constraint in constraints {
+        let Ok((start, end)) = constraint.resolve_dates() else {
+            continue;
+        };
+        if (start.year(), start.month()) != (end.year(), end.month()) {
+            continue;
+        }
+        let year_end = get_fiscal_year_end_for_date(end, fiscal_year_end_month);
+        let month = get_fiscal_month_index(end.month(), fiscal_year_end_month);
+        years.entry(year_end).or_insert([None; 12])[month].get_or_insert(constraint.value);
+    }
+
+    let shares: Vec<Vec<f64>> = years
+        .values()
+        .filter_map(|months| months.iter().copied().collect::<Option<Vec<f64>>>())
+        .map(|values| values.into_iter().map(|v| v.max(0.0)).collect::<Vec<_>>())
+        .filter(|values| values.iter().sum::<f64>() > 0.0)
+        .map(|values| normalize_weights(&values))
+        .collect();
+    if shares.is_empty() {
+        return None;
+    }
+
+    let averaged: Vec<f64> = (0..12)
+        .map(|m| shares.iter().map(|s| s[m]).sum::<f64>() / shares.len() as f64)
+        .collect();
+    Some(averaged)
+}
+
 fn validate_custom_weights(weights: &[f64]) -> Result<()> {
     if weights.len() != 12 {
         return Err(FinancialHistoryError::InvalidSeasonalityWeights(format!(
@@ -164,6 +352,95 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_registry_builtins_are_valid() {
+        let registry = SeasonalityRegistry::new();
+        for name in registry.names() {
+            assert!(
+                validate_custom_weights(registry.get(name).unwrap()).is_ok(),
+                "{}",
+                name
+            );
+        }
+
+        let southern = get_profile_weights(&SeasonalityProfileId::Named(
+            "SouthernSummerHigh".to_string(),
+        ))
+        .unwrap();
+        assert!(southern[0] > southern[6]);
+        assert!(matches!(
+            registry.weights(&SeasonalityProfileId::Named("Ski Season".to_string())),
+            Err(FinancialHistoryError::UnknownSeasonalityProfile(_))
+        ));
+    }
+
+    #[test]
+    fn test_registry_loads_json_profiles_atomically() {
+        let mut registry = SeasonalityRegistry::empty();
+        registry
+            .extend_from_json_str(
+                r#"{"Ski Season": [0.2, 0.2, 0.1, 0, 0, 0.05, 0.1, 0.1, 0.05, 0, 0, 0.2]}"#,
+            )
+            .unwrap();
+        let weights = registry
+            .weights(&SeasonalityProfileId::Named("Ski Season".to_string()))
+            .unwrap();
+        assert_eq!(weights[0], 0.2);
+
+        let result = registry
+            .extend_from_json_str(r#"{"A Good One": [0.25, 0.25, 0.25, 0.25, 0, 0, 0, 0, 0, 0, 0, 0], "Short": [0.5, 0.5]}"#);
+        assert!(result.unwrap_err().to_string().contains("'Short'"));
+        assert_eq!(registry.names().collect::<Vec<_>>(), ["Ski Season"]);
+    }
+
+    /// Single-month constraints for consecutive months starting at `year`-`month`.
+    fn months(year: i32, month: u32, values: &[f64]) -> Vec<PeriodConstraint> {
+        values
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                let index = (month - 1) as usize + i;
+                PeriodConstraint {
+                    period: format!("{}-{:02}", year + (index / 12) as i32, index % 12 + 1),
+                    value,
+                    source: None,
+                    override_lineage: None,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_infer_profile_averages_complete_fiscal_years() {
+        // Fiscal year ending June: July 2022 to June 2023 is complete, July 2023 onwards is not
+        let mut year = vec![10.0; 12];
+        year[0] = 20.0;
+        year[6] = 30.0;
+        let mut constraints = months(2022, 7, &year);
+        constraints.extend(months(2023, 7, &[50.0; 3]));
+        constraints.push(PeriodConstraint {
+            period: "2022-07:2023-06".to_string(),
+            value: 999.0,
+            source: None,
+            override_lineage: None,
+        });
+
+        let weights = infer_profile(&constraints, 6).unwrap();
+        assert!((weights.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        // Index 0 is July (20 of 150), index 6 is January (30 of 150)
+        assert!((weights[0] - 20.0 / 150.0).abs() < 1e-12);
+        assert!((weights[6] - 30.0 / 150.0).abs() < 1e-12);
+
+        // A second, larger year counts the same as the first: shares are averaged
+        constraints.extend(months(2023, 10, &[100.0; 9]));
+        let weights = infer_profile(&constraints, 6).unwrap();
+        let july_2023_share = 50.0 / 1050.0;
+        assert!((weights[0] - (20.0 / 150.0 + july_2023_share) / 2.0).abs() < 1e-12);
+
+        assert!(infer_profile(&months(2023, 1, &[1.0; 11]), 12).is_none());
+        assert!(get_profile_weights(&SeasonalityProfileId::Learned).is_err());
+    }
+
     #[test]
     fn test_rotate_weights() {
         let weights = vec![
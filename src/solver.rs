@@ -0,0 +1,154 @@
+//! Small dense linear algebra used by the least-squares income statement solver.
+//!
+//! Problem sizes are tiny (one row per period constraint), so a Jacobi eigen-decomposition
+//! is used for its robustness on the singular systems produced by redundant or
+//! inconsistent constraints.
+
+/// Eigenvalues below this fraction of the largest one are treated as zero.
+const RELATIVE_RANK_TOLERANCE: f64 = 1e-12;
+const MAX_JACOBI_SWEEPS: usize = 100;
+
+/// Finds the monthly values `x` that satisfy `A x = b` as closely as possible, and among
+/// those the one minimising `sum(x_m^2 / w_m)`.
+///
+/// `memberships[i]` lists the month indices covered by constraint `i` (row `i` of `A`, all ones),
+/// `targets[i]` is `b_i` and `weights[m]` is the (strictly positive) seasonality weight `w_m`.
+///
+/// The closed form is `x = W Aᵀ y` with `y = (A W Aᵀ)⁺ b`. Using the pseudo-inverse means
+/// consistent constraint sets are met exactly, while inconsistent ones get the least-squares
+/// compromise on the constraint totals.
+pub(crate) fn solve_weighted_min_norm(
+    memberships: &[Vec<usize>],
+    targets: &[f64],
+    weights: &[f64],
+) -> Vec<f64> {
+    let k = memberships.len();
+
+    let mut gram = vec![vec![0.0; k]; k];
+    for i in 0..k {
+        for j in i..k {
+            let overlap: f64 = memberships[i]
+                .iter()
+                .filter(|m| memberships[j].contains(m))
+                .map(|&m| weights[m])
+                .sum();
+            gram[i][j] = overlap;
+            gram[j][i] = overlap;
+        }
+    }
+
+    let y = pseudo_inverse_solve(gram, targets);
+
+    let mut x = vec![0.0; weights.len()];
+    for (i, months) in memberships.iter().enumerate() {
+        for &m in months {
+            x[m] += weights[m] * y[i];
+        }
+    }
+    x
+}
+
+/// Solves `M y = b` for symmetric positive semi-definite `M` using its pseudo-inverse.
+fn pseudo_inverse_solve(matrix: Vec<Vec<f64>>, rhs: &[f64]) -> Vec<f64> {
+    let (eigenvalues, eigenvectors) = jacobi_eigen(matrix);
+    let n = rhs.len();
+
+    let max_eigen = eigenvalues.iter().cloned().fold(0.0_f64, f64::max);
+    let cutoff = max_eigen * RELATIVE_RANK_TOLERANCE;
+
+    let mut y = vec![0.0; n];
+    for (col, &lambda) in eigenvalues.iter().enumerate() {
+        if lambda <= cutoff {
+            continue;
+        }
+        let projection: f64 = (0..n).map(|row| eigenvectors[row][col] * rhs[row]).sum();
+        let scale = projection / lambda;
+        for (row, value) in y.iter_mut().enumerate() {
+            *value += scale * eigenvectors[row][col];
+        }
+    }
+    y
+}
+
+/// Cyclic Jacobi eigenvalue algorithm for symmetric matrices.
+/// Returns the eigenvalues and a matrix whose columns are the matching eigenvectors.
+fn jacobi_eigen(mut a: Vec<Vec<f64>>) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = a.len();
+    let mut v = vec![vec![0.0; n]; n];
+    for (i, row) in v.iter_mut().enumerate() {
+        row[i] = 1.0;
+    }
+
+    for _ in 0..MAX_JACOBI_SWEEPS {
+        let off_diagonal: f64 = (0..n)
+            .flat_map(|i| (0..n).filter(move |&j| j != i).map(move |j| (i, j)))
+            .map(|(i, j)| a[i][j] * a[i][j])
+            .sum();
+        let diagonal: f64 = (0..n).map(|i| a[i][i] * a[i][i]).sum();
+        if off_diagonal <= f64::EPSILON * f64::EPSILON * diagonal.max(f64::MIN_POSITIVE) {
+            break;
+        }
+
+        for p in 0..n {
+            for q in (p + 1)..n {
+                if a[p][q] == 0.0 {
+                    continue;
+                }
+
+                let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+                let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+                let c = 1.0 / (t * t + 1.0).sqrt();
+                let s = t * c;
+
+                for row in a.iter_mut() {
+                    let akp = row[p];
+                    let akq = row[q];
+                    row[p] = c * akp - s * akq;
+                    row[q] = s * akp + c * akq;
+                }
+                let (upper, lower) = a.split_at_mut(q);
+                for (apk, aqk) in upper[p].iter_mut().zip(lower[0].iter_mut()) {
+                    let (x, y) = (*apk, *aqk);
+                    *apk = c * x - s * y;
+                    *aqk = s * x + c * y;
+                }
+                for row in v.iter_mut() {
+                    let vkp = row[p];
+                    let vkq = row[q];
+                    row[p] = c * vkp - s * vkq;
+                    row[q] = s * vkp + c * vkq;
+                }
+            }
+        }
+    }
+
+    let eigenvalues = (0..n).map(|i| a[i][i]).collect();
+    (eigenvalues, v)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_partial_overlap_is_satisfied_exactly() {
+        // Months 0..6 sum to 600, months 3..12 sum to 900, flat weights.
+        let memberships = vec![(0..6).collect(), (3..12).collect()];
+        let x = solve_weighted_min_norm(&memberships, &[600.0, 900.0], &[1.0; 12]);
+
+        let first: f64 = x[0..6].iter().sum();
+        let second: f64 = x[3..12].iter().sum();
+        assert!((first - 600.0).abs() < 1e-9);
+        assert!((second - 900.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_redundant_constraints_are_satisfied_exactly() {
+        let memberships = vec![vec![0], vec![1], vec![2], vec![0, 1, 2]];
+        let x = solve_weighted_min_norm(&memberships, &[1.0, 2.0, 3.0, 6.0], &[1.0, 1.0, 1.0]);
+
+        assert!((x[0] - 1.0).abs() < 1e-9);
+        assert!((x[1] - 2.0).abs() < 1e-9);
+        assert!((x[2] - 3.0).abs() < 1e-9);
+    }
+}
@@ -1,5 +1,5 @@
 use crate::error::{FinancialHistoryError, Result};
-use chrono::{Datelike, Days, NaiveDate};
+use chrono::{Datelike, Days, Months, NaiveDate};
 
 pub fn next_month_end(date: NaiveDate) -> NaiveDate {
     let year = if date.month() == 12 {
@@ -168,6 +168,24 @@ pub fn parse_period_string(period: &str) -> Result<(NaiveDate, NaiveDate)> {
     }
 }
 
+/// Formats a period as "YYYY-MM", or "YYYY-MM:YYYY-MM" when it spans more than one month.
+pub fn format_period_string(start: NaiveDate, end: NaiveDate) -> String {
+    if (start.year(), start.month()) == (end.year(), end.month()) {
+        end.format("%Y-%m").to_string()
+    } else {
+        format!("{}:{}", start.format("%Y-%m"), end.format("%Y-%m"))
+    }
+}
+
+/// The month-end `months` months after the month of `date` (before it, if negative).
+/// `None` if that falls outside the dates chrono supports.
+pub fn shift_month_end(date: NaiveDate, months: i32) -> Option<NaiveDate> {
+    let index = (date.year() * 12 + date.month0() as i32).checked_add(months)?;
+    NaiveDate::from_ymd_opt(index.div_euclid(12), index.rem_euclid(12) as u32 + 1, 1)?
+        .checked_add_months(Months::new(1))?
+        .pred_opt()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -249,4 +267,23 @@ mod tests {
         assert_eq!(start, NaiveDate::from_ymd_opt(2023, 1, 1).unwrap());
         assert_eq!(end, NaiveDate::from_ymd_opt(2023, 3, 31).unwrap());
     }
+
+    #[test]
+    fn test_shift_month_end_and_format_period() {
+        let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+        assert_eq!(
+            shift_month_end(date, -12),
+            NaiveDate::from_ymd_opt(2023, 2, 28)
+        );
+        assert_eq!(
+            shift_month_end(date, 11),
+            NaiveDate::from_ymd_opt(2025, 1, 31)
+        );
+        assert_eq!(shift_month_end(date, 5_000_000), None);
+        assert_eq!(shift_month_end(date, i32::MAX), None);
+
+        let (start, end) = parse_period_string("2023-01:2023-12").unwrap();
+        assert_eq!(format_period_string(start, end), "2023-01:2023-12");
+        assert_eq!(format_period_string(end, end), "2023-12");
+    }
 }
@@ -0,0 +1,765 @@
+//! Deterministic checks on a [`FinancialHistoryConfig`] before it is processed.
+//!
+//! [`validate`] never stops at the first problem: it returns every finding as a [`Diagnostic`]
+//! with the offending account, a field path into the config and, where there is an obvious
+//! one, a suggested fix.
+
+use crate::engine::{ConstraintStatus, Densifier, IncomeStatementSolver, CONSTRAINT_TOLERANCE};
+use crate::schema::{FinancialHistoryConfig, InterpolationMethod, SeasonalityProfileId};
+use crate::seasonality::{infer_profile, SeasonalityRegistry};
+use crate::utils::{last_day_of_month, validate_fiscal_year_end_month};
+use chrono::Datelike;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+
+/// Smallest gap between a constraint and its best achievable total that is treated as a
+/// conflict rather than rounding in the source documents. Matches the extractor's tolerance
+/// for the accounting equation.
+const ROUNDING_TOLERANCE: f64 = 1.0;
+/// Larger totals may miss by this fraction of the requested value before it is a conflict.
+const ROUNDING_FRACTION: f64 = 0.001;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Severity {
+    Info,
+    /// The config processes, but the result is probably not what was intended.
+    Warning,
+    /// Processing will fail or silently produce wrong numbers.
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiagnosticCode {
+    InvalidFiscalYearEndMonth,
+    DuplicateName,
+    MissingSource,
+    NoBalancingAccount,
+    MultipleBalancingAccounts,
+    /// An income statement type on a balance sheet account, or the reverse.
+    WrongStatement,
+    SnapshotNotMonthEnd,
+    InvalidNoiseFactor,
+    InvalidSeasonalityWeights,
+    /// A `Learned` seasonality profile with no complete fiscal year to learn from; it
+    /// falls back to Flat.
+    LearnedSeasonalityFallback,
+    /// A named seasonality profile that is not in the registry.
+    UnknownSeasonalityProfile,
+    InvalidPeriod,
+    /// A driver-linked balance naming a driver that is not an income statement account.
+    UnknownDriver,
+    UnsatisfiableConstraint,
+    /// A period whose months are all fixed by smaller periods; it is met but has no effect.
+    RedundantConstraint,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub code: DiagnosticCode,
+    pub account: Option<String>,
+    /// Location in the config, e.g. `balance_sheet[2].snapshots[0].source`.
+    pub path: String,
+    pub message: String,
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(
+        severity: Severity,
+        code: DiagnosticCode,
+        path: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            code,
+            account: None,
+            path: path.into(),
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn with_account(mut self, account: &str) -> Self {
+        self.account = Some(account.to_string());
+        self
+    }
+
+    fn with_suggestion(mut self, suggestion: impl Into<String>) -> Self {
+        self.suggestion = Some(suggestion.into());
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} at {}: {}", self.severity, self.path, self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, " ({})", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs every check and returns the findings, most severe first.
+pub fn validate(config: &FinancialHistoryConfig) -> Vec<Diagnostic> {
+    validate_with_registry(config, &SeasonalityRegistry::new())
+}
+
+/// Same as [`validate`], resolving named seasonality profiles in `registry`.
+pub fn validate_with_registry(
+    config: &FinancialHistoryConfig,
+    registry: &SeasonalityRegistry,
+) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let fiscal_year_valid = validate_fiscal_year_end_month(config.fiscal_year_end_month).is_ok();
+    if !fiscal_year_valid {
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Error,
+                DiagnosticCode::InvalidFiscalYearEndMonth,
+                "fiscal_year_end_month",
+                format!(
+                    "Fiscal year end month {} is not between 1 and 12",
+                    config.fiscal_year_end_month
+                ),
+            )
+            .with_suggestion("Use 12 for a December year end, 6 for June, 3 for March"),
+        );
+    }
+
+    if let (Some(start), Some(end)) = (config.history_start, config.history_end) {
+        if end < start {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    DiagnosticCode::InvalidPeriod,
+                    "history_end",
+                    format!("History window ends ({}) before it starts ({})", end, start),
+                )
+                .with_suggestion("Swap history_start and history_end"),
+            );
+        }
+    }
+
+    check_duplicate_names(config, &mut diagnostics);
+    check_balancing_accounts(config, &mut diagnostics);
+    check_balance_sheet(config, &mut diagnostics);
+    check_income_statement(config, registry, fiscal_year_valid, &mut diagnostics);
+    if fiscal_year_valid {
+        check_constraints_satisfiable(config, registry, &mut diagnostics);
+    }
+
+    diagnostics.sort_by_key(|d| std::cmp::Reverse(d.severity));
+    diagnostics
+}
+
+fn check_duplicate_names(config: &FinancialHistoryConfig, diagnostics: &mut Vec<Diagnostic>) {
+    let names = config
+        .balance_sheet
+        .iter()
+        .enumerate()
+        .map(|(i, a)| (format!("balance_sheet[{}].name", i), &a.name))
+        .chain(
+            config
+                .income_statement
+                .iter()
+                .enumerate()
+                .map(|(i, a)| (format!("income_statement[{}].name", i), &a.name)),
+        );
+
+    let mut first_seen: BTreeMap<&str, String> = BTreeMap::new();
+    for (path, name) in names {
+        match first_seen.get(name.as_str()) {
+            Some(original) => diagnostics.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    DiagnosticCode::DuplicateName,
+                    path,
+                    format!("Account name '{}' is already used at {}", name, original),
+                )
+                .with_account(name)
+                .with_suggestion("Merge the two accounts or rename one of them"),
+            ),
+            None => {
+                first_seen.insert(name, path);
+            }
+        }
+    }
+}
+
+fn check_balancing_accounts(config: &FinancialHistoryConfig, diagnostics: &mut Vec<Diagnostic>) {
+    let flagged: Vec<usize> = (0..config.balance_sheet.len())
+        .filter(|&i| config.balance_sheet[i].is_balancing_account)
+        .collect();
+
+    if flagged.is_empty() && !config.balance_sheet.is_empty() {
+        let cash = config.balance_sheet.iter().find(|a| {
+            let name = a.name.to_lowercase();
+            name.contains("cash") || name.contains("bank")
+        });
+        let suggestion = match cash {
+            Some(account) => format!("Set is_balancing_account on '{}'", account.name),
+            None => "Set is_balancing_account on the main cash or bank account".to_string(),
+        };
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Warning,
+                DiagnosticCode::NoBalancingAccount,
+                "balance_sheet",
+                "No balance sheet account is flagged as the balancing account",
+            )
+            .with_suggestion(suggestion),
+        );
+    }
+
+    for &i in flagged.iter().skip(1) {
+        let account = &config.balance_sheet[i];
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Warning,
+                DiagnosticCode::MultipleBalancingAccounts,
+                format!("balance_sheet[{}].is_balancing_account", i),
+                format!(
+                    "'{}' is flagged as a balancing account, but so is '{}'",
+                    account.name, config.balance_sheet[flagged[0]].name
+                ),
+            )
+            .with_account(&account.name)
+            .with_suggestion("Keep a single balancing account, usually cash"),
+        );
+    }
+}
+
+fn check_balance_sheet(config: &FinancialHistoryConfig, diagnostics: &mut Vec<Diagnostic>) {
+    for (i, account) in config.balance_sheet.iter().enumerate() {
+        let path = format!("balance_sheet[{}]", i);
+
+        if !account.account_type.is_balance_sheet() {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    DiagnosticCode::WrongStatement,
+                    format!("{}.account_type", path),
+                    format!(
+                        "{:?} is an income statement type, but '{}' is a balance sheet account",
+                        account.account_type, account.name
+                    ),
+                )
+                .with_account(&account.name)
+                .with_suggestion(
+                    "Move it with repair_statement_placement, or use Asset, Liability or Equity",
+                ),
+            );
+        }
+
+        check_noise_factor(account.noise_factor, &path, &account.name, diagnostics);
+
+        if let InterpolationMethod::DriverLinked { drivers } = &account.method {
+            if drivers.is_empty() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticCode::UnknownDriver,
+                        format!("{}.method", path),
+                        "Driver-linked account has no drivers".to_string(),
+                    )
+                    .with_account(&account.name)
+                    .with_suggestion("Name the income statement accounts it follows, e.g. Sales"),
+                );
+            }
+            for (j, driver) in drivers.iter().enumerate() {
+                if !config.income_statement.iter().any(|a| &a.name == driver) {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            Severity::Error,
+                            DiagnosticCode::UnknownDriver,
+                            format!("{}.method.drivers[{}]", path, j),
+                            format!("Driver '{}' is not an income statement account", driver),
+                        )
+                        .with_account(&account.name)
+                        .with_suggestion("Use the exact name of an income statement account"),
+                    );
+                }
+            }
+        }
+
+        for (j, snapshot) in account.snapshots.iter().enumerate() {
+            let snapshot_path = format!("{}.snapshots[{}]", path, j);
+            if snapshot.source.is_none() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        DiagnosticCode::MissingSource,
+                        format!("{}.source", snapshot_path),
+                        format!("Snapshot on {} has no source document", snapshot.date),
+                    )
+                    .with_account(&account.name)
+                    .with_suggestion("Record the document the balance was taken from"),
+                );
+            }
+
+            let month_end = last_day_of_month(snapshot.date.year(), snapshot.date.month());
+            if snapshot.date != month_end {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        DiagnosticCode::SnapshotNotMonthEnd,
+                        format!("{}.date", snapshot_path),
+                        format!("Snapshot date {} is not a month end", snapshot.date),
+                    )
+                    .with_account(&account.name)
+                    .with_suggestion(format!("Use {}", month_end)),
+                );
+            }
+        }
+    }
+}
+
+fn check_income_statement(
+    config: &FinancialHistoryConfig,
+    registry: &SeasonalityRegistry,
+    fiscal_year_valid: bool,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    for (i, account) in config.income_statement.iter().enumerate() {
+        let path = format!("income_statement[{}]", i);
+
+        if account.account_type.is_balance_sheet() {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Error,
+                    DiagnosticCode::WrongStatement,
+                    format!("{}.account_type", path),
+                    format!(
+                        "{:?} is a balance sheet type, but '{}' is an income statement account",
+                        account.account_type, account.name
+                    ),
+                )
+                .with_account(&account.name)
+                .with_suggestion(
+                    "Move it with repair_statement_placement, or use an income statement type",
+                ),
+            );
+        }
+
+        check_noise_factor(account.noise_factor, &path, &account.name, diagnostics);
+
+        match &account.seasonality_profile {
+            SeasonalityProfileId::Named(name) if registry.get(name).is_none() => {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticCode::UnknownSeasonalityProfile,
+                        format!("{}.seasonality_profile", path),
+                        format!("No seasonality profile is registered as '{}'", name),
+                    )
+                    .with_account(&account.name)
+                    .with_suggestion(format!(
+                        "Use one of: {}",
+                        registry.names().collect::<Vec<_>>().join(", ")
+                    )),
+                );
+            }
+            SeasonalityProfileId::Custom(_) => {
+                if let Err(e) = registry.weights(&account.seasonality_profile) {
+                    diagnostics.push(
+                        Diagnostic::new(
+                            Severity::Error,
+                            DiagnosticCode::InvalidSeasonalityWeights,
+                            format!("{}.seasonality_profile", path),
+                            e.to_string(),
+                        )
+                        .with_account(&account.name)
+                        .with_suggestion(
+                            "Provide 12 non-negative weights summing to 1.0, or use Flat",
+                        ),
+                    );
+                }
+            }
+            _ => {}
+        }
+
+        if account.seasonality_profile == SeasonalityProfileId::Learned
+            && fiscal_year_valid
+            && infer_profile(&account.constraints, config.fiscal_year_end_month).is_none()
+        {
+            diagnostics.push(
+                Diagnostic::new(
+                    Severity::Warning,
+                    DiagnosticCode::LearnedSeasonalityFallback,
+                    format!("{}.seasonality_profile", path),
+                    "No fiscal year has a constraint for every month, so Learned falls back to Flat"
+                        .to_string(),
+                )
+                .with_account(&account.name)
+                .with_suggestion("Add the missing monthly values, or choose a fixed profile"),
+            );
+        }
+
+        for (j, constraint) in account.constraints.iter().enumerate() {
+            let constraint_path = format!("{}.constraints[{}]", path, j);
+
+            match constraint.resolve_dates() {
+                Ok((start, end)) if end < start => diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticCode::InvalidPeriod,
+                        format!("{}.period", constraint_path),
+                        format!("Period '{}' ends before it starts", constraint.period),
+                    )
+                    .with_account(&account.name)
+                    .with_suggestion("Swap the start and end months"),
+                ),
+                Ok(_) => {}
+                Err(e) => diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticCode::InvalidPeriod,
+                        format!("{}.period", constraint_path),
+                        format!("Period '{}' cannot be parsed: {}", constraint.period, e),
+                    )
+                    .with_account(&account.name)
+                    .with_suggestion("Use 'YYYY-MM' or 'YYYY-MM:YYYY-MM'"),
+                ),
+            }
+
+            if constraint.source.is_none() {
+                diagnostics.push(
+                    Diagnostic::new(
+                        Severity::Warning,
+                        DiagnosticCode::MissingSource,
+                        format!("{}.source", constraint_path),
+                        format!("Constraint '{}' has no source document", constraint.period),
+                    )
+                    .with_account(&account.name)
+                    .with_suggestion("Record the document the total was taken from"),
+                );
+            }
+        }
+    }
+}
+
+fn check_noise_factor(
+    noise_factor: f64,
+    path: &str,
+    account: &str,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    if !(0.0..=1.0).contains(&noise_factor) {
+        diagnostics.push(
+            Diagnostic::new(
+                Severity::Error,
+                DiagnosticCode::InvalidNoiseFactor,
+                format!("{}.noise_factor", path),
+                format!("Noise factor {} is outside 0.0 to 1.0", noise_factor),
+            )
+            .with_account(account)
+            .with_suggestion("Use 0.0 for no noise, or a small value such as 0.05"),
+        );
+    }
+}
+
+/// Solves each income statement account jointly and flags constraints no monthly series can
+/// honour together with the others, or that add nothing. Accounts that fail to solve were already reported above.
+fn check_constraints_satisfiable(
+    config: &FinancialHistoryConfig,
+    registry: &SeasonalityRegistry,
+    diagnostics: &mut Vec<Diagnostic>,
+) {
+    let densifier = Densifier::new(config.fiscal_year_end_month)
+        .without_noise()
+        .with_solver(IncomeStatementSolver::LeastSquares)
+        .with_seasonality_registry(registry.clone());
+
+    for (i, account) in config.income_statement.iter().enumerate() {
+        let Ok(solution) = densifier.solve_income_statement(account) else {
+            continue;
+        };
+
+        for (j, outcome) in solution.constraints.iter().enumerate() {
+            let path = format!("income_statement[{}].constraints[{}].value", i, j);
+
+            let diagnostic = match outcome.status {
+                ConstraintStatus::Conflicting
+                    if outcome.residual.abs() > rounding_tolerance(outcome.requested) =>
+                {
+                    Diagnostic::new(
+                        Severity::Error,
+                        DiagnosticCode::UnsatisfiableConstraint,
+                        path,
+                        format!(
+                            "Period '{}' requires {:.2}, but the closest the other constraints allow is {:.2}",
+                            outcome.period, outcome.requested, outcome.achieved
+                        ),
+                    )
+                    .with_suggestion(
+                        "Check the overlapping periods; one of the totals is likely mis-keyed",
+                    )
+                }
+                ConstraintStatus::Conflicting if outcome.residual.abs() > CONSTRAINT_TOLERANCE => {
+                    Diagnostic::new(
+                        Severity::Warning,
+                        DiagnosticCode::UnsatisfiableConstraint,
+                        path,
+                        format!(
+                            "Period '{}' requires {:.2}, but the other constraints only allow {:.2}",
+                            outcome.period, outcome.requested, outcome.achieved
+                        ),
+                    )
+                    .with_suggestion("The gap is small enough to be rounding in the source figures")
+                }
+                ConstraintStatus::Conflicting => Diagnostic::new(
+                    Severity::Warning,
+                    DiagnosticCode::UnsatisfiableConstraint,
+                    path,
+                    format!(
+                        "Period '{}' is only met by pushing some months against the sign of the total",
+                        outcome.period
+                    ),
+                )
+                .with_suggestion("Smaller periods inside it already exceed the total"),
+                ConstraintStatus::OverDetermined => Diagnostic::new(
+                    Severity::Info,
+                    DiagnosticCode::RedundantConstraint,
+                    path,
+                    format!(
+                        "Period '{}' is fully covered by smaller periods and has no influence",
+                        outcome.period
+                    ),
+                ),
+                ConstraintStatus::Satisfied | ConstraintStatus::OutOfRange => continue,
+            };
+            diagnostics.push(diagnostic.with_account(&account.name));
+        }
+    }
+}
+
+/// Largest gap between a constraint and its achieved total that is still put down to rounding.
+fn rounding_tolerance(requested: f64) -> f64 {
+    ROUNDING_TOLERANCE.max(requested.abs() * ROUNDING_FRACTION)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::{
+        AccountType, BalanceSheetAccount, BalanceSheetSnapshot, ExtrapolationPolicy,
+        IncomeStatementAccount, InterpolationMethod, PeriodConstraint, SourceMetadata,
+    };
+    use chrono::NaiveDate;
+
+    fn source() -> Option<SourceMetadata> {
+        Some(SourceMetadata {
+            document_name: "0".to_string(),
+            original_text: None,
+        })
+    }
+
+    fn balance(name: &str, account_type: AccountType, date: NaiveDate) -> BalanceSheetAccount {
+        BalanceSheetAccount {
+            name: name.to_string(),
+            category: None,
+            account_type,
+            method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
+            snapshots: vec![BalanceSheetSnapshot {
+                date,
+                value: 1000.0,
+                source: source(),
+                override_lineage: None,
+            }],
+            is_balancing_account: false,
+            noise_factor: 0.0,
+        }
+    }
+
+    fn constraint(period: &str, value: f64) -> PeriodConstraint {
+        PeriodConstraint {
+            period: period.to_string(),
+            value,
+            source: source(),
+            override_lineage: None,
+        }
+    }
+
+    fn income(name: &str, constraints: Vec<PeriodConstraint>) -> IncomeStatementAccount {
+        IncomeStatementAccount {
+            name: name.to_string(),
+            account_type: AccountType::Revenue,
+            seasonality_profile: SeasonalityProfileId::Flat,
+            constraints,
+            noise_factor: 0.0,
+        }
+    }
+
+    fn clean_config() -> FinancialHistoryConfig {
+        let year_end = NaiveDate::from_ymd_opt(2023, 12, 31).unwrap();
+        let mut cash = balance("Cash", AccountType::Asset, year_end);
+        cash.is_balancing_account = true;
+        FinancialHistoryConfig {
+            organization_name: "Test".to_string(),
+            fiscal_year_end_month: 12,
+            history_start: None,
+            history_end: None,
+            balance_sheet: vec![cash, balance("Loan", AccountType::Liability, year_end)],
+            income_statement: vec![income("Sales", vec![constraint("2023-01:2023-12", 1200.0)])],
+        }
+    }
+
+    fn codes(diagnostics: &[Diagnostic]) -> Vec<DiagnosticCode> {
+        diagnostics.iter().map(|d| d.code).collect()
+    }
+
+    #[test]
+    fn test_clean_config_has_no_diagnostics() {
+        assert!(validate(&clean_config()).is_empty());
+    }
+
+    #[test]
+    fn test_reports_every_problem_with_paths() {
+        let mut config = clean_config();
+        config.balance_sheet[0].is_balancing_account = false;
+        config.balance_sheet[1].snapshots[0].date = NaiveDate::from_ymd_opt(2023, 12, 15).unwrap();
+        config.balance_sheet[1].snapshots[0].source = None;
+        config.balance_sheet[1].account_type = AccountType::OperatingExpense;
+        config.income_statement.push(income("Loan", vec![]));
+        config.income_statement[0].seasonality_profile = SeasonalityProfileId::Custom(vec![0.5; 3]);
+
+        let diagnostics = validate(&config);
+        let found = codes(&diagnostics);
+        assert!(found.contains(&DiagnosticCode::DuplicateName));
+        assert!(found.contains(&DiagnosticCode::WrongStatement));
+        assert!(found.contains(&DiagnosticCode::InvalidSeasonalityWeights));
+        assert!(found.contains(&DiagnosticCode::MissingSource));
+        assert!(found.contains(&DiagnosticCode::NoBalancingAccount));
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+
+        let month_end = diagnostics
+            .iter()
+            .find(|d| d.code == DiagnosticCode::SnapshotNotMonthEnd)
+            .unwrap();
+        assert_eq!(month_end.path, "balance_sheet[1].snapshots[0].date");
+        assert_eq!(month_end.account.as_deref(), Some("Loan"));
+        assert_eq!(month_end.suggestion.as_deref(), Some("Use 2023-12-31"));
+
+        let balancing = diagnostics
+            .iter()
+            .find(|d| d.code == DiagnosticCode::NoBalancingAccount)
+            .unwrap();
+        assert_eq!(
+            balancing.suggestion.as_deref(),
+            Some("Set is_balancing_account on 'Cash'")
+        );
+    }
+
+    #[test]
+    fn test_multiple_balancing_accounts() {
+        let mut config = clean_config();
+        config.balance_sheet[1].is_balancing_account = true;
+        let diagnostics = validate(&config);
+        assert_eq!(
+            codes(&diagnostics),
+            [DiagnosticCode::MultipleBalancingAccounts]
+        );
+        assert_eq!(diagnostics[0].path, "balance_sheet[1].is_balancing_account");
+    }
+
+    #[test]
+    fn test_unsatisfiable_constraints() {
+        let mut config = clean_config();
+        config.income_statement[0].constraints = vec![
+            constraint("2023-01", 500.0),
+            constraint("2023-02", 500.0),
+            constraint("2023-01:2023-02", 100.0),
+        ];
+        let diagnostics = validate(&config);
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics.iter().any(|d| {
+            d.code == DiagnosticCode::UnsatisfiableConstraint && d.severity == Severity::Error
+        }));
+        assert_eq!(diagnostics[0].account.as_deref(), Some("Sales"));
+    }
+
+    #[test]
+    fn test_rounding_gap_against_annual_total_only_warns() {
+        let mut config = clean_config();
+        let mut constraints: Vec<_> = (1..=12)
+            .map(|month| constraint(&format!("2023-{:02}", month), 10_000.40))
+            .collect();
+        constraints.push(constraint("2023-01:2023-12", 120_000.0));
+        config.income_statement[0].constraints = constraints;
+
+        let diagnostics = validate(&config);
+        assert!(!diagnostics.is_empty());
+        assert!(diagnostics.iter().all(|d| {
+            d.code == DiagnosticCode::UnsatisfiableConstraint && d.severity == Severity::Warning
+        }));
+    }
+
+    #[test]
+    fn test_unknown_driver() {
+        let mut config = clean_config();
+        config.balance_sheet[1].method = InterpolationMethod::DriverLinked {
+            drivers: vec!["Sales".to_string(), "Cost of Sales".to_string()],
+        };
+        let diagnostics = validate(&config);
+        assert_eq!(codes(&diagnostics), [DiagnosticCode::UnknownDriver]);
+        assert_eq!(diagnostics[0].path, "balance_sheet[1].method.drivers[1]");
+    }
+
+    #[test]
+    fn test_learned_seasonality_without_a_complete_year_warns() {
+        let mut config = clean_config();
+        config.income_statement[0].seasonality_profile = SeasonalityProfileId::Learned;
+        let diagnostics = validate(&config);
+        assert_eq!(
+            codes(&diagnostics),
+            [DiagnosticCode::LearnedSeasonalityFallback]
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_named_seasonality_profile_resolves_in_registry() {
+        let mut config = clean_config();
+        config.income_statement[0].seasonality_profile =
+            SeasonalityProfileId::Named("Ski Season".to_string());
+        let diagnostics = validate(&config);
+        assert_eq!(
+            codes(&diagnostics),
+            [DiagnosticCode::UnknownSeasonalityProfile]
+        );
+        assert!(diagnostics[0]
+            .suggestion
+            .as_deref()
+            .unwrap()
+            .contains("SouthernSummerHigh"));
+
+        let mut registry = SeasonalityRegistry::new();
+        registry.insert("Ski Season", vec![1.0 / 12.0; 12]).unwrap();
+        assert!(validate_with_registry(&config, &registry).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_period_is_reported_once() {
+        let mut config = clean_config();
+        config.income_statement[0].constraints = vec![constraint("2023-13", 100.0)];
+        let diagnostics = validate(&config);
+        assert_eq!(codes(&diagnostics), [DiagnosticCode::InvalidPeriod]);
+        assert_eq!(
+            diagnostics[0].path,
+            "income_statement[0].constraints[0].period"
+        );
+
+        let mut config = clean_config();
+        config.history_start = NaiveDate::from_ymd_opt(2024, 1, 1);
+        config.history_end = NaiveDate::from_ymd_opt(2023, 12, 31);
+        let diagnostics = validate(&config);
+        assert_eq!(codes(&diagnostics), [DiagnosticCode::InvalidPeriod]);
+        assert_eq!(diagnostics[0].path, "history_end");
+    }
+}
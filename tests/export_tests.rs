@@ -0,0 +1,191 @@
+use chrono::NaiveDate;
+use financial_history_builder::*;
+use std::collections::BTreeMap;
+
+fn date(y: i32, m: u32, d: u32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(y, m, d).unwrap()
+}
+
+fn point(value: f64, origin: DataOrigin, document: Option<&str>, logic: &str) -> MonthlyDataPoint {
+    MonthlyDataPoint {
+        value,
+        origin,
+        source: document.map(|doc| SourceMetadata {
+            document_name: doc.to_string(),
+            original_text: Some("Balance, per statement".to_string()),
+        }),
+        override_lineage: None,
+        derivation: DerivationDetails {
+            original_period_value: None,
+            period_start: None,
+            period_end: None,
+            logic: logic.to_string(),
+            balancing_strategy: None,
+        },
+    }
+}
+
+fn fixture() -> (FinancialHistoryConfig, BTreeMap<String, DenseSeries>) {
+    let balance = |name: &str, account_type: AccountType| BalanceSheetAccount {
+        name: name.to_string(),
+        category: None,
+        account_type,
+        method: InterpolationMethod::Linear,
+        extrapolation: ExtrapolationPolicy::Hold,
+        snapshots: vec![],
+        is_balancing_account: false,
+        noise_factor: 0.0,
+    };
+    let income = |name: &str, account_type: AccountType| IncomeStatementAccount {
+        name: name.to_string(),
+        account_type,
+        seasonality_profile: SeasonalityProfileId::Flat,
+        constraints: vec![],
+        noise_factor: 0.0,
+    };
+
+    let config = FinancialHistoryConfig {
+        organization_name: "Export Co".to_string(),
+        fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
+        balance_sheet: vec![
+            balance("Cash", AccountType::Asset),
+            balance("Debtors", AccountType::Asset),
+            balance("Bank Loan", AccountType::Liability),
+        ],
+        income_statement: vec![
+            income("Sales", AccountType::Revenue),
+            income("Rent", AccountType::OperatingExpense),
+        ],
+    };
+
+    let months = [date(2023, 1, 31), date(2023, 2, 28), date(2023, 3, 31)];
+    let series = |values: [(f64, DataOrigin, Option<&str>, &str); 3]| -> DenseSeries {
+        months
+            .iter()
+            .zip(values)
+            .map(|(d, (v, o, doc, logic))| (*d, point(v, o, doc, logic)))
+            .collect()
+    };
+
+    let mut dense = BTreeMap::new();
+    dense.insert(
+        "Cash".to_string(),
+        series([
+            (12500.0, DataOrigin::Anchor, Some("0"), "Anchor"),
+            (-1520.256, DataOrigin::BalancingPlug, None, "Plug"),
+            (18000.0, DataOrigin::Anchor, Some("0"), "Anchor"),
+        ]),
+    );
+    dense.insert(
+        "Debtors".to_string(),
+        series([
+            (4000.0, DataOrigin::Anchor, Some("1"), "Anchor"),
+            (4500.0, DataOrigin::Interpolated, None, "Linear"),
+            (5000.0, DataOrigin::Anchor, Some("1"), "Anchor"),
+        ]),
+    );
+    dense.insert(
+        "Bank Loan".to_string(),
+        series([
+            (10000.0, DataOrigin::Anchor, Some("0"), "Anchor"),
+            (9000.0, DataOrigin::Interpolated, None, "Linear"),
+            (8000.0, DataOrigin::Anchor, Some("0"), "Anchor"),
+        ]),
+    );
+    dense.insert(
+        "Sales".to_string(),
+        series([
+            (3000.0, DataOrigin::Allocated, None, "Q1, \"flat\""),
+            (3000.0, DataOrigin::Allocated, None, "Q1, \"flat\""),
+            (3000.0, DataOrigin::Allocated, None, "Q1, \"flat\""),
+        ]),
+    );
+    dense.insert(
+        "Rent".to_string(),
+        series([
+            (1000.0, DataOrigin::Allocated, None, "Allocated"),
+            (1000.0, DataOrigin::Allocated, None, "Allocated"),
+            (1000.0, DataOrigin::Allocated, None, "Allocated"),
+        ]),
+    );
+    let mut equity = series([
+        (6500.0, DataOrigin::BalancingPlug, None, "Plug"),
+        (-6020.256, DataOrigin::BalancingPlug, None, "Plug"),
+        (15000.0, DataOrigin::BalancingPlug, None, "Plug"),
+    ]);
+    // Generated accounts may be missing months; the wide layout leaves those cells empty.
+    equity.remove(&date(2023, 2, 28));
+    dense.insert(SUSPENSE_ACCOUNT_NAME.to_string(), equity);
+
+    (config, dense)
+}
+
+fn golden(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("missing golden file {path}: {e}"))
+}
+
+#[test]
+fn test_wide_csv_matches_golden() {
+    let (config, dense) = fixture();
+    let csv = to_wide_csv(&config, &dense, &ExportOptions::new());
+    assert_eq!(csv, golden("export_wide.csv"));
+}
+
+#[test]
+fn test_wide_csv_with_subtotals_and_formatting_matches_golden() {
+    let (config, dense) = fixture();
+    let options = ExportOptions::new()
+        .with_scope(ExportScope::BalanceSheet)
+        .with_subtotals(true)
+        .with_decimals(0)
+        .with_thousands_separator(',')
+        .with_negative_parentheses(true)
+        .with_date_format("%b %Y")
+        .unwrap();
+    let csv = to_wide_csv(&config, &dense, &options);
+    assert_eq!(csv, golden("export_wide_subtotals.csv"));
+}
+
+#[test]
+fn test_long_csv_matches_golden() {
+    let (config, dense) = fixture();
+    let csv = to_long_csv(&config, &dense, &ExportOptions::new());
+    assert_eq!(csv, golden("export_long.csv"));
+
+    let rows = csv.lines().count() - 1;
+    let points: usize = dense.values().map(|s| s.len()).sum();
+    assert_eq!(rows, points);
+}
+
+#[test]
+fn test_write_wide_csv_round_trips_through_csv_reader() {
+    let (config, dense) = fixture();
+    let path = std::env::temp_dir().join(format!(
+        "fhb_export_wide_test_{}_{}.csv",
+        std::process::id(),
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_nanos()
+    ));
+    let options = ExportOptions::new().with_thousands_separator(',');
+    write_wide_csv(&path, &config, &dense, &options).unwrap();
+
+    let mut reader = csv::Reader::from_path(&path).unwrap();
+    let headers = reader.headers().unwrap().clone();
+    assert_eq!(&headers[0], "Section");
+    assert_eq!(&headers[2], "2023-01-31");
+
+    let cash = reader
+        .records()
+        .map(|r| r.unwrap())
+        .find(|r| &r[1] == "Cash")
+        .unwrap();
+    assert_eq!(&cash[2], "12,500.00");
+    assert_eq!(&cash[3], "-1,520.26");
+
+    std::fs::remove_file(path).ok();
+}
@@ -58,27 +58,33 @@ fn test_comprehensive_retail_business() {
     let config = FinancialHistoryConfig {
         organization_name: "Retail Haven Inc".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![
             BalanceSheetAccount {
                 name: "Cash at Bank".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Curve,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 150_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 180_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 250_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: true,
@@ -89,21 +95,25 @@ fn test_comprehensive_retail_business() {
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 200_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 240_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 300_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -114,21 +124,25 @@ fn test_comprehensive_retail_business() {
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 80_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 130_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -139,21 +153,25 @@ fn test_comprehensive_retail_business() {
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Step,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 95_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 90_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -164,21 +182,25 @@ fn test_comprehensive_retail_business() {
                 category: None,
                 account_type: AccountType::Liability,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 60_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 75_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 95_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -189,21 +211,25 @@ fn test_comprehensive_retail_business() {
                 category: None,
                 account_type: AccountType::Liability,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 200_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 180_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 160_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -214,21 +240,25 @@ fn test_comprehensive_retail_business() {
                 category: None,
                 account_type: AccountType::Equity,
                 method: InterpolationMethod::Step,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 250_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 250_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 250_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -245,11 +275,13 @@ fn test_comprehensive_retail_business() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 2_400_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 3_000_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.05,
@@ -263,11 +295,13 @@ fn test_comprehensive_retail_business() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 1_440_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 1_800_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.04,
@@ -281,11 +315,13 @@ fn test_comprehensive_retail_business() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 120_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 132_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.0,
@@ -299,11 +335,13 @@ fn test_comprehensive_retail_business() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 480_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 540_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.02,
@@ -317,11 +355,13 @@ fn test_comprehensive_retail_business() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 144_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 180_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.08,
@@ -354,27 +394,33 @@ fn test_saas_startup() {
     let config = FinancialHistoryConfig {
         organization_name: "CloudTech SaaS Inc".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![
             BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 500_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 350_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 200_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: true,
@@ -385,21 +431,25 @@ fn test_saas_startup() {
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 50_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 75_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 125_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -410,21 +460,25 @@ fn test_saas_startup() {
                 category: None,
                 account_type: AccountType::Liability,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 40_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 55_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 75_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -435,21 +489,25 @@ fn test_saas_startup() {
                 category: None,
                 account_type: AccountType::Liability,
                 method: InterpolationMethod::Curve,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 12, 31).unwrap(),
                         value: 150_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 250_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -460,16 +518,19 @@ fn test_saas_startup() {
                 category: None,
                 account_type: AccountType::Equity,
                 method: InterpolationMethod::Step,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2022, 1, 31).unwrap(),
                         value: 1_000_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 1_500_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -486,11 +547,13 @@ fn test_saas_startup() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 600_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 1_200_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.03,
@@ -504,11 +567,13 @@ fn test_saas_startup() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 150_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 300_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.06,
@@ -522,11 +587,13 @@ fn test_saas_startup() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 120_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 240_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.02,
@@ -540,11 +607,13 @@ fn test_saas_startup() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 720_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 960_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.01,
@@ -558,11 +627,13 @@ fn test_saas_startup() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 300_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 480_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.07,
@@ -576,11 +647,13 @@ fn test_saas_startup() {
                         period: period_range(2022, 1, 2022, 12),
                         value: 60_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     PeriodConstraint {
                         period: period_range(2023, 1, 2023, 12),
                         value: 72_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 noise_factor: 0.03,
@@ -603,27 +676,33 @@ fn test_hospitality_business() {
     let config = FinancialHistoryConfig {
         organization_name: "Seaside Resort Ltd".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![
             BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Curve,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 200_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 8, 31).unwrap(),
                         value: 400_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 280_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: true,
@@ -634,16 +713,19 @@ fn test_hospitality_business() {
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 2_000_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 1_900_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -654,16 +736,19 @@ fn test_hospitality_business() {
                 category: None,
                 account_type: AccountType::Liability,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 80_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -674,16 +759,19 @@ fn test_hospitality_business() {
                 category: None,
                 account_type: AccountType::Liability,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 1_500_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 1_450_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -694,10 +782,12 @@ fn test_hospitality_business() {
                 category: None,
                 account_type: AccountType::Equity,
                 method: InterpolationMethod::Step,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                     value: 600_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 is_balancing_account: false,
                 noise_factor: 0.0,
@@ -712,6 +802,7 @@ fn test_hospitality_business() {
                     period: period_range(2023, 1, 2023, 12),
                     value: 1_800_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.06,
             },
@@ -723,6 +814,7 @@ fn test_hospitality_business() {
                     period: period_range(2023, 1, 2023, 12),
                     value: 600_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.07,
             },
@@ -734,6 +826,7 @@ fn test_hospitality_business() {
                     period: period_range(2023, 1, 2023, 12),
                     value: 210_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.04,
             },
@@ -745,6 +838,7 @@ fn test_hospitality_business() {
                     period: period_range(2023, 1, 2023, 12),
                     value: 720_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.03,
             },
@@ -756,6 +850,7 @@ fn test_hospitality_business() {
                     period: period_range(2023, 1, 2023, 12),
                     value: 120_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.05,
             },
@@ -767,6 +862,7 @@ fn test_hospitality_business() {
                     period: period_range(2023, 1, 2023, 12),
                     value: 240_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 noise_factor: 0.0,
             },
@@ -804,16 +900,20 @@ fn test_designated_balancing_account() {
     let config = FinancialHistoryConfig {
         organization_name: "Tech Startup Inc".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![
             BalanceSheetAccount {
                 name: "Cash at Bank".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                     value: 100_000.0,
                     source: None,
+                    override_lineage: None,
                 }],
                 is_balancing_account: true,
                 noise_factor: 0.0,
@@ -823,16 +923,19 @@ fn test_designated_balancing_account() {
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 50_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 75_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -843,16 +946,19 @@ fn test_designated_balancing_account() {
                 category: None,
                 account_type: AccountType::Liability,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 30_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 40_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -863,16 +969,19 @@ fn test_designated_balancing_account() {
                 category: None,
                 account_type: AccountType::Equity,
                 method: InterpolationMethod::Step,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -922,22 +1031,27 @@ fn test_retained_earnings_integrity_check() {
     let config = FinancialHistoryConfig {
         organization_name: "Integrity Check Co".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![
             BalanceSheetAccount {
                 name: "Cash".to_string(),
                 category: None,
                 account_type: AccountType::Asset,
                 method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 2, 28).unwrap(),
                         value: 100_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: true,
@@ -948,16 +1062,19 @@ fn test_retained_earnings_integrity_check() {
                 category: None,
                 account_type: AccountType::Equity,
                 method: InterpolationMethod::Step,
+                extrapolation: ExtrapolationPolicy::Hold,
                 snapshots: vec![
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                         value: 500_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                     BalanceSheetSnapshot {
                         date: NaiveDate::from_ymd_opt(2023, 2, 28).unwrap(),
                         value: 500_000.0,
                         source: None,
+                        override_lineage: None,
                     },
                 ],
                 is_balancing_account: false,
@@ -972,6 +1089,7 @@ fn test_retained_earnings_integrity_check() {
                 period: period_range(2023, 2, 2023, 2),
                 value: 100_000.0,
                 source: None,
+                override_lineage: None,
             }],
             noise_factor: 0.0,
         }],
@@ -994,21 +1112,26 @@ fn test_hierarchical_constraints() {
     let config = FinancialHistoryConfig {
         organization_name: "Mixed Mode Corp".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![BalanceSheetAccount {
             name: "Cash".to_string(),
             category: None,
             account_type: AccountType::Asset,
             method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
             snapshots: vec![
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             is_balancing_account: true,
@@ -1023,16 +1146,19 @@ fn test_hierarchical_constraints() {
                     period: period_range(2023, 1, 2023, 1),
                     value: 10_000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: period_range(2023, 2, 2023, 2),
                     value: 0.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: period_range(2023, 1, 2023, 3),
                     value: 25_000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             noise_factor: 0.0,
@@ -1076,21 +1202,26 @@ fn test_quarterly_constraints() {
     let config = FinancialHistoryConfig {
         organization_name: "Quarterly Corp".to_string(),
         fiscal_year_end_month: 12,
+        history_start: None,
+        history_end: None,
         balance_sheet: vec![BalanceSheetAccount {
             name: "Cash".to_string(),
             category: None,
             account_type: AccountType::Asset,
             method: InterpolationMethod::Linear,
+            extrapolation: ExtrapolationPolicy::Hold,
             snapshots: vec![
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 1, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 BalanceSheetSnapshot {
                     date: NaiveDate::from_ymd_opt(2023, 12, 31).unwrap(),
                     value: 100000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             is_balancing_account: true,
@@ -1105,11 +1236,13 @@ fn test_quarterly_constraints() {
                     period: period_range(2023, 1, 2023, 6),
                     value: 50_000.0,
                     source: None,
+                    override_lineage: None,
                 },
                 PeriodConstraint {
                     period: period_range(2023, 7, 2023, 9),
                     value: 15_000.0,
                     source: None,
+                    override_lineage: None,
                 },
             ],
             noise_factor: 0.0,
@@ -1150,3 +1283,167 @@ fn test_quarterly_constraints() {
 
     println!("✓ Quarterly constraints test passed");
 }
+
+#[test]
+fn test_seeded_processing_is_bit_identical() {
+    let config = FinancialHistoryConfig {
+        organization_name: "Audit Replay Ltd".to_string(),
+        fiscal_year_end_month: 6,
+        history_start: None,
+        history_end: None,
+        balance_sheet: vec![
+            BalanceSheetAccount {
+                name: "Cash".to_string(),
+                category: None,
+                account_type: AccountType::Asset,
+                method: InterpolationMethod::Curve,
+                extrapolation: ExtrapolationPolicy::Hold,
+                snapshots: vec![
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2022, 6, 30).unwrap(),
+                        value: 80_000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2023, 6, 30).unwrap(),
+                        value: 95_000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                ],
+                is_balancing_account: true,
+                noise_factor: 0.03,
+            },
+            BalanceSheetAccount {
+                name: "Accounts Receivable".to_string(),
+                category: None,
+                account_type: AccountType::Asset,
+                method: InterpolationMethod::Linear,
+                extrapolation: ExtrapolationPolicy::Hold,
+                snapshots: vec![
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2022, 6, 30).unwrap(),
+                        value: 20_000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                    BalanceSheetSnapshot {
+                        date: NaiveDate::from_ymd_opt(2023, 6, 30).unwrap(),
+                        value: 26_000.0,
+                        source: None,
+                        override_lineage: None,
+                    },
+                ],
+                is_balancing_account: false,
+                noise_factor: 0.05,
+            },
+        ],
+        income_statement: vec![IncomeStatementAccount {
+            name: "Sales".to_string(),
+            account_type: AccountType::Revenue,
+            seasonality_profile: SeasonalityProfileId::RetailPeak,
+            constraints: vec![PeriodConstraint {
+                period: period_range(2022, 7, 2023, 6),
+                value: 480_000.0,
+                source: None,
+                override_lineage: None,
+            }],
+            noise_factor: 0.08,
+        }],
+    };
+
+    let first = process_financial_history_with_seed(&config, 2024).unwrap();
+    let second = process_financial_history_with_seed(&config, 2024).unwrap();
+
+    assert_eq!(first.len(), second.len());
+    for (name, series) in &first {
+        let replay = second.get(name).unwrap();
+        assert_eq!(series.len(), replay.len());
+        for (date, point) in series {
+            assert_eq!(
+                point.value.to_bits(),
+                replay.get(date).unwrap().value.to_bits(),
+                "{} on {} differs between seeded runs",
+                name,
+                date
+            );
+        }
+    }
+}
+
+#[test]
+fn test_history_window_extrapolates_late_accounts() {
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let account = |name: &str,
+                   account_type: AccountType,
+                   extrapolation: ExtrapolationPolicy,
+                   snapshots: &[(NaiveDate, f64)]| BalanceSheetAccount {
+        name: name.to_string(),
+        category: None,
+        account_type,
+        method: InterpolationMethod::Linear,
+        extrapolation,
+        snapshots: snapshots
+            .iter()
+            .map(|&(date, value)| BalanceSheetSnapshot {
+                date,
+                value,
+                source: None,
+                override_lineage: None,
+            })
+            .collect(),
+        is_balancing_account: false,
+        noise_factor: 0.0,
+    };
+
+    let mut cash = account(
+        "Cash",
+        AccountType::Asset,
+        ExtrapolationPolicy::Hold,
+        &[
+            (date(2022, 12, 31), 120_000.0),
+            (date(2023, 12, 31), 150_000.0),
+        ],
+    );
+    cash.is_balancing_account = true;
+    let config = FinancialHistoryConfig {
+        organization_name: "Late Loan Ltd".to_string(),
+        fiscal_year_end_month: 12,
+        history_start: Some(date(2022, 1, 1)),
+        history_end: Some(date(2023, 12, 31)),
+        balance_sheet: vec![
+            cash,
+            account(
+                "Bank Loan",
+                AccountType::Liability,
+                ExtrapolationPolicy::Zero,
+                &[
+                    (date(2023, 6, 30), 50_000.0),
+                    (date(2023, 12, 31), 45_000.0),
+                ],
+            ),
+            account(
+                "Share Capital",
+                AccountType::Equity,
+                ExtrapolationPolicy::Hold,
+                &[(date(2023, 12, 31), 100_000.0)],
+            ),
+        ],
+        income_statement: vec![],
+    };
+
+    let dense = process_financial_history(&config).unwrap();
+
+    for name in ["Bank Loan", "Share Capital"] {
+        assert_eq!(dense[name].len(), 24, "{} should span the window", name);
+    }
+    let loan = &dense["Bank Loan"][&date(2022, 9, 30)];
+    assert_eq!(loan.origin, DataOrigin::Extrapolated);
+    assert_eq!(loan.value, 0.0);
+    let capital = &dense["Share Capital"][&date(2022, 1, 31)];
+    assert_eq!(capital.origin, DataOrigin::Extrapolated);
+    assert_eq!(capital.value, 100_000.0);
+
+    assert!(verify_accounting_equation(&config, &dense, 0.01).is_ok());
+}
@@ -0,0 +1,237 @@
+use chrono::NaiveDate;
+use financial_history_builder::*;
+
+fn fixture(name: &str) -> String {
+    format!("{}/tests/fixtures/{}", env!("CARGO_MANIFEST_DIR"), name)
+}
+
+fn income<'a>(config: &'a FinancialHistoryConfig, name: &str) -> &'a IncomeStatementAccount {
+    config
+        .income_statement
+        .iter()
+        .find(|a| a.name == name)
+        .unwrap_or_else(|| panic!("missing income statement account {name}"))
+}
+
+fn balance<'a>(config: &'a FinancialHistoryConfig, name: &str) -> &'a BalanceSheetAccount {
+    config
+        .balance_sheet
+        .iter()
+        .find(|a| a.name == name)
+        .unwrap_or_else(|| panic!("missing balance sheet account {name}"))
+}
+
+#[test]
+fn test_xero_reports_build_a_balanced_config() {
+    let layout = ReportLayout::xero();
+    let pl = import_report_csv(fixture("xero_profit_and_loss.csv"), &layout).unwrap();
+    let bs = import_report_csv(fixture("xero_balance_sheet.csv"), &layout).unwrap();
+    assert!(pl.errors.is_empty() && bs.errors.is_empty());
+
+    let mut config = reports_to_config(&[pl, bs], 12);
+    assert_eq!(config.organization_name, "Kiwi Plumbing Limited");
+    assert_eq!(config.income_statement.len(), 7);
+    assert_eq!(config.balance_sheet.len(), 9);
+
+    assert_eq!(income(&config, "Sales").account_type, AccountType::Revenue);
+    assert_eq!(
+        income(&config, "Purchases").account_type,
+        AccountType::CostOfSales
+    );
+    assert_eq!(
+        income(&config, "Interest Income").account_type,
+        AccountType::OtherIncome
+    );
+    assert_eq!(
+        income(&config, "Depreciation").account_type,
+        AccountType::Depreciation
+    );
+    assert_eq!(
+        income(&config, "Interest Expense").account_type,
+        AccountType::Interest
+    );
+    assert_eq!(
+        income(&config, "Rent").account_type,
+        AccountType::OperatingExpense
+    );
+
+    let sales = income(&config, "Sales");
+    let periods: Vec<(&str, f64)> = sales
+        .constraints
+        .iter()
+        .map(|c| (c.period.as_str(), c.value))
+        .collect();
+    assert_eq!(
+        periods,
+        vec![
+            ("2023-01", 20_000.0),
+            ("2023-02", 22_000.0),
+            ("2023-03", 25_000.0)
+        ]
+    );
+
+    let bank = balance(&config, "Business Cheque Account");
+    assert_eq!(bank.category.as_deref(), Some("Bank"));
+    assert_eq!(
+        bank.snapshots[1].date,
+        NaiveDate::from_ymd_opt(2023, 2, 28).unwrap()
+    );
+    let accumulated = balance(
+        &config,
+        "Less Accumulated Depreciation on Plant and Equipment",
+    );
+    assert_eq!(accumulated.category.as_deref(), Some("Fixed Assets"));
+    assert_eq!(accumulated.snapshots[0].value, -5_500.0);
+    assert_eq!(
+        balance(&config, "Loan").category.as_deref(),
+        Some("Non-current Liabilities")
+    );
+    assert_eq!(
+        balance(&config, "Retained Earnings").account_type,
+        AccountType::Equity
+    );
+
+    config
+        .balance_sheet
+        .iter_mut()
+        .find(|a| a.name == "Business Cheque Account")
+        .unwrap()
+        .is_balancing_account = true;
+    let dense = process_financial_history(&config).unwrap();
+    assert!(verify_accounting_equation(&config, &dense, 0.01).is_ok());
+}
+
+#[test]
+fn test_myob_reports_strip_account_codes() {
+    let layout = ReportLayout::myob();
+    let pl = import_report_csv(fixture("myob_profit_and_loss.csv"), &layout).unwrap();
+    let bs = import_report_csv(fixture("myob_balance_sheet.csv"), &layout).unwrap();
+    let config = reports_to_config(&[pl, bs], 6);
+
+    assert_eq!(config.organization_name, "Aussie Hardware Pty Ltd");
+    let names: Vec<&str> = config
+        .income_statement
+        .iter()
+        .map(|a| a.name.as_str())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "Sales",
+            "Purchases",
+            "Advertising",
+            "Rent",
+            "Wages",
+            "Depreciation",
+            "Interest Received",
+            "Income Tax Expense",
+        ]
+    );
+
+    let sales = income(&config, "Sales");
+    assert_eq!(
+        sales.constraints.len(),
+        3,
+        "the Total column is not a month"
+    );
+    assert_eq!(sales.constraints[0].period, "2023-07");
+    let source = sales.constraints[0].source.as_ref().unwrap();
+    assert_eq!(source.document_name, "myob_profit_and_loss.csv");
+    assert_eq!(source.original_text.as_deref(), Some("4-1000 Sales"));
+
+    assert_eq!(
+        income(&config, "Income Tax Expense").account_type,
+        AccountType::IncomeTax
+    );
+    assert_eq!(
+        income(&config, "Depreciation").account_type,
+        AccountType::Depreciation
+    );
+    assert_eq!(
+        income(&config, "Interest Received").constraints[2].value,
+        0.0
+    );
+
+    let fittings = balance(&config, "Shop Fittings");
+    assert_eq!(fittings.category.as_deref(), Some("Property & Equipment"));
+    assert_eq!(
+        fittings.snapshots[2].date,
+        NaiveDate::from_ymd_opt(2023, 9, 30).unwrap()
+    );
+    assert_eq!(
+        balance(&config, "GST Collected").account_type,
+        AccountType::Liability
+    );
+}
+
+#[test]
+fn test_quickbooks_reports_keep_net_income_equity_line() {
+    let layout = ReportLayout::quickbooks();
+    let pl = import_report_csv(fixture("quickbooks_profit_and_loss.csv"), &layout).unwrap();
+    let bs = import_report_csv(fixture("quickbooks_balance_sheet.csv"), &layout).unwrap();
+    let config = reports_to_config(&[pl, bs], 12);
+
+    assert_eq!(config.organization_name, "Maple Bakery LLC");
+    assert!(config
+        .income_statement
+        .iter()
+        .all(|a| a.name != "Net Income"));
+    assert_eq!(
+        income(&config, "Cost of Goods Sold").account_type,
+        AccountType::CostOfSales
+    );
+    assert_eq!(
+        income(&config, "Interest Paid").account_type,
+        AccountType::Interest
+    );
+    assert_eq!(
+        income(&config, "Sales of Product Income").constraints[0].value,
+        9_000.0
+    );
+
+    let utilities = income(&config, "Utilities");
+    assert_eq!(
+        utilities.constraints.len(),
+        2,
+        "blank January is not a constraint"
+    );
+
+    let net_income = balance(&config, "Net Income");
+    assert_eq!(net_income.account_type, AccountType::Equity);
+    assert_eq!(net_income.snapshots[2].value, 6_300.0);
+    assert_eq!(
+        balance(&config, "Accounts Receivable (A/R)")
+            .category
+            .as_deref(),
+        Some("Current Assets")
+    );
+    assert_eq!(
+        balance(&config, "Accounts Payable (A/P)").account_type,
+        AccountType::Liability
+    );
+}
+
+#[test]
+fn test_report_rows_with_bad_amounts_are_reported() {
+    let csv = "\
+Account,Jan 2023,Feb 2023
+Income,,
+Sales,100,n/a
+Consulting,50,60
+";
+    let import = parse_report_csv(csv.as_bytes(), &ReportLayout::xero(), "inline").unwrap();
+    assert_eq!(import.income_statement.len(), 1);
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].line, 3);
+    assert!(import.errors[0].message.contains("invalid amount 'n/a'"));
+}
+
+#[test]
+fn test_report_rows_that_are_not_utf8_are_reported() {
+    let mut csv = b"Account,Jan 2023\nIncome,\nSales,100\n".to_vec();
+    csv.extend_from_slice(b"Caf\xe9 Sales,40\n");
+    let import = parse_report_csv(csv.as_slice(), &ReportLayout::xero(), "inline").unwrap();
+    assert_eq!(import.income_statement.len(), 1);
+    assert_eq!(import.errors.len(), 1);
+    assert_eq!(import.errors[0].line, 4);
+}